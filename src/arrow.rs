@@ -0,0 +1,236 @@
+//! Arrow record batches and Parquet files of prices, enabled with the `arrow` feature.
+//!
+//! One row per price with typed columns, see `schema`:
+//! `region` (dictionary), `from` and `to` (timestamp in UTC), `date` (delivery date), `value` (decimal),
+//! `currency`, `currency_unit`, `power_unit`, `mtu_minutes` and `state` (`Final`, `Preliminary` or null when not known).
+//!
+//! Values are stored as `Decimal128` with `VALUE_SCALE` decimals, values with more decimals
+//! (e.g. after currency conversion) are rounded half away from zero.
+//! Parquet files are written uncompressed.
+
+use std::fs::File;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array,
+    ArrayAccessor,
+    ArrayRef,
+    AsArray,
+    Date32Array,
+    Decimal128Array,
+    DictionaryArray,
+    Int32Array,
+    RecordBatch,
+    StringArray,
+    TimestampMillisecondArray,
+};
+use arrow::datatypes::{
+    DataType,
+    Date32Type,
+    Decimal128Type,
+    Field,
+    Int32Type,
+    Schema,
+    SchemaRef,
+    TimeUnit,
+    TimestampMillisecondType,
+};
+use chrono::{DateTime, Utc};
+use parquet::arrow::{ArrowWriter, arrow_reader::ParquetRecordBatchReaderBuilder};
+
+use crate::elspot::{Price, PriceExtractor, dataportal_dayaheadprices::states::State};
+use crate::error::{
+    ElspotError,
+    ElspotResult,
+};
+use crate::price_value::PriceValue;
+use crate::region::Region;
+use crate::units::{self, CurrencyCode, CurrencyUnit};
+
+pub const VALUE_PRECISION: u8 = 38;
+pub const VALUE_SCALE: i8 = 12;
+
+/// A price read back from a record batch, with the state of the dataset it was written from.
+#[derive(Clone, Debug)]
+pub struct ArchivedPrice {
+    pub price: Price,
+    pub state: Option<State>,
+}
+
+pub fn schema() -> SchemaRef {
+    let timestamp = DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()));
+
+    Arc::new(Schema::new(vec![
+        Field::new("region", DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)), false),
+        Field::new("from", timestamp.clone(), false),
+        Field::new("to", timestamp, false),
+        Field::new("date", DataType::Date32, false),
+        Field::new("value", DataType::Decimal128(VALUE_PRECISION, VALUE_SCALE), false),
+        Field::new("currency", DataType::Utf8, false),
+        Field::new("currency_unit", DataType::Utf8, false),
+        Field::new("power_unit", DataType::Utf8, false),
+        Field::new("mtu_minutes", DataType::Int32, false),
+        Field::new("state", DataType::Utf8, true),
+    ]))
+}
+
+/// One row per price in `prices` (any mix of regions), with `state` for all of them.
+pub fn record_batch(prices: &[Price], state: Option<State>) -> ElspotResult<RecordBatch> {
+    let mut values = Vec::with_capacity(prices.len());
+    for p in prices {
+        values.push(decimal(p.value).ok_or_else(|| ElspotError::ArrowInvalidValue { column: "value", value: p.value.to_string() })?);
+    }
+    let values = Decimal128Array::from(values)
+        .with_precision_and_scale(VALUE_PRECISION, VALUE_SCALE)
+        .map_err(ElspotError::Arrow)?;
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(prices.iter().map(|p| p.region.as_str()).collect::<DictionaryArray<Int32Type>>()),
+        Arc::new(TimestampMillisecondArray::from_iter_values(prices.iter().map(|p| p.from.timestamp_millis())).with_timezone("UTC")),
+        Arc::new(TimestampMillisecondArray::from_iter_values(prices.iter().map(|p| p.to.timestamp_millis())).with_timezone("UTC")),
+        Arc::new(Date32Array::from_iter_values(prices.iter().map(|p| Date32Type::from_naive_date(p.date)))),
+        Arc::new(values),
+        Arc::new(StringArray::from_iter_values(prices.iter().map(|p| p.currency_unit.code().as_str()))),
+        Arc::new(StringArray::from_iter_values(prices.iter().map(|p| p.currency_unit.as_str()))),
+        Arc::new(StringArray::from_iter_values(prices.iter().map(|p| p.power_unit.as_str()))),
+        Arc::new(Int32Array::from_iter_values(prices.iter().map(|p| p.market_time_unit as i32))),
+        Arc::new(prices.iter().map(|_| state.as_ref().map(State::as_str)).collect::<StringArray>()),
+    ];
+
+    RecordBatch::try_new(schema(), columns).map_err(ElspotError::Arrow)
+}
+
+/// All regions in `data` (see `extract_prices_all_regions`) in one batch, with the state of the dataset.
+pub fn record_batch_all_regions(data: &dyn PriceExtractor) -> ElspotResult<RecordBatch> {
+    let prices: Vec<Price> = data.extract_prices_all_regions()?.into_iter().flatten().collect();
+    let state = if data.is_final() {
+        Some(State::Final)
+    } else if data.is_preliminary() {
+        Some(State::Preliminary)
+    } else {
+        None
+    };
+
+    record_batch(&prices, state)
+}
+
+/// Rebuilds prices from a batch with the columns of `schema`, other columns are ignored.
+pub fn prices_from_record_batch(batch: &RecordBatch) -> ElspotResult<Vec<ArchivedPrice>> {
+    let regions = column(batch, "region")?
+        .as_dictionary_opt::<Int32Type>()
+        .and_then(|d| d.downcast_dict::<StringArray>())
+        .ok_or(ElspotError::ArrowInvalidColumn("region"))?;
+    let from = column(batch, "from")?.as_primitive_opt::<TimestampMillisecondType>().ok_or(ElspotError::ArrowInvalidColumn("from"))?;
+    let to = column(batch, "to")?.as_primitive_opt::<TimestampMillisecondType>().ok_or(ElspotError::ArrowInvalidColumn("to"))?;
+    let dates = column(batch, "date")?.as_primitive_opt::<Date32Type>().ok_or(ElspotError::ArrowInvalidColumn("date"))?;
+    let values = column(batch, "value")?.as_primitive_opt::<Decimal128Type>().ok_or(ElspotError::ArrowInvalidColumn("value"))?;
+    let scale = match values.data_type() {
+        DataType::Decimal128(_, scale) if *scale >= 0 => *scale as u32,
+        _ => return Err(ElspotError::ArrowInvalidColumn("value")),
+    };
+    let currencies = string_column(batch, "currency")?;
+    let currency_units = string_column(batch, "currency_unit")?;
+    let power_units = string_column(batch, "power_unit")?;
+    let states = string_column(batch, "state")?;
+
+    let mut prices = Vec::with_capacity(batch.num_rows());
+    for i in 0..batch.num_rows() {
+        let region: Region = regions.value(i).parse()?;
+        let from = timestamp(from.value(i)).ok_or_else(|| invalid("from", from.value(i)))?;
+        let to = timestamp(to.value(i)).ok_or_else(|| invalid("to", to.value(i)))?;
+        let date = dates.value_as_date(i).ok_or_else(|| invalid("date", dates.value(i)))?;
+        let value = PriceValue::new(values.value(i), scale).normalize();
+
+        let unit_error = |e| ElspotError::InvalidUnit { region: region.to_string(), date, source: e };
+        let currency: CurrencyCode = currencies.value(i).parse().map_err(unit_error)?;
+        let currency_unit = [CurrencyUnit::Full, CurrencyUnit::Fraction]
+            .into_iter()
+            .map(|unit| units::Currency::from_code(currency, unit))
+            .find(|c| c.as_str() == currency_units.value(i))
+            .ok_or_else(|| invalid("currency_unit", currency_units.value(i)))?;
+        let state = match states.is_null(i) {
+            true => None,
+            false => Some(parse_state(states.value(i))?),
+        };
+
+        let price = Price {
+            from,
+            to,
+            date,
+            region,
+            value,
+            currency_unit,
+            market_time_unit: units::Mtu::new(from, to).map_err(unit_error)?,
+            power_unit: units::Power::new(power_units.value(i)).map_err(unit_error)?,
+            exchange_rate: None,
+        };
+        prices.push(ArchivedPrice { price, state });
+    }
+
+    Ok(prices)
+}
+
+/// Writes `batches` (with the columns of `schema`) to a Parquet file at `path`.
+pub fn write_parquet(path: &str, batches: &[RecordBatch]) -> ElspotResult<()> {
+    let file = File::create(path).map_err(|e| ElspotError::IOError { path: path.to_string(), source: e })?;
+    let mut writer = ArrowWriter::try_new(file, schema(), None).map_err(ElspotError::Parquet)?;
+    for batch in batches {
+        writer.write(batch).map_err(ElspotError::Parquet)?;
+    }
+    writer.close().map_err(ElspotError::Parquet)?;
+
+    Ok(())
+}
+
+/// Reads all record batches from the Parquet file at `path`.
+pub fn read_parquet(path: &str) -> ElspotResult<Vec<RecordBatch>> {
+    let file = File::open(path).map_err(|e| ElspotError::IOError { path: path.to_string(), source: e })?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+        .and_then(|builder| builder.build())
+        .map_err(ElspotError::Parquet)?;
+
+    reader.map(|batch| batch.map_err(ElspotError::Arrow)).collect()
+}
+
+/// Same as `read_parquet`, as prices.
+pub fn prices_from_parquet(path: &str) -> ElspotResult<Vec<ArchivedPrice>> {
+    let mut prices = vec![];
+    for batch in read_parquet(path)? {
+        prices.extend(prices_from_record_batch(&batch)?);
+    }
+
+    Ok(prices)
+}
+
+/// The mantissa of `value` at `VALUE_SCALE` decimals, `None` if it does not fit in the precision.
+fn decimal(value: PriceValue) -> Option<i128> {
+    let scale = VALUE_SCALE as u32;
+    let value = value.round(scale);
+    let mantissa = value.mantissa().checked_mul(10_i128.checked_pow(scale.checked_sub(value.scale())?)?)?;
+
+    (mantissa.unsigned_abs() < 10_u128.pow(VALUE_PRECISION as u32)).then_some(mantissa)
+}
+
+fn column<'a>(batch: &'a RecordBatch, name: &'static str) -> ElspotResult<&'a ArrayRef> {
+    batch.column_by_name(name).ok_or(ElspotError::ArrowInvalidColumn(name))
+}
+
+fn string_column<'a>(batch: &'a RecordBatch, name: &'static str) -> ElspotResult<&'a StringArray> {
+    column(batch, name)?.as_string_opt::<i32>().ok_or(ElspotError::ArrowInvalidColumn(name))
+}
+
+fn timestamp(millis: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(millis)
+}
+
+fn parse_state(s: &str) -> ElspotResult<State> {
+    match s {
+        "Final" => Ok(State::Final),
+        "Preliminary" => Ok(State::Preliminary),
+        _ => Err(invalid("state", s)),
+    }
+}
+
+fn invalid(column: &'static str, value: impl ToString) -> ElspotError {
+    ElspotError::ArrowInvalidValue { column, value: value.to_string() }
+}
@@ -0,0 +1,105 @@
+//! Prometheus exporter for eb_nordpool (feature `exporter`).
+//!
+//! ```sh
+//! eb-nordpool-exporter --regions NO1,NO3
+//! eb-nordpool-exporter --currency NOK,EUR --regions NO1,SE3 --listen 0.0.0.0:9796 --interval 600 --kwh --fraction
+//! ```
+
+use std::net::TcpListener;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use clap::Parser;
+
+use eb_nordpool::{
+    clock::SystemClock,
+    elspot::{client::Client, dataportal_dayaheadprices::query::NORDPOOL_BASE_URL},
+    error::{ElspotError, ElspotResult},
+    exporter::Exporter,
+    metrics::MetricsOptions,
+    units::{CurrencyUnit, Power},
+};
+
+#[derive(Parser)]
+#[command(
+    name = "eb-nordpool-exporter",
+    version,
+    about = "Serves elspot prices from Nord Pool as Prometheus metrics on /metrics, and /healthz"
+)]
+struct Cli {
+    /// Address to listen on.
+    #[arg(short, long, default_value = "127.0.0.1:9796")]
+    listen: String,
+    /// Comma separated currencies, e.g. NOK,EUR.
+    #[arg(short, long, default_value = "EUR", value_delimiter = ',')]
+    currency: Vec<String>,
+    /// Comma separated area codes, e.g. NO1,SE3.
+    #[arg(short, long, required = true, value_delimiter = ',')]
+    regions: Vec<String>,
+    /// Seconds between refreshes, days with final prices are not fetched again.
+    #[arg(short, long, default_value_t = 300, value_parser = clap::value_parser!(u64).range(1..))]
+    interval: u64,
+    /// Data portal endpoint, e.g. a caching proxy.
+    #[arg(long, default_value = NORDPOOL_BASE_URL)]
+    base_url: String,
+    /// Name of the price metric, also the prefix of the next price and day statistics.
+    #[arg(long, default_value = eb_nordpool::metrics::DEFAULT_NAME)]
+    name: String,
+    /// Prices per kWh instead of per MWh.
+    #[arg(long)]
+    kwh: bool,
+    /// Prices in the currency fraction (e.g. Øre).
+    #[arg(long)]
+    fraction: bool,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            let mut source = std::error::Error::source(&e);
+            while let Some(s) = source {
+                eprintln!("  caused by: {s}");
+                source = s.source();
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> ElspotResult<()> {
+    let mut options = MetricsOptions::new().name(&cli.name);
+    if cli.kwh {
+        options = options.power_unit(Power::kWh);
+    }
+    if cli.fraction {
+        options = options.currency_unit(CurrencyUnit::Fraction);
+    }
+
+    let currencies: Vec<&str> = cli.currency.iter().map(String::as_str).collect();
+    let regions: Vec<&str> = cli.regions.iter().map(String::as_str).collect();
+    let exporter = Exporter::new(Client::new()?, SystemClock, &cli.base_url, &currencies, &regions)?.options(options);
+    let exporter = Arc::new(exporter);
+
+    let listener = TcpListener::bind(&cli.listen).map_err(|e| ElspotError::IOError { path: cli.listen.clone(), source: e })?;
+    eprintln!("listening on http://{}/metrics", cli.listen);
+
+    let refresher = Arc::clone(&exporter);
+    let interval = Duration::from_secs(cli.interval);
+    thread::spawn(move || {
+        loop {
+            if let Err(e) = refresher.refresh() {
+                eprintln!("refresh failed: {e}");
+            }
+            thread::sleep(interval);
+        }
+    });
+    exporter.serve(&listener);
+
+    Ok(())
+}
@@ -0,0 +1,303 @@
+//! Command line interface for eb_nordpool (feature `cli`).
+//!
+//! ```sh
+//! eb-nordpool fetch --regions SE3 --date tomorrow
+//! eb-nordpool fetch --currency NOK --regions NO1,NO3 --date 2024-09-22 --output prices.json
+//! eb-nordpool show prices.json --regions NO3 --format csv
+//! eb-nordpool convert prices.json --kwh --fraction
+//! eb-nordpool regions --format json
+//! ```
+
+mod output;
+
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use chrono::Days;
+use clap::{Args, Parser, Subcommand};
+use serde_json::{Number, Value};
+
+use eb_nordpool::{
+    clock::{Clock, SystemClock},
+    elspot::{self, Price, PriceExtractor, current, dataportal_dayaheadprices::regions},
+    error::{ElspotError, ElspotResult},
+    units::{self, CurrencyCode, CurrencyUnit},
+};
+
+use output::{Format, Rows};
+
+const EXIT_CODES: &str = "\
+Exit codes:
+  0  success
+  1  other errors
+  2  invalid usage
+  3  network error (request failed, HTTP status, invalid url)
+  4  I/O error (reading or writing a file or stdout)
+  5  invalid price data (not a supported format, or prices could not be extracted)
+  6  invalid query (currency, date or region)";
+
+#[derive(Parser)]
+#[command(name = "eb-nordpool", version, about = "Elspot prices from Nord Pool", after_help = EXIT_CODES)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Fetch prices from Nord Pool, print them or write them to file (as json).
+    Fetch {
+        #[arg(short, long, default_value = "EUR")]
+        currency: String,
+        /// Delivery date as YYYY-MM-DD, "today" or "tomorrow" (delivery days are in CET).
+        #[arg(short, long, default_value = "today")]
+        date: String,
+        /// Comma separated area codes, e.g. NO1,SE3.
+        #[arg(short, long, required = true, value_delimiter = ',')]
+        regions: Vec<String>,
+        /// Write the data as json to this file instead of printing the prices.
+        #[arg(short, long)]
+        output: Option<String>,
+        #[command(flatten)]
+        format: FormatArg,
+    },
+    /// Print prices from a json file (data portal or marketdata page 10), "-" reads from stdin.
+    Show {
+        file: String,
+        #[command(flatten)]
+        regions: RegionsArg,
+        #[command(flatten)]
+        format: FormatArg,
+    },
+    /// Print prices from a json file converted to kWh and/or the currency fraction (e.g. Øre).
+    Convert {
+        file: String,
+        #[arg(long, required_unless_present = "fraction")]
+        kwh: bool,
+        #[arg(long)]
+        fraction: bool,
+        #[command(flatten)]
+        regions: RegionsArg,
+        #[command(flatten)]
+        format: FormatArg,
+    },
+    /// List supported regions with their metadata.
+    Regions {
+        #[command(flatten)]
+        format: FormatArg,
+    },
+    /// List supported currencies with their units.
+    Currencies {
+        #[command(flatten)]
+        format: FormatArg,
+    },
+}
+
+#[derive(Args)]
+struct FormatArg {
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+}
+
+#[derive(Args)]
+struct RegionsArg {
+    /// Only regions in this comma separated list, all regions in the file if not set.
+    #[arg(short, long, value_delimiter = ',')]
+    regions: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            let mut source = std::error::Error::source(&e);
+            while let Some(s) = source {
+                eprintln!("  caused by: {s}");
+                source = s.source();
+            }
+            ExitCode::from(exit_code(&e))
+        }
+    }
+}
+
+fn run(command: Command) -> ElspotResult<()> {
+    let (rows, format) = match command {
+        Command::Fetch { currency, date, regions, output, format } => {
+            let date = resolve_date(&date)?;
+            let regions: Vec<&str> = regions.iter().map(String::as_str).collect();
+            let data = elspot::from_nordpool(&currency, &date, &regions)?;
+            if let Some(path) = output {
+                return data.to_file(&path);
+            }
+            (price_rows(&prices(data.as_ref(), &[])?)?, format.format)
+        }
+        Command::Show { file, regions, format } => {
+            let data = load(&file)?;
+            (price_rows(&prices(data.as_ref(), &regions.regions)?)?, format.format)
+        }
+        Command::Convert { file, kwh, fraction, regions, format } => {
+            let data = load(&file)?;
+            let mut prices = prices(data.as_ref(), &regions.regions)?;
+            for p in prices.iter_mut() {
+                if kwh {
+                    units::convert_to_kwh(p);
+                }
+                if fraction {
+                    units::convert_to_currency_fraction(p);
+                }
+            }
+            (price_rows(&prices)?, format.format)
+        }
+        Command::Regions { format } => (region_rows(), format.format),
+        Command::Currencies { format } => (currency_rows(), format.format),
+    };
+
+    write(&rows, format)
+}
+
+/// Maps errors to the exit codes in `EXIT_CODES`.
+fn exit_code(e: &ElspotError) -> u8 {
+    match e {
+        ElspotError::HttpRequestFailed { .. }
+        | ElspotError::InvalidHttpResponse { .. }
+        | ElspotError::HttpStatus { .. }
+        | ElspotError::HttpClientBuildFailed(_)
+        | ElspotError::InvalidHttpHeader { .. }
+        | ElspotError::InvalidUrl { .. }
+        | ElspotError::NetworkDisabled(_) => 3,
+
+        ElspotError::IOError { .. } => 4,
+
+        ElspotError::InvalidInputData
+        | ElspotError::InvalidUnit { .. }
+        | ElspotError::InvalidPrice { .. }
+        | ElspotError::DataPortalDayaheadPricesInvalidJson(_)
+        | ElspotError::DataPortalDayaheadPricesInvalidMarket(_)
+        | ElspotError::DataPortalDayaheadPricesInvalidVersion(_)
+        | ElspotError::DataPortalDayaheadPricesMissingCurrency
+        | ElspotError::DataPortalDayaheadPricesMissingDate
+        | ElspotError::MarketdataPage10InvalidJson(_)
+        | ElspotError::MarketdataPage10InvalidPageId(_)
+        | ElspotError::MarketdataPage10MissingUnitString
+        | ElspotError::MarketdataPage10InvalidUnitString(_)
+        | ElspotError::MarketdataPage10InvalidPriceCount { .. }
+        | ElspotError::MarketdataPage10DateMismatch { .. } => 5,
+
+        ElspotError::InvalidDateRange { .. }
+        | ElspotError::Region(_)
+        | ElspotError::DataPortalDayaheadPricesNoRegionsSupplied
+        | ElspotError::DataPortalDayaheadPricesUnsupportedCurrency(_)
+        | ElspotError::DataPortalDayaheadPricesUnsupportedRegion(_)
+        | ElspotError::DataPortalDayaheadPricesInvalidDate { .. } => 6,
+
+        ElspotError::JsonSerializationFailed(_)
+        | ElspotError::StoreInvalidColumn { .. }
+        | ElspotError::ArrowInvalidColumn(_)
+        | ElspotError::ArrowInvalidValue { .. }
+        | ElspotError::Series(_)
+        | ElspotError::Exchange(_) => 1,
+        #[cfg(feature = "sqlite")]
+        ElspotError::Sqlite(_) => 1,
+        #[cfg(feature = "arrow")]
+        ElspotError::Arrow(_) | ElspotError::Parquet(_) => 1,
+    }
+}
+
+/// "today" and "tomorrow" are delivery days (CET), other values are passed on as is.
+fn resolve_date(date: &str) -> ElspotResult<String> {
+    let today = current::delivery_date(SystemClock.now());
+    let date = match date {
+        "today" => today,
+        "tomorrow" => today.checked_add_days(Days::new(1)).ok_or(ElspotError::InvalidInputData)?,
+        _ => return Ok(date.to_string()),
+    };
+
+    Ok(date.format("%Y-%m-%d").to_string())
+}
+
+fn load(file: &str) -> ElspotResult<Box<dyn PriceExtractor>> {
+    if file != "-" {
+        return elspot::from_file(file);
+    }
+    let json_str = io::read_to_string(io::stdin())
+        .map_err(|e| ElspotError::IOError { path: file.to_string(), source: e })?;
+
+    elspot::from_json(&json_str)
+}
+
+/// Prices for `regions`, or all regions in the data when empty.
+fn prices(data: &dyn PriceExtractor, regions: &[String]) -> ElspotResult<Vec<Price>> {
+    if regions.is_empty() {
+        return Ok(data.extract_prices_all_regions()?.into_iter().flatten().collect());
+    }
+
+    let mut prices = vec![];
+    for region in regions {
+        let region = region.parse()?;
+        prices.extend(data.extract_prices(region)?);
+    }
+
+    Ok(prices)
+}
+
+fn price_rows(prices: &[Price]) -> ElspotResult<Rows> {
+    let mut rows = Rows::new(&["region", "from", "to", "value", "unit", "price"]);
+    for p in prices {
+        let (from, to) = p.from_to();
+        let label = p.price_label();
+        let value = p.value.to_string().parse::<Number>().map_or(Value::Null, Value::Number);
+        rows.push(vec![
+            p.region.as_str().into(),
+            from.format("%Y-%m-%dT%H:%M%:z").to_string().into(),
+            to.format("%Y-%m-%dT%H:%M%:z").to_string().into(),
+            value,
+            format!("{}/{}", p.currency_unit.as_str(), p.power_unit.as_str()).into(),
+            label.into(),
+        ]);
+    }
+
+    Ok(rows)
+}
+
+fn region_rows() -> Rows {
+    let mut rows = Rows::new(&["code", "name", "local_name", "country", "group", "currency", "timezone", "eic"]);
+    for z in regions::zones() {
+        rows.push(vec![
+            z.code().into(),
+            z.name.into(),
+            z.local_name.into(),
+            z.country.map_or(Value::Null, Value::from),
+            z.group().as_str().into(),
+            z.currency.as_str().into(),
+            z.tz().name().into(),
+            z.eic.map_or(Value::Null, Value::from),
+        ]);
+    }
+
+    rows
+}
+
+fn currency_rows() -> Rows {
+    let mut rows = Rows::new(&["code", "full", "fraction"]);
+    for c in CurrencyCode::ALL {
+        rows.push(vec![
+            c.as_str().into(),
+            units::Currency::from_code(c, CurrencyUnit::Full).as_str().into(),
+            units::Currency::from_code(c, CurrencyUnit::Fraction).as_str().into(),
+        ]);
+    }
+
+    rows
+}
+
+/// A closed stdout (e.g. piped to `head`) is not an error.
+fn write(rows: &Rows, format: Format) -> ElspotResult<()> {
+    let mut out = io::stdout().lock();
+    match rows.write(&mut out, format).and_then(|_| out.flush()) {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(ElspotError::IOError { path: "stdout".to_string(), source: e }),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,109 @@
+//! Rows of data written as table, json or csv.
+
+use std::io::{self, Write};
+
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Aligned columns for reading in a terminal.
+    Table,
+    /// An array with one object per row.
+    Json,
+    /// A header line followed by one line per row.
+    Csv,
+}
+
+/// Named columns, each row has one value per column.
+pub struct Rows {
+    columns: &'static [&'static str],
+    rows: Vec<Vec<Value>>,
+}
+
+impl Rows {
+    pub fn new(columns: &'static [&'static str]) -> Self {
+        Self { columns, rows: vec![] }
+    }
+
+    pub fn push(&mut self, row: Vec<Value>) {
+        debug_assert_eq!(self.columns.len(), row.len());
+        self.rows.push(row);
+    }
+
+    pub fn write(&self, out: &mut impl Write, format: Format) -> io::Result<()> {
+        match format {
+            Format::Table => self.write_table(out),
+            Format::Json => self.write_json(out),
+            Format::Csv => self.write_csv(out),
+        }
+    }
+
+    fn write_table(&self, out: &mut impl Write) -> io::Result<()> {
+        let cells: Vec<Vec<String>> = self.rows.iter().map(|r| r.iter().map(cell).collect()).collect();
+        let widths: Vec<usize> = self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| cells.iter().map(|r| r[i].chars().count()).chain([c.len()]).max().unwrap_or(0))
+            .collect();
+
+        let header: Vec<String> = self.columns.iter().map(|c| c.to_uppercase()).collect();
+        write_aligned(out, &header, &widths, &[])?;
+        for (row, values) in cells.iter().zip(&self.rows) {
+            write_aligned(out, row, &widths, values)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_json(&self, out: &mut impl Write) -> io::Result<()> {
+        let rows: Vec<Map<String, Value>> = self.rows
+            .iter()
+            .map(|r| self.columns.iter().map(|c| c.to_string()).zip(r.iter().cloned()).collect())
+            .collect();
+        serde_json::to_writer_pretty(&mut *out, &rows)?;
+
+        writeln!(out)
+    }
+
+    fn write_csv(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "{}", self.columns.join(","))?;
+        for row in &self.rows {
+            let fields: Vec<String> = row.iter().map(|v| csv_field(&cell(v))).collect();
+            writeln!(out, "{}", fields.join(","))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Numbers are right aligned, everything else left aligned.
+fn write_aligned(out: &mut impl Write, row: &[String], widths: &[usize], values: &[Value]) -> io::Result<()> {
+    let line: Vec<String> = row
+        .iter()
+        .zip(widths)
+        .enumerate()
+        .map(|(i, (s, w))| match values.get(i) {
+            Some(Value::Number(_)) => format!("{s:>w$}"),
+            _ => format!("{s:<w$}"),
+        })
+        .collect();
+
+    writeln!(out, "{}", line.join("  ").trim_end())
+}
+
+fn cell(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        v => v.to_string(),
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
@@ -0,0 +1,253 @@
+//! Write prices as CSV for spreadsheets, and read them back.
+//!
+//! Two layouts are supported:
+//! * long, one row per region and delivery period with the units in their own columns, `from_long` reads it back.
+//! * wide, one row per delivery period with one column per region (values only).
+//!
+//! Timestamps are RFC 3339 with offset, in UTC, in the timezone of the region or in a given `Tz`.
+//! Values are exact decimals, written with the decimal separator of `CsvOptions` (e.g. ',' for Nordic Excel).
+
+use std::collections::BTreeMap;
+use std::fs;
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use chrono_tz::Tz;
+
+use crate::elspot::Price;
+use crate::error::{
+    CsvError,
+    CsvResult,
+};
+use crate::price_value::PriceValue;
+use crate::region::Region;
+use crate::units::{self, CurrencyCode, CurrencyUnit};
+
+const LONG_COLUMNS: [&str; 8] = ["region", "date", "from", "to", "value", "currency", "currency_unit", "power_unit"];
+
+/// Timezone for the `from` and `to` timestamps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Timestamps {
+    Utc,
+    /// The timezone of the region of each price, for the wide format the region of the first column.
+    Region,
+    Tz(Tz),
+}
+
+#[derive(Clone, Debug)]
+pub struct CsvOptions {
+    delimiter: char,
+    decimal_separator: char,
+    timestamps: Timestamps,
+    currency_unit: Option<CurrencyUnit>,
+    power_unit: Option<units::Power>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            decimal_separator: '.',
+            timestamps: Timestamps::Utc,
+            currency_unit: None,
+            power_unit: None,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// ',' as delimiter, '.' as decimal separator, timestamps in UTC and prices in the units they are in.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ';' as delimiter and ',' as decimal separator, as Excel expects with Nordic locales.
+    pub fn nordic() -> Self {
+        Self::new().delimiter(';').decimal_separator(',')
+    }
+
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn decimal_separator(mut self, decimal_separator: char) -> Self {
+        self.decimal_separator = decimal_separator;
+        self
+    }
+
+    pub fn timestamps(mut self, timestamps: Timestamps) -> Self {
+        self.timestamps = timestamps;
+        self
+    }
+
+    /// Converts prices to `currency_unit` before writing, see `units::convert_to_currency_fraction`.
+    pub fn currency_unit(mut self, currency_unit: CurrencyUnit) -> Self {
+        self.currency_unit = Some(currency_unit);
+        self
+    }
+
+    /// Converts prices to `power_unit` before writing, see `units::convert_to_kwh`.
+    pub fn power_unit(mut self, power_unit: units::Power) -> Self {
+        self.power_unit = Some(power_unit);
+        self
+    }
+
+    fn validate(&self) -> CsvResult<()> {
+        let (d, s) = (self.delimiter, self.decimal_separator);
+        let in_values = |c: char| c.is_alphanumeric() || c.is_whitespace() || matches!(c, '-' | '+' | ':' | '"');
+        if d == s || in_values(d) || in_values(s) || d == '.' {
+            return Err(CsvError::InvalidOptions { delimiter: d, decimal_separator: s });
+        }
+
+        Ok(())
+    }
+
+    fn convert(&self, p: &Price) -> Price {
+        let mut p = p.clone();
+        units::convert_to(&mut p, self.currency_unit.as_ref(), self.power_unit.as_ref());
+
+        p
+    }
+
+    fn timestamp(&self, dt: DateTime<Utc>, region: Region) -> String {
+        match self.timestamps {
+            Timestamps::Utc => dt.to_rfc3339_opts(SecondsFormat::Secs, true),
+            Timestamps::Region => dt.with_timezone(&region.tz()).to_rfc3339_opts(SecondsFormat::Secs, true),
+            Timestamps::Tz(tz) => dt.with_timezone(&tz).to_rfc3339_opts(SecondsFormat::Secs, true),
+        }
+    }
+
+    fn value(&self, value: PriceValue) -> String {
+        value.to_string().replace('.', &self.decimal_separator.to_string())
+    }
+}
+
+/// One row per price, in the order of `prices`, with the columns
+/// region, date, from, to, value, currency (e.g. "NOK"), currency_unit (e.g. "Øre") and power_unit (e.g. "kWh").
+pub fn long(prices: &[Price], options: &CsvOptions) -> CsvResult<String> {
+    options.validate()?;
+    let d = options.delimiter.to_string();
+
+    let mut csv = LONG_COLUMNS.join(&d);
+    csv.push('\n');
+    for p in prices.iter().map(|p| options.convert(p)) {
+        let row = [
+            p.region.as_str().to_string(),
+            p.date.to_string(),
+            options.timestamp(p.from, p.region),
+            options.timestamp(p.to, p.region),
+            options.value(p.value),
+            p.currency_unit.code().to_string(),
+            p.currency_unit.as_str().to_string(),
+            p.power_unit.as_str().to_string(),
+        ];
+        csv.push_str(&row.join(&d));
+        csv.push('\n');
+    }
+
+    Ok(csv)
+}
+
+/// One row per delivery period (`from`, `to`) and one column per region, in the order the regions first appear
+/// in `prices` (e.g. the flattened `extract_prices_all_regions`). A region without a price for a period is left empty.
+pub fn wide(prices: &[Price], options: &CsvOptions) -> CsvResult<String> {
+    options.validate()?;
+    let d = options.delimiter.to_string();
+
+    let mut regions: Vec<Region> = vec![];
+    let mut rows: BTreeMap<(DateTime<Utc>, DateTime<Utc>), BTreeMap<Region, PriceValue>> = BTreeMap::new();
+    for p in prices.iter().map(|p| options.convert(p)) {
+        if !regions.contains(&p.region) {
+            regions.push(p.region);
+        }
+        rows.entry((p.from, p.to)).or_default().insert(p.region, p.value);
+    }
+    let tz_region = regions.first().copied().unwrap_or(Region::SYS);
+
+    let mut csv = ["from", "to"].into_iter().chain(regions.iter().map(|r| r.as_str())).collect::<Vec<_>>().join(&d);
+    csv.push('\n');
+    for ((from, to), values) in rows {
+        let mut row = vec![options.timestamp(from, tz_region), options.timestamp(to, tz_region)];
+        row.extend(regions.iter().map(|r| values.get(r).map(|v| options.value(*v)).unwrap_or_default()));
+        csv.push_str(&row.join(&d));
+        csv.push('\n');
+    }
+
+    Ok(csv)
+}
+
+/// Rebuilds prices from the long format, columns are found by name in the header (in any order).
+pub fn from_long(csv: &str, options: &CsvOptions) -> CsvResult<Vec<Price>> {
+    options.validate()?;
+    let mut lines = csv.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+
+    let header: Vec<&str> = match lines.next() {
+        Some((_, header)) => split(header, options.delimiter).collect(),
+        None => return Err(CsvError::MissingColumn(LONG_COLUMNS[0])),
+    };
+    let mut index = [0; LONG_COLUMNS.len()];
+    for (i, column) in LONG_COLUMNS.iter().enumerate() {
+        index[i] = header.iter().position(|h| h == column).ok_or(CsvError::MissingColumn(column))?;
+    }
+
+    let mut prices = vec![];
+    for (i, line) in lines {
+        let fields: Vec<&str> = split(line, options.delimiter).collect();
+        let field = |column: usize| fields.get(index[column]).copied().unwrap_or_default();
+        let invalid = |reason: String| CsvError::InvalidRow { line: i + 1, reason };
+
+        let region: Region = field(0).parse().map_err(|e| invalid(format!("{e}")))?;
+        let date = NaiveDate::parse_from_str(field(1), "%Y-%m-%d")
+            .map_err(|_| invalid(format!("'{}' is not a valid date", field(1))))?;
+        let from = parse_timestamp(field(2)).ok_or_else(|| invalid(format!("'{}' is not a valid timestamp", field(2))))?;
+        let to = parse_timestamp(field(3)).ok_or_else(|| invalid(format!("'{}' is not a valid timestamp", field(3))))?;
+        let value: PriceValue = field(4)
+            .replace(options.decimal_separator, ".")
+            .parse()
+            .map_err(|e| invalid(format!("{e}")))?;
+        let currency: CurrencyCode = field(5).parse().map_err(|e| invalid(format!("{e}")))?;
+        let currency_unit = [CurrencyUnit::Full, CurrencyUnit::Fraction]
+            .into_iter()
+            .map(|unit| units::Currency::from_code(currency, unit))
+            .find(|c| c.as_str() == field(6))
+            .ok_or_else(|| invalid(format!("'{}' is not a unit of {currency}", field(6))))?;
+
+        prices.push(Price {
+            from,
+            to,
+            date,
+            region,
+            value,
+            currency_unit,
+            market_time_unit: units::Mtu::new(from, to).map_err(|e| invalid(format!("{e}")))?,
+            power_unit: units::Power::new(field(7)).map_err(|e| invalid(format!("{e}")))?,
+            exchange_rate: None,
+        });
+    }
+
+    Ok(prices)
+}
+
+/// Writes `csv` (from `long` or `wide`) to file.
+pub fn to_file(path: &str, csv: &str) -> CsvResult<()> {
+    fs::write(path, csv.as_bytes()).map_err(|e| CsvError::IOError { path: path.to_string(), source: e })
+}
+
+/// Reads prices from a file in the long format, see `from_long`.
+pub fn from_file(path: &str, options: &CsvOptions) -> CsvResult<Vec<Price>> {
+    let csv = fs::read_to_string(path).map_err(|e| CsvError::IOError { path: path.to_string(), source: e })?;
+
+    from_long(&csv, options)
+}
+
+/// Fields are trimmed, and quotes around a field (as added by some spreadsheets) are removed.
+fn split(line: &str, delimiter: char) -> impl Iterator<Item = &str> {
+    line.split(delimiter).map(|f| {
+        let f = f.trim();
+        f.strip_prefix('"').and_then(|f| f.strip_suffix('"')).unwrap_or(f)
+    })
+}
+
+fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s).ok().map(|dt| dt.to_utc())
+}
@@ -3,17 +3,36 @@ use std::{fmt, fs};
 use chrono::{DateTime, Utc, NaiveDate};
 use chrono_tz::Tz;
 
-use reqwest;
-
 use crate::error::{
     ElspotError,
     ElspotResult,
+    RegionResult,
+    UnitError,
+    UnitResult,
 };
+use crate::exchange::ExchangeRate;
+use crate::price_value::{PriceValue, Rounding};
+use crate::region::Region;
+use crate::series::{PriceSeries, PriceSet};
 use crate::region_time::dt_region_from_utc_dt;
 use crate::units;
 
+use dataportal_dayaheadprices::query::NORDPOOL_BASE_URL;
+use transport::Transport;
+
 pub mod dataportal_dayaheadprices;
 pub mod marketdata_page_10;
+pub mod cache;
+pub mod current;
+pub mod local_day;
+pub mod range;
+pub mod transport;
+
+#[cfg(feature = "blocking")]
+pub mod client;
+
+#[cfg(feature = "async")]
+pub mod r#async;
 
 /// Each price returned comes in the form of this datatype.
 #[derive(Clone, Debug)]
@@ -21,11 +40,13 @@ pub struct Price {
     pub from: DateTime<Utc>,
     pub to: DateTime<Utc>,
     pub date: NaiveDate,
-    pub region: String,
-    pub value: String,
+    pub region: Region,
+    pub value: PriceValue,
     pub currency_unit: units::Currency,
     pub market_time_unit: units::Mtu,
     pub power_unit: units::Power,
+    /// The rate the price was converted with, `None` for prices as published, see `exchange::convert`.
+    pub exchange_rate: Option<ExchangeRate>,
 }
 
 impl fmt::Display for Price {
@@ -34,69 +55,59 @@ impl fmt::Display for Price {
     }
 }
 
-// FIXME: Add Result types and remove panics.
 impl Price {
-    fn formatted_decimal(&self) -> String {
-        // Since we are working with money, we want to round to 2 decimals.
-        // This function will try its best to round the floating point number in the right direction.
-        // Large numbers (including negative) or numbers with many fractional digits,
-        // might in rare cases be rounded the wrong way due to floating point precision errors.
-        // The rounding error is marginal and I consider this an OK trade off for not using fixed point calculation.
-
-        // Validates that the number is parsable before starting, otherwise do not continue..
-        self.value.parse::<f64>().unwrap_or_else(|e| panic!("{}: '{}' could not be parsad into float", e, self.value));
-
-        let mut split = self.value.split('.');
-        let whole_numbers = split.next().unwrap();
-        match split.next() {
-            Some("") | None => {
-                whole_numbers.to_string()
-            }
-            Some(fractions) => {
-                if fractions.len() > 3 {
-                    // Only keep at most 3 fractions, the 3rd is for rounding and fixes some rounding errors.
-                    format!("{}.{}", whole_numbers, &fractions.to_string()[..3]).to_string()
-                } else {
-                    format!("{}.{}", whole_numbers, fractions).to_string()
-                }
-            }
+    /// Returns the price rounded to 2 decimals (half away from zero),
+    /// or to a whole number when the currency is a fraction unit (e.g. "Øre").
+    pub fn rounded(&self) -> PriceValue {
+        self.rounded_with(Rounding::default())
+    }
+
+    /// Returns the price rounded with `rounding`, see `Rounding` for the default amount of decimals.
+    pub fn rounded_with(&self, rounding: Rounding) -> PriceValue {
+        match rounding.decimals {
+            Some(decimals) => self.value.round_with(decimals, rounding.mode),
+            // Same as earlier versions, rounded to 2 decimals first, e.g. 1050.495 Øre becomes 1050.50 and then 1051.
+            None if self.currency_unit.is_fraction() => self.value.round_with(2, rounding.mode).round_with(0, rounding.mode),
+            None => self.value.round_with(2, rounding.mode),
         }
     }
 
     pub fn as_f32(&self) -> f32 {
-        let v_f32 = self.formatted_decimal().parse::<f32>().unwrap();
+        self.as_f32_with(Rounding::default())
+    }
 
-        // Only keep two decimal places..
-        let v_f32 = (v_f32 * 100.0).round() / 100.0;
+    pub fn as_f32_with(&self, rounding: Rounding) -> f32 {
+        self.rounded_with(rounding).to_f32()
+    }
 
-        if self.currency_unit.is_fraction() {
-            // Currency sub-unit does not use fractions, we round all the way up.
-            v_f32.round()
-        } else {
-            v_f32
-        }
+    /// Fails if the rounded price does not fit in an `i32`.
+    pub fn as_i32(&self) -> UnitResult<i32> {
+        self.as_i32_with(Rounding::default())
     }
 
-    pub fn as_i32(&self) -> i32 {
-        self.as_f32().round() as i32
+    /// Rounds with `rounding` first, and then to a whole number with the same rounding mode.
+    pub fn as_i32_with(&self, rounding: Rounding) -> UnitResult<i32> {
+        i32::try_from(self.as_i64_with(rounding)?).map_err(|_| UnitError::PriceValueOutOfRange(self.value.to_string()))
     }
 
     pub fn as_f64(&self) -> f64 {
-        let v_f64 = self.formatted_decimal().parse::<f64>().unwrap();
+        self.as_f64_with(Rounding::default())
+    }
 
-        // Only keep two decimal places..
-        let v_f64 = (v_f64 * 100.0).round() / 100.0;
+    pub fn as_f64_with(&self, rounding: Rounding) -> f64 {
+        self.rounded_with(rounding).to_f64()
+    }
 
-        if self.currency_unit.is_fraction() {
-            // Currency sub-unit does not use fractions, we round all the way up.
-            v_f64.round()
-        } else {
-            v_f64
-        }
+    /// Fails if the rounded price does not fit in an `i64`.
+    pub fn as_i64(&self) -> UnitResult<i64> {
+        self.as_i64_with(Rounding::default())
     }
 
-    pub fn as_i64(&self) -> i64 {
-        self.as_f64().round() as i64
+    /// Rounds with `rounding` first, and then to a whole number with the same rounding mode.
+    pub fn as_i64_with(&self, rounding: Rounding) -> UnitResult<i64> {
+        let whole = self.rounded_with(rounding).round_with(0, rounding.mode);
+
+        whole.to_string().parse::<i64>().map_err(|_| UnitError::PriceValueOutOfRange(self.value.to_string()))
     }
 
     pub fn hour(&self) -> String {
@@ -104,7 +115,12 @@ impl Price {
     }
 
     pub fn price_label(&self) -> String {
-        let value = self.as_f32().to_string().replace('.', ",");
+        self.price_label_with(Rounding::default())
+    }
+
+    /// Same as `price_label`, with the value rounded with `rounding`.
+    pub fn price_label_with(&self, rounding: Rounding) -> String {
+        let value = self.rounded_with(rounding).to_string().replace('.', ",");
         let currency_unit = self.currency_unit.as_str();
         let power_unit = self.power_unit.as_str();
         let country = self.currency_unit.country_code_as_str();
@@ -113,15 +129,15 @@ impl Price {
     }
 
     pub fn from_to(&self) -> (DateTime<Tz>, DateTime<Tz>) {
-        (dt_region_from_utc_dt(&self.from, &self.region), dt_region_from_utc_dt(&self.to, &self.region))
+        self.from_to_with_tz(self.region.tz())
     }
 
     pub fn from_to_as_utc(&self) -> (DateTime<Utc>, DateTime<Utc>) {
         (self.from, self.to)
     }
 
-    pub fn from_to_with_region(&self, region: &str) -> (DateTime<Tz>, DateTime<Tz>) {
-        (dt_region_from_utc_dt(&self.from, region), dt_region_from_utc_dt(&self.to, region))
+    pub fn from_to_with_region(&self, region: &str) -> RegionResult<(DateTime<Tz>, DateTime<Tz>)> {
+        Ok((dt_region_from_utc_dt(&self.from, region)?, dt_region_from_utc_dt(&self.to, region)?))
     }
 
     pub fn from_to_with_tz(&self, tz: Tz) -> (DateTime<Tz>, DateTime<Tz>) {
@@ -129,7 +145,7 @@ impl Price {
     }
 }
 
-pub trait PriceExtractor {
+pub trait PriceExtractor: Send + Sync {
     fn new(json_str: &str) -> ElspotResult<Self> where Self: Sized;
 
     /// Check if prices are final.
@@ -154,71 +170,117 @@ pub trait PriceExtractor {
     fn date(&self) -> NaiveDate;
 
     /// Returns a vector of all prices for selected region.
-    fn extract_prices_for_region(&self, region: &str) -> Vec<Price>;
+    fn extract_prices_for_region(&self, region: &str) -> ElspotResult<Vec<Price>>;
+
+    /// Same as `extract_prices_for_region`, for the first region in the dataset that is (or is an alias of) `region`.
+    fn extract_prices(&self, region: Region) -> ElspotResult<Vec<Price>> {
+        let name = self.regions().into_iter().find(|name| name.parse::<Region>().ok() == Some(region)).map(|name| name.to_string());
+
+        match name {
+            Some(name) => self.extract_prices_for_region(&name),
+            None => Ok(vec![]),
+        }
+    }
 
     /// Returns a vector of a vector of all prices for all regions.
-    fn extract_prices_all_regions(&self) -> Vec<Vec<Price>>;
+    fn extract_prices_all_regions(&self) -> ElspotResult<Vec<Vec<Price>>>;
+
+    /// Same as `extract_prices_for_region`, as a `PriceSeries` (`None` if there are no prices for region).
+    fn extract_series_for_region(&self, region: &str) -> ElspotResult<Option<PriceSeries>> {
+        let prices = self.extract_prices_for_region(region)?;
+        if prices.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(PriceSeries::new(prices)?))
+    }
+
+    /// Same as `extract_prices_all_regions`, as a `PriceSet` with one series per region.
+    fn extract_price_set(&self) -> ElspotResult<PriceSet> {
+        Ok(PriceSet::from_regions(self.extract_prices_all_regions()?)?)
+    }
 
     /// Returns a json string of all data.
-    fn to_json_string(&self) -> String;
+    fn to_json_string(&self) -> ElspotResult<String>;
 
     /// Writes the data to file (as json).
-    fn to_file(&self, path: &str);
+    fn to_file(&self, path: &str) -> ElspotResult<()>;
+
+    /// Returns the data portal data (with block aggregates, area averages, exchange rate and states)
+    /// when the prices came from the data portal, `None` for other formats.
+    fn as_dataportal(&self) -> Option<&dataportal_dayaheadprices::PriceData> {
+        None
+    }
 }
 
 pub fn from_json(json_str: &str) -> ElspotResult<Box<dyn PriceExtractor>> {
-    if let Ok(data) = dataportal_dayaheadprices::PriceData::new(json_str) {
-        return Ok(Box::new(data))
+    // Only move on to the next format if the json does not match the current one,
+    // any other error means that we found the right format, but the data is invalid.
+    match dataportal_dayaheadprices::PriceData::new(json_str) {
+        Ok(data) => return Ok(Box::new(data)),
+        Err(ElspotError::DataPortalDayaheadPricesInvalidJson(_)) => (),
+        Err(e) => return Err(e),
     }
 
-    if let Ok(data) = marketdata_page_10::PriceData::new(json_str) {
-        return Ok(Box::new(data))
+    match marketdata_page_10::PriceData::new(json_str) {
+        Ok(data) => return Ok(Box::new(data)),
+        Err(ElspotError::MarketdataPage10InvalidJson(_)) => (),
+        Err(e) => return Err(e),
     }
 
-    eprintln!("Could not extract price data from input, is it valid?");
-    eprintln!("Dumping input data:\n{json_str}");
     Err(ElspotError::InvalidInputData)
 }
 
 pub fn from_file(path: &str) -> ElspotResult<Box<dyn PriceExtractor>> {
     match fs::read_to_string(path) {
         Ok(s) => from_json(&s),
-        Err(e) => {
-            eprintln!("{e}");
-            Err(ElspotError::IOError)
-        }
+        Err(e) => Err(ElspotError::IOError { path: path.to_string(), source: e }),
     }
 }
 
+#[cfg(feature = "blocking")]
 pub fn from_url(url: &str) -> ElspotResult<Box<dyn PriceExtractor>> {
-    match reqwest::blocking::get(url) {
-        Ok(r) => {
-            match r.text() {
-                Ok(s) => from_json(&s),
-                Err(e) => {
-                    eprintln!("{e}");
-                    Err(ElspotError::InvalidHttpResponse)
-                }
-            }
-        }
-        Err(e) => {
-            eprintln!("{e}");
-            Err(ElspotError::HttpRequestFailed)
-        }
-    }
+    from_url_with(&transport::ReqwestTransport, url)
 }
 
+pub fn from_url_with<T: Transport>(transport: &T, url: &str) -> ElspotResult<Box<dyn PriceExtractor>> {
+    let json_str = transport.get(url)?;
+
+    from_json(&json_str)
+}
+
+#[cfg(feature = "blocking")]
 pub fn from_nordpool(currency: &str, date: &str, regions: &[&str]) -> ElspotResult<Box<dyn PriceExtractor>> {
+    from_nordpool_with(&transport::ReqwestTransport, currency, date, regions)
+}
+
+pub fn from_nordpool_with<T: Transport>(
+    transport: &T,
+    currency: &str,
+    date: &str,
+    regions: &[&str],
+) -> ElspotResult<Box<dyn PriceExtractor>> {
+    let url = nordpool_url(NORDPOOL_BASE_URL, currency, date, regions)?;
+
+    from_url_with(transport, &url)
+}
+
+/// Builds the data portal url for `from_nordpool`, shared by the blocking and async clients.
+pub(crate) fn nordpool_url(base_url: &str, currency: &str, date: &str, regions: &[&str]) -> ElspotResult<String> {
     if regions.is_empty() {
         return Err(ElspotError::DataPortalDayaheadPricesNoRegionsSupplied);
     }
 
     let mut q = dataportal_dayaheadprices::query::QueryOptions::new();
-    q.set_date(date);
-    q.set_currency(currency);
-    q.set_regions(regions);
+    q.set_date(date)?;
+    q.set_currency(currency)?;
+    q.set_regions(regions)?;
 
-    let url = q.build_url();
+    q.build_url_with_base(base_url)
+}
 
-    from_url(&url)
+/// Writes json string to file, shared by the `PriceExtractor::to_file` implementations.
+pub(crate) fn write_json_file(path: &str, json_str: &str) -> ElspotResult<()> {
+    fs::write(path, json_str.as_bytes())
+        .map_err(|e| ElspotError::IOError { path: path.to_string(), source: e })
 }
@@ -0,0 +1,50 @@
+//! Async versions of the functions that download price data, enabled with the `async` feature.
+//!
+//! They mirror the blocking functions in `elspot` and `elspot::marketdata_page_10`,
+//! and hand the response over to the same parsers.
+
+use crate::error::{
+    ElspotError,
+    ElspotResult,
+};
+
+use super::{PriceExtractor, from_json, nordpool_url};
+use super::dataportal_dayaheadprices::query::NORDPOOL_BASE_URL;
+
+pub async fn from_url(url: &str) -> ElspotResult<Box<dyn PriceExtractor>> {
+    let json_str = get_text(url).await?;
+
+    from_json(&json_str)
+}
+
+pub async fn from_nordpool(currency: &str, date: &str, regions: &[&str]) -> ElspotResult<Box<dyn PriceExtractor>> {
+    let url = nordpool_url(NORDPOOL_BASE_URL, currency, date, regions)?;
+
+    from_url(&url).await
+}
+
+pub(crate) async fn get_text(url: &str) -> ElspotResult<String> {
+    match reqwest::get(url).await {
+        Ok(r) if !r.status().is_success() => {
+            Err(ElspotError::HttpStatus { url: url.to_string(), status: r.status().as_u16() })
+        }
+        Ok(r) => {
+            match r.text().await {
+                Ok(s) => Ok(s),
+                Err(e) => Err(ElspotError::InvalidHttpResponse { url: url.to_string(), source: e }),
+            }
+        }
+        Err(e) => Err(ElspotError::HttpRequestFailed { url: url.to_string(), source: e }),
+    }
+}
+
+pub mod marketdata_page_10 {
+    use crate::error::ElspotResult;
+    use crate::elspot::{PriceExtractor, marketdata_page_10::PriceData};
+
+    pub async fn from_url(url: &str) -> ElspotResult<PriceData> {
+        let json_str = super::get_text(url).await?;
+
+        PriceData::new(&json_str)
+    }
+}
@@ -0,0 +1,186 @@
+//! Opt-in on-disk cache in front of the data portal fetch (`from_nordpool`).
+//!
+//! Entries are keyed by market, currency, delivery date and regions, and hold the same json that
+//! `PriceExtractor::to_file` writes. Final prices never change, so they are served for as long as they are cached.
+//! A response without any area states is not known to be final, and is treated like preliminary prices.
+//! Preliminary prices are only served while their `updatedAt` is younger than the TTL, otherwise they are re-fetched.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use chrono::Utc;
+
+use crate::error::{
+    ElspotError,
+    ElspotResult,
+};
+
+use super::dataportal_dayaheadprices::{self, query::NORDPOOL_BASE_URL};
+use super::transport::Transport;
+use super::{PriceExtractor, nordpool_url};
+
+const MARKET: &str = "DayAhead";
+
+pub struct ResponseCache<T: Transport> {
+    dir: PathBuf,
+    transport: T,
+    base_url: String,
+    ttl: Option<Duration>,
+    max_entries: Option<usize>,
+    bypass: bool,
+}
+
+impl<T: Transport> ResponseCache<T> {
+    /// Cache in `dir` (created when needed) that fetches missing or stale entries with `transport`.
+    /// By default, preliminary prices are always re-fetched and there is no limit on the amount of entries.
+    pub fn new<P: AsRef<Path>>(dir: P, transport: T) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            transport,
+            base_url: NORDPOOL_BASE_URL.to_string(),
+            ttl: None,
+            max_entries: None,
+            bypass: false,
+        }
+    }
+
+    /// Url for the DayAheadPrices endpoint, defaults to `NORDPOOL_BASE_URL`.
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Serve preliminary prices from the cache while their `updatedAt` is younger than `ttl`.
+    pub fn ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Keep at most `max_entries` in the cache, the least recently used entries are removed first.
+    pub fn max_entries(mut self, max_entries: Option<usize>) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Skip reading from the cache, fetched prices are still stored.
+    pub fn bypass(mut self, bypass: bool) -> Self {
+        self.bypass = bypass;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the path to the cache entry for the query.
+    /// Fails like `from_nordpool` for a currency, date or region that is not supported,
+    /// so that only known codes (and never a path) end up in the file name.
+    pub fn path_for(&self, currency: &str, date: &str, regions: &[&str]) -> ElspotResult<PathBuf> {
+        nordpool_url(&self.base_url, currency, date, regions)?;
+
+        let mut regions = regions.to_vec();
+        regions.sort_unstable();
+        regions.dedup();
+
+        Ok(self.dir.join(format!("{}_{}_{}_{}.json", MARKET, currency, date, regions.join("-"))))
+    }
+
+    /// Same as `elspot::from_nordpool`, but served from the cache when possible.
+    pub fn from_nordpool(&self, currency: &str, date: &str, regions: &[&str]) -> ElspotResult<Box<dyn PriceExtractor>> {
+        // Validates the query before we touch the cache.
+        let url = nordpool_url(&self.base_url, currency, date, regions)?;
+        let path = self.path_for(currency, date, regions)?;
+
+        if !self.bypass && let Some(data) = self.read_fresh(&path) {
+            touch(&path);
+            return Ok(Box::new(data));
+        }
+
+        let json_str = self.transport.get(&url)?;
+        let data = dataportal_dayaheadprices::PriceData::new(&json_str)?;
+
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| ElspotError::IOError { path: self.dir.display().to_string(), source: e })?;
+        data.to_file(&path.display().to_string())?;
+        self.evict()?;
+
+        Ok(Box::new(data))
+    }
+
+    /// Removes the least recently used entries until there are at most `max_entries` left.
+    pub fn evict(&self) -> ElspotResult<()> {
+        let Some(max_entries) = self.max_entries else { return Ok(()) };
+
+        let mut entries = self.entries()?;
+        if entries.len() <= max_entries {
+            return Ok(());
+        }
+
+        entries.sort_by_key(|(_, modified)| *modified);
+        for (path, _) in entries.iter().take(entries.len() - max_entries) {
+            fs::remove_file(path)
+                .map_err(|e| ElspotError::IOError { path: path.display().to_string(), source: e })?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes all entries.
+    pub fn clear(&self) -> ElspotResult<()> {
+        for (path, _) in self.entries()? {
+            fs::remove_file(&path)
+                .map_err(|e| ElspotError::IOError { path: path.display().to_string(), source: e })?;
+        }
+
+        Ok(())
+    }
+
+    // Returns the cached prices if they exist and we are allowed to serve them.
+    fn read_fresh(&self, path: &Path) -> Option<dataportal_dayaheadprices::PriceData> {
+        let json_str = fs::read_to_string(path).ok()?;
+        // A broken entry is treated as missing and will be overwritten.
+        let data = dataportal_dayaheadprices::PriceData::new(&json_str).ok()?;
+
+        if data.is_known_final() {
+            return Some(data);
+        }
+
+        let ttl = chrono::Duration::from_std(self.ttl?).ok()?;
+        match data.updated_at() {
+            Some(updated_at) if Utc::now() - updated_at < ttl => Some(data),
+            _ => None,
+        }
+    }
+
+    fn entries(&self) -> ElspotResult<Vec<(PathBuf, SystemTime)>> {
+        let io_error = |e| ElspotError::IOError { path: self.dir.display().to_string(), source: e };
+
+        let read_dir = match fs::read_dir(&self.dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(io_error(e)),
+        };
+
+        let mut entries = vec![];
+        for entry in read_dir {
+            let entry = entry.map_err(io_error)?;
+            let path = entry.path();
+            let is_entry = path.extension().is_some_and(|ext| ext == "json")
+                && path.file_name().is_some_and(|name| name.to_string_lossy().starts_with(MARKET));
+            if is_entry {
+                let modified = entry.metadata().and_then(|m| m.modified()).map_err(io_error)?;
+                entries.push((path, modified));
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+// Marks the entry as recently used for eviction, failing to do so is not worth an error.
+fn touch(path: &Path) {
+    if let Ok(file) = fs::File::options().append(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
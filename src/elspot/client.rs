@@ -0,0 +1,287 @@
+//! A configurable (blocking) client for the Nord Pool data portal.
+//!
+//! Unlike `elspot::from_nordpool`, which uses reqwest defaults against `NORDPOOL_BASE_URL`,
+//! the client lets us set base url (e.g. a caching proxy), timeout, retry policy, user agent and headers.
+//! It implements `Transport`, so it can be combined with `FileTransport` and the other `*_with` functions.
+
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER, USER_AGENT};
+
+use crate::error::{
+    ElspotError,
+    ElspotResult,
+};
+
+use super::dataportal_dayaheadprices::query::NORDPOOL_BASE_URL;
+use super::range::{PriceRange, fetch_range_with};
+use super::transport::Transport;
+use super::{PriceExtractor, from_url_with, marketdata_page_10, nordpool_url};
+
+const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// How many times, and how long to wait between, retries of requests that fail with a retryable error.
+/// See `ElspotError::is_retryable`.
+///
+/// The wait before retry `n` (starting at 0) is `initial_backoff * 2^n`, capped at `max_backoff`.
+/// With `jitter` enabled, a random wait between half of that and all of it is used instead,
+/// so that clients started at the same time (e.g. at the 12:45 CET publication) spread out their retries.
+/// When a 429 or 503 response has a `Retry-After` header, the wait it asks for is used instead, also capped at `max_backoff`.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Never retry.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the wait before retry number `retry` (starting at 0).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry);
+        let backoff = self.initial_backoff.saturating_mul(factor).min(self.max_backoff);
+
+        if self.jitter {
+            let half = backoff / 2;
+            half + half.mul_f64(random_fraction())
+        } else {
+            backoff
+        }
+    }
+
+    /// Returns the wait a `Retry-After` header asks for, in seconds or until an HTTP date (as of `now`),
+    /// capped at `max_backoff`. `None` if `value` is neither.
+    pub fn retry_after(&self, value: &str, now: DateTime<Utc>) -> Option<Duration> {
+        let value = value.trim();
+        let wait = match value.parse::<u64>() {
+            Ok(seconds) => Duration::from_secs(seconds),
+            Err(_) => {
+                let date = DateTime::parse_from_rfc2822(value).ok()?;
+                // A date in the past means no wait.
+                (date.to_utc() - now).to_std().unwrap_or(Duration::ZERO)
+            }
+        };
+
+        Some(wait.min(self.max_backoff))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ClientBuilder {
+    base_url: String,
+    timeout: Option<Duration>,
+    retry_policy: RetryPolicy,
+    user_agent: String,
+    headers: Vec<(String, String)>,
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        Self {
+            base_url: NORDPOOL_BASE_URL.to_string(),
+            timeout: Some(Duration::from_secs(30)),
+            retry_policy: RetryPolicy::default(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            headers: vec![],
+        }
+    }
+
+    /// Url for the DayAheadPrices endpoint, defaults to `NORDPOOL_BASE_URL`.
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Timeout for each request (attempt), `None` disables it.
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Adds a header that is sent with every request.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn build(self) -> ElspotResult<Client> {
+        if let Err(e) = url::Url::parse(&self.base_url) {
+            return Err(ElspotError::InvalidUrl { url: self.base_url, source: e });
+        }
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in self.headers.iter() {
+            let invalid = || ElspotError::InvalidHttpHeader { name: name.clone(), value: value.clone() };
+            let header_name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?;
+            let header_value = HeaderValue::from_str(value).map_err(|_| invalid())?;
+            headers.append(header_name, header_value);
+        }
+        let user_agent = HeaderValue::from_str(&self.user_agent)
+            .map_err(|_| ElspotError::InvalidHttpHeader { name: USER_AGENT.to_string(), value: self.user_agent.clone() })?;
+        headers.insert(USER_AGENT, user_agent);
+
+        let mut builder = reqwest::blocking::Client::builder().default_headers(headers);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        let http = builder.build().map_err(ElspotError::HttpClientBuildFailed)?;
+
+        Ok(Client {
+            http,
+            base_url: self.base_url,
+            retry_policy: self.retry_policy,
+        })
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Client {
+    http: reqwest::blocking::Client,
+    base_url: String,
+    retry_policy: RetryPolicy,
+}
+
+impl Client {
+    /// Client with default settings, same as `ClientBuilder::new().build()`.
+    pub fn new() -> ElspotResult<Self> {
+        ClientBuilder::new().build()
+    }
+
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Same as `elspot::from_nordpool`, but against the clients base url.
+    pub fn from_nordpool(&self, currency: &str, date: &str, regions: &[&str]) -> ElspotResult<Box<dyn PriceExtractor>> {
+        let url = self.nordpool_url(currency, date, regions)?;
+
+        from_url_with(self, &url)
+    }
+
+    /// Same as `elspot::range::fetch_range`, with at most `concurrency` requests in flight.
+    pub fn fetch_range(
+        &self,
+        currency: &str,
+        start: &str,
+        end: &str,
+        regions: &[&str],
+        concurrency: usize,
+    ) -> ElspotResult<PriceRange> {
+        fetch_range_with(self, &self.base_url, currency, start, end, regions, concurrency)
+    }
+
+    /// Returns the url that `from_nordpool` requests.
+    pub fn nordpool_url(&self, currency: &str, date: &str, regions: &[&str]) -> ElspotResult<String> {
+        nordpool_url(&self.base_url, currency, date, regions)
+    }
+
+    /// Same as `elspot::from_url`.
+    pub fn from_url(&self, url: &str) -> ElspotResult<Box<dyn PriceExtractor>> {
+        from_url_with(self, url)
+    }
+
+    /// Same as `elspot::marketdata_page_10::from_url`.
+    pub fn marketdata_page_10_from_url(&self, url: &str) -> ElspotResult<marketdata_page_10::PriceData> {
+        marketdata_page_10::from_url_with(self, url)
+    }
+
+    // On error, also returns the wait from `Retry-After` if the server asked us to come back later.
+    fn get_once(&self, url: &str) -> Result<String, (ElspotError, Option<Duration>)> {
+        match self.http.get(url).send() {
+            Ok(r) if !r.status().is_success() => {
+                let retry_after = match r.status() {
+                    StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => r
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| self.retry_policy.retry_after(v, Utc::now())),
+                    _ => None,
+                };
+                Err((ElspotError::HttpStatus { url: url.to_string(), status: r.status().as_u16() }, retry_after))
+            }
+            Ok(r) => {
+                match r.text() {
+                    Ok(s) => Ok(s),
+                    Err(e) => Err((ElspotError::InvalidHttpResponse { url: url.to_string(), source: e }, None)),
+                }
+            }
+            Err(e) => Err((ElspotError::HttpRequestFailed { url: url.to_string(), source: e }, None)),
+        }
+    }
+}
+
+impl Transport for Client {
+    fn get(&self, url: &str) -> ElspotResult<String> {
+        let mut retry = 0;
+        loop {
+            match self.get_once(url) {
+                Err((e, retry_after)) if e.is_retryable() && retry < self.retry_policy.max_retries => {
+                    thread::sleep(retry_after.unwrap_or_else(|| self.retry_policy.backoff(retry)));
+                    retry += 1;
+                }
+                res => return res.map_err(|(e, _)| e),
+            }
+        }
+    }
+}
+
+// Returns a number in the range [0, 1), good enough for spreading out retries (not for anything else).
+fn random_fraction() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos());
+
+    // xorshift to spread the bits of the nanoseconds.
+    let mut x = u64::from(nanos) ^ 0x9E37_79B9_7F4A_7C15;
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+
+    (x >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,129 @@
+//! The price right now, and the prices for today and tomorrow, for a region.
+//!
+//! "Now" comes from a `Clock`, so that tests can freeze time (e.g. on DST transition days).
+//! Today and tomorrow are calendar days in the timezone of the region (see `local_day`), while the price right now
+//! is looked up in the delivery day (CET) that contains the current time.
+//! A day that is not published yet (Nord Pool publishes tomorrow around 12:45 CET) is returned as `None`.
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use chrono_tz::CET;
+
+use crate::clock::Clock;
+use crate::error::{
+    ElspotError,
+    ElspotResult,
+};
+use crate::region_time::tz_from_region;
+
+use super::local_day::{self, fetch_day};
+use super::transport::Transport;
+use super::{Price, PriceExtractor};
+
+/// Returns the calendar date of now in the timezone of `region`.
+pub fn local_date<C: Clock>(clock: &C, region: &str) -> ElspotResult<NaiveDate> {
+    let tz = tz_from_region(region)?;
+
+    Ok(clock.now().with_timezone(&tz).date_naive())
+}
+
+/// Returns the delivery day (defined in CET) that contains `dt`.
+pub fn delivery_date(dt: DateTime<Utc>) -> NaiveDate {
+    dt.with_timezone(&CET).date_naive()
+}
+
+/// Returns the price in `data` for `region` that is active at `dt`.
+pub fn price_at(data: &dyn PriceExtractor, region: &str, dt: DateTime<Utc>) -> ElspotResult<Option<Price>> {
+    Ok(data
+        .extract_prices_for_region(region)?
+        .into_iter()
+        .find(|p| p.from <= dt && dt < p.to))
+}
+
+/// Returns the price in `data` for `region` that is active now.
+pub fn price_now_in<C: Clock>(data: &dyn PriceExtractor, clock: &C, region: &str) -> ElspotResult<Option<Price>> {
+    price_at(data, region, clock.now())
+}
+
+/// Returns the prices in `datasets` for `region` for today in the timezone of the region, see `local_day::prices_in`.
+pub fn today_in<C: Clock>(datasets: &[&dyn PriceExtractor], clock: &C, region: &str) -> ElspotResult<Option<Vec<Price>>> {
+    local_day::prices_in(datasets, region, local_date(clock, region)?)
+}
+
+/// Returns the prices in `datasets` for `region` for tomorrow in the timezone of the region, see `local_day::prices_in`.
+pub fn tomorrow_in<C: Clock>(datasets: &[&dyn PriceExtractor], clock: &C, region: &str) -> ElspotResult<Option<Vec<Price>>> {
+    local_day::prices_in(datasets, region, next_day(local_date(clock, region)?)?)
+}
+
+/// Fetches the delivery day that contains now and returns the active price for `region`.
+pub fn price_now_with<T: Transport, C: Clock>(
+    transport: &T,
+    base_url: &str,
+    clock: &C,
+    currency: &str,
+    region: &str,
+) -> ElspotResult<Option<Price>> {
+    let now = clock.now();
+
+    match fetch_day(transport, base_url, currency, delivery_date(now), region)? {
+        Some(data) => price_at(data.as_ref(), region, now),
+        None => Ok(None),
+    }
+}
+
+/// Fetches the prices for `region` for today in the timezone of the region.
+pub fn today_with<T: Transport, C: Clock>(
+    transport: &T,
+    base_url: &str,
+    clock: &C,
+    currency: &str,
+    region: &str,
+) -> ElspotResult<Option<Vec<Price>>> {
+    let date = local_date(clock, region)?;
+
+    local_day::prices_with(transport, base_url, currency, region, date)
+}
+
+/// Fetches the prices for `region` for tomorrow in the timezone of the region, `None` if they are not published yet.
+pub fn tomorrow_with<T: Transport, C: Clock>(
+    transport: &T,
+    base_url: &str,
+    clock: &C,
+    currency: &str,
+    region: &str,
+) -> ElspotResult<Option<Vec<Price>>> {
+    let date = next_day(local_date(clock, region)?)?;
+
+    local_day::prices_with(transport, base_url, currency, region, date)
+}
+
+/// Same as `price_now_with`, using the system clock and reqwest defaults against `NORDPOOL_BASE_URL`.
+#[cfg(feature = "blocking")]
+pub fn price_now(currency: &str, region: &str) -> ElspotResult<Option<Price>> {
+    use super::{dataportal_dayaheadprices::query::NORDPOOL_BASE_URL, transport::ReqwestTransport};
+    use crate::clock::SystemClock;
+
+    price_now_with(&ReqwestTransport, NORDPOOL_BASE_URL, &SystemClock, currency, region)
+}
+
+/// Same as `today_with`, using the system clock and reqwest defaults against `NORDPOOL_BASE_URL`.
+#[cfg(feature = "blocking")]
+pub fn today(currency: &str, region: &str) -> ElspotResult<Option<Vec<Price>>> {
+    use super::{dataportal_dayaheadprices::query::NORDPOOL_BASE_URL, transport::ReqwestTransport};
+    use crate::clock::SystemClock;
+
+    today_with(&ReqwestTransport, NORDPOOL_BASE_URL, &SystemClock, currency, region)
+}
+
+/// Same as `tomorrow_with`, using the system clock and reqwest defaults against `NORDPOOL_BASE_URL`.
+#[cfg(feature = "blocking")]
+pub fn tomorrow(currency: &str, region: &str) -> ElspotResult<Option<Vec<Price>>> {
+    use super::{dataportal_dayaheadprices::query::NORDPOOL_BASE_URL, transport::ReqwestTransport};
+    use crate::clock::SystemClock;
+
+    tomorrow_with(&ReqwestTransport, NORDPOOL_BASE_URL, &SystemClock, currency, region)
+}
+
+// Only fails for the last date chrono can represent.
+fn next_day(date: NaiveDate) -> ElspotResult<NaiveDate> {
+    date.checked_add_days(Days::new(1)).ok_or(ElspotError::InvalidInputData)
+}
@@ -1,4 +1,4 @@
-use std::{fs, fmt};
+use std::fmt;
 use std::collections::HashMap;
 
 use chrono::{
@@ -14,21 +14,25 @@ use crate::error::{
     ElspotError,
     ElspotResult,
 };
+use crate::exchange::RateTable;
+use crate::price_value::{PriceValue, json_number};
 use crate::units;
 
-use super::{PriceExtractor, Price};
+use super::{PriceExtractor, Price, write_json_file};
+use aggregates::{AreaAverage, BlockAggregate, BlockName};
 
+pub mod aggregates;
 pub mod currencies;
 pub mod regions;
 pub mod query;
-
-mod states;
+pub mod states;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-struct AreaAverage {
+struct AreaAverageEntry {
     area_code: regions::Region,
-    price: Option<f32>,
+    #[serde(with = "json_number::option")]
+    price: Option<PriceValue>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -41,9 +45,12 @@ struct AreaState {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct Aggregate {
-    average: f32,
-    min: f32,
-    max: f32,
+    #[serde(with = "json_number")]
+    average: PriceValue,
+    #[serde(with = "json_number")]
+    min: PriceValue,
+    #[serde(with = "json_number")]
+    max: PriceValue,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -51,7 +58,8 @@ struct Aggregate {
 struct AreaEntries {
     delivery_start: DateTime<Utc>,
     delivery_end: DateTime<Utc>,
-    entry_per_area: HashMap<String, f32>
+    #[serde(with = "json_number::map")]
+    entry_per_area: HashMap<String, PriceValue>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -68,14 +76,17 @@ struct PriceAggregates {
 pub struct PriceData {
     delivery_date_c_e_t: NaiveDate,
     version: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<DateTime<Utc>>,
     delivery_areas: Vec<String>,
     market: String,
     multi_area_entries: Vec<AreaEntries>,
     block_price_aggregates: Vec<PriceAggregates>,
     currency: currencies::Currency,
-    exchange_rate: f32,
+    #[serde(with = "json_number")]
+    exchange_rate: PriceValue,
     area_states: Vec<AreaState>,
-    area_averages: Vec<AreaAverage>,
+    area_averages: Vec<AreaAverageEntry>,
 }
 
 impl PriceExtractor for PriceData {
@@ -84,20 +95,16 @@ impl PriceExtractor for PriceData {
             Ok(data) => {
                 // FIXME:: do we need these code blocks below?
                 // if data.version < 1 || data.version > 3 {
-                //     return Err(ElspotError::DataPortalDayaheadPricesInvalidVersion);
+                //     return Err(ElspotError::DataPortalDayaheadPricesInvalidVersion(data.version));
                 // }
 
                 // if data.market != "DayAhead" {
-                //     return Err(ElspotError::DataPortalDayaheadPricesInvalidMarket);
+                //     return Err(ElspotError::DataPortalDayaheadPricesInvalidMarket(data.market));
                 // }
 
                 Ok(data)
             }
-            Err(_e) => {
-                // FIXME: show this error if user wants to..
-                eprintln!("{_e}");
-                Err(ElspotError::DataPortalDayaheadPricesInvalidJson)
-            }
+            Err(e) => Err(ElspotError::DataPortalDayaheadPricesInvalidJson(e)),
         }
     }
 
@@ -123,18 +130,21 @@ impl PriceExtractor for PriceData {
 
     fn print_regions(&self) {
         println!("Available regions:");
-        for r in self.multi_area_entries[0].entry_per_area.iter() {
-            println!("{}", r.0);
+        for r in self.regions() {
+            println!("{}", r);
         }
         println!();
 
     }
 
     fn regions(&self) -> Vec<&str> {
-        self.multi_area_entries[0].entry_per_area
-            .iter()
-            .map(|v| v.0.as_ref())
-            .collect()
+        match self.multi_area_entries.first() {
+            Some(entries) => entries.entry_per_area
+                .iter()
+                .map(|v| v.0.as_ref())
+                .collect(),
+            None => vec![],
+        }
     }
 
     fn has_region(&self, region: &str) -> bool {
@@ -155,55 +165,198 @@ impl PriceExtractor for PriceData {
         self.delivery_date_c_e_t
     }
 
-    fn extract_prices_for_region(&self, region: &str) -> Vec<Price> {
+    fn extract_prices_for_region(&self, region: &str) -> ElspotResult<Vec<Price>> {
         if !self.has_region(region) {
-            return vec![];
+            return Ok(vec![]);
         }
+        let area: regions::Region = region.parse()?;
+
+        let unit_error = |e| ElspotError::InvalidUnit {
+            region: region.to_string(),
+            date: self.delivery_date_c_e_t,
+            source: e,
+        };
 
         let mut prices: Vec<Price> = Vec::with_capacity(100);
         for e in self.multi_area_entries.iter() {
-            let v = e.entry_per_area[region].to_string();
+            let v = e.entry_per_area[region];
 
-            let cu = units::Currency::new(&self.currency.to_string()).unwrap_or_else(|e| panic!("{}", e));
-            let pu = units::Power::new("MWh").unwrap_or_else(|e| panic!("{}", e));
-            let mtu = units::Mtu::new(e.delivery_start, e.delivery_end).unwrap_or_else(|e| panic!("{}", e));
+            let (cu, pu) = self.units_for_region(region)?;
+            let mtu = units::Mtu::new(e.delivery_start, e.delivery_end).map_err(unit_error)?;
 
             let p = Price {
                 value: v,
                 from: e.delivery_start,
                 to: e.delivery_end,
                 date: self.delivery_date_c_e_t,
-                region: region.to_string(),
+                region: area,
                 currency_unit: cu,
                 market_time_unit: mtu,
                 power_unit: pu,
+                exchange_rate: None,
             };
 
             prices.push(p);
         }
 
-        prices
+        Ok(prices)
     }
 
-    fn extract_prices_all_regions(&self) -> Vec<Vec<Price>> {
+    fn extract_prices_all_regions(&self) -> ElspotResult<Vec<Vec<Price>>> {
         let mut prices_all: Vec<Vec<Price>> = vec![];
         for region in self.delivery_areas.iter() {
-            let prices = self.extract_prices_for_region(region);
+            let prices = self.extract_prices_for_region(region)?;
             if !prices.is_empty() {
                 prices_all.push(prices);
             }
         }
 
-        prices_all
+        Ok(prices_all)
+    }
+
+    fn to_json_string(&self) -> ElspotResult<String> {
+        serde_json::to_string(&self).map_err(ElspotError::JsonSerializationFailed)
+    }
+
+    fn to_file(&self, path: &str) -> ElspotResult<()> {
+        let s = self.to_json_string()?;
+
+        write_json_file(path, &s)
+    }
+
+    fn as_dataportal(&self) -> Option<&PriceData> {
+        Some(self)
+    }
+}
+
+impl PriceData {
+    /// Returns when Nord Pool last updated the prices (missing in data saved by older versions of this crate).
+    pub fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
+
+    /// Returns the version of the prices, Nord Pool increments it when they are republished.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Returns the market, e.g. "DayAhead".
+    pub fn market(&self) -> &str {
+        &self.market
+    }
+
+    /// Returns the exchange rate Nord Pool used to convert from EUR, the price of 1 EUR in `currency()`.
+    pub fn exchange_rate(&self) -> PriceValue {
+        self.exchange_rate
+    }
+
+    /// Returns the exchange rate as a `RateTable` for the delivery day, to convert prices with `exchange::convert`.
+    pub fn rate_table(&self) -> ElspotResult<RateTable> {
+        let rate = self.exchange_rate();
+        let mut table = RateTable::new();
+        table.insert(self.delivery_date_c_e_t, self.currency, rate)?;
+
+        Ok(table)
+    }
+
+    /// Same as `is_final`, but false when there are no area states at all, which `is_final` treats as final.
+    /// Use this to decide whether prices can be kept without fetching them again.
+    pub fn is_known_final(&self) -> bool {
+        !self.area_states.is_empty() && self.is_final()
+    }
+
+    /// Returns the state (Final or Preliminary) of the prices for region, `None` if region has no state.
+    pub fn state_for_region(&self, region: &str) -> Option<states::State> {
+        self.area_states
+            .iter()
+            .find(|s| s.areas.iter().any(|r| *r == region))
+            .map(|s| s.state)
+    }
+
+    /// Returns the state for every region, in the order of `area_states`.
+    pub fn region_states(&self) -> Vec<(regions::Region, states::State)> {
+        self.area_states
+            .iter()
+            .flat_map(|s| s.areas.iter().map(|r| (*r, s.state)))
+            .collect()
+    }
+
+    /// Returns min, average and max for every block of the day (e.g. "Peak") for region, in time ascending order.
+    pub fn block_aggregates_for_region(&self, region: &str) -> ElspotResult<Vec<BlockAggregate>> {
+        let mut aggregates = vec![];
+        for block in self.block_price_aggregates.iter() {
+            let Some(a) = block.average_price_per_area.get(region) else { continue };
+            let area: regions::Region = region.parse()?;
+            let (currency_unit, power_unit) = self.units_for_region(region)?;
+
+            aggregates.push(BlockAggregate {
+                block: BlockName::new(&block.block_name),
+                from: block.delivery_start,
+                to: block.delivery_end,
+                date: self.delivery_date_c_e_t,
+                region: area,
+                min: a.min,
+                average: a.average,
+                max: a.max,
+                currency_unit,
+                power_unit,
+            });
+        }
+        aggregates.sort_by_key(|a| a.from);
+
+        Ok(aggregates)
+    }
+
+    /// Same as `block_aggregates_for_region` for all regions, one Vec per region.
+    pub fn block_aggregates_all_regions(&self) -> ElspotResult<Vec<Vec<BlockAggregate>>> {
+        let mut aggregates_all = vec![];
+        for region in self.delivery_areas.iter() {
+            let aggregates = self.block_aggregates_for_region(region)?;
+            if !aggregates.is_empty() {
+                aggregates_all.push(aggregates);
+            }
+        }
+
+        Ok(aggregates_all)
+    }
+
+    /// Returns the day average for region, `None` if region is not in the data.
+    pub fn area_average_for_region(&self, region: &str) -> ElspotResult<Option<AreaAverage>> {
+        match self.area_averages.iter().find(|a| a.area_code == region) {
+            Some(a) => Ok(Some(self.area_average(a)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the day average for every region.
+    pub fn area_averages(&self) -> ElspotResult<Vec<AreaAverage>> {
+        self.area_averages.iter().map(|a| self.area_average(a)).collect()
     }
 
-    fn to_json_string(&self) -> String {
-        serde_json::to_string(&self).unwrap_or_else(|e| panic!("{}", e))
+    fn area_average(&self, a: &AreaAverageEntry) -> ElspotResult<AreaAverage> {
+        let region = a.area_code.as_str();
+        let (currency_unit, power_unit) = self.units_for_region(region)?;
+        Ok(AreaAverage {
+            date: self.delivery_date_c_e_t,
+            region: a.area_code,
+            value: a.price,
+            currency_unit,
+            power_unit,
+        })
     }
 
-    fn to_file(&self, path: &str) {
-        let s = serde_json::to_string(&self).unwrap_or_else(|e| panic!("{}", e));
-        fs::write(path, s.as_bytes()).unwrap_or_else(|e| panic!("{}", e));
+    // Prices from the data portal are always in full currency unit per MWh.
+    fn units_for_region(&self, region: &str) -> ElspotResult<(units::Currency, units::Power)> {
+        let unit_error = |e| ElspotError::InvalidUnit {
+            region: region.to_string(),
+            date: self.delivery_date_c_e_t,
+            source: e,
+        };
+
+        Ok((
+            units::Currency::from_code(self.currency, units::CurrencyUnit::Full),
+            units::Power::new("MWh").map_err(unit_error)?,
+        ))
     }
 }
 
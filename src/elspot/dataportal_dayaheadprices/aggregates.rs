@@ -0,0 +1,69 @@
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::price_value::PriceValue;
+use crate::region::Region;
+use crate::units;
+
+/// Name of a block in `block_price_aggregates`, the day is split into "Off-peak 1", "Peak" and "Off-peak 2".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockName {
+    OffPeak1,
+    Peak,
+    OffPeak2,
+    /// Any block name we do not know about (yet).
+    Other(String),
+}
+
+impl BlockName {
+    pub fn new(name: &str) -> Self {
+        match name {
+            "Off-peak 1" => Self::OffPeak1,
+            "Peak" => Self::Peak,
+            "Off-peak 2" => Self::OffPeak2,
+            _ => Self::Other(name.to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::OffPeak1 => "Off-peak 1",
+            Self::Peak => "Peak",
+            Self::OffPeak2 => "Off-peak 2",
+            Self::Other(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for BlockName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Min, average and max price for one region in one block of the delivery day.
+#[derive(Debug, Clone)]
+pub struct BlockAggregate {
+    pub block: BlockName,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub date: NaiveDate,
+    pub region: Region,
+    pub min: PriceValue,
+    pub average: PriceValue,
+    pub max: PriceValue,
+    pub currency_unit: units::Currency,
+    pub power_unit: units::Power,
+}
+
+/// Average price for one region over the whole delivery day.
+#[derive(Debug, Clone)]
+pub struct AreaAverage {
+    pub date: NaiveDate,
+    pub region: Region,
+    /// `None` when Nord Pool has not published an average for the region.
+    pub value: Option<PriceValue>,
+    pub currency_unit: units::Currency,
+    pub power_unit: units::Power,
+}
@@ -1,37 +1,21 @@
-use std::fmt;
+/// Currencies are shared with prices, see `units::CurrencyCode`.
+pub use crate::units::CurrencyCode as Currency;
 
-use serde::{Deserialize, Serialize};
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub enum Currency {
-    BGN,
-    EUR,
-    DKK,
-    NOK,
-    PLN,
-    RON,
-    SEK,
-}
-
-impl fmt::Display for Currency {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+/// The codes of `Currency::ALL` as strings.
+pub const SUPPORTED_CURRENCIES: [&str; Currency::ALL.len()] = {
+    let mut codes = [""; Currency::ALL.len()];
+    let mut i = 0;
+    while i < codes.len() {
+        codes[i] = Currency::ALL[i].as_str();
+        i += 1;
     }
-}
 
-pub const SUPPORTED_CURRENCIES: [&str; 7] = [
-    "BGN",
-    "EUR",
-    "DKK",
-    "NOK",
-    "PLN",
-    "RON",
-    "SEK",
-];
+    codes
+};
 
 pub fn list_supported() {
     println!("Supported currencies");
-    for c in SUPPORTED_CURRENCIES {
+    for c in Currency::ALL {
         println!("'{}' ", c);
     }
     println!();
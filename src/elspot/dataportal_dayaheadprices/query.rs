@@ -1,15 +1,20 @@
 use chrono::NaiveDate;
 use url::Url;
 
-use super::currencies::SUPPORTED_CURRENCIES;
-use super::regions::SUPPORTED_REGIONS;
+use crate::error::{
+    ElspotError,
+    ElspotResult,
+};
 
-const NORDPOOL_BASE_URL: &str = "https://dataportal-api.nordpoolgroup.com/api/DayAheadPrices";
+use super::currencies::Currency;
+use super::regions::Region;
+
+pub const NORDPOOL_BASE_URL: &str = "https://dataportal-api.nordpoolgroup.com/api/DayAheadPrices";
 
 pub struct QueryOptions<'a> {
     currency: Option<&'a str>,
     date: Option<&'a str>,
-    regions: Vec<&'a str>,
+    regions: Vec<Region>,
 }
 
 impl <'a>QueryOptions<'a> {
@@ -21,64 +26,83 @@ impl <'a>QueryOptions<'a> {
         }
     }
 
-    pub fn set_currency(&mut self, currency: &'a str) {
-        if !SUPPORTED_CURRENCIES.contains(&currency) {
-            let supported = SUPPORTED_CURRENCIES
-                .iter()
-                .map(|v| format!("\n{}", v))
-                .collect::<String>();
-
-            panic!("'{}' is not a supported currency, use one of:{}", currency, supported);
+    pub fn set_currency(&mut self, currency: &'a str) -> ElspotResult<()> {
+        if currency.parse::<Currency>().is_err() {
+            return Err(ElspotError::DataPortalDayaheadPricesUnsupportedCurrency(currency.to_string()));
         }
         self.currency = Some(currency);
+
+        Ok(())
     }
 
-    pub fn set_date(&mut self, date: &'a str) {
+    pub fn set_date(&mut self, date: &'a str) -> ElspotResult<()> {
         if let Err(e) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
-            panic!("{}: '{}' is not a valid date, use the format '%Y-%m-%d' for example: '2018-01-26'", e, date);
+            return Err(ElspotError::DataPortalDayaheadPricesInvalidDate { date: date.to_string(), source: e });
         }
-
         self.date = Some(date);
+
+        Ok(())
     }
 
-    pub fn set_regions(&mut self, regions: &[&'a str]) {
+    /// Same as `add_regions`, for area codes (e.g. "NO3"), legacy names (e.g. "Tr.heim") are not supported by the data portal.
+    pub fn set_regions(&mut self, regions: &[&str]) -> ElspotResult<()> {
+        let mut areas = Vec::with_capacity(regions.len());
         for region in regions.iter() {
-            if !SUPPORTED_REGIONS.contains(&region) {
-                let supported = SUPPORTED_REGIONS
-                    .iter()
-                    .map(|v| format!("{}\n", v))
-                    .collect::<String>();
-
-                panic!("'{}' is not a supported region, use any of the regions listed below\n{}", region, supported);
+            match Region::from_code(region) {
+                Some(area) => areas.push(area),
+                None => return Err(ElspotError::DataPortalDayaheadPricesUnsupportedRegion(region.to_string())),
             }
+        }
+        self.add_regions(&areas);
+
+        Ok(())
+    }
+
+    /// Adds `regions` to the query, regions that are already added are skipped.
+    pub fn add_regions(&mut self, regions: &[Region]) {
+        for region in regions.iter() {
             if !self.regions.contains(region) {
-                self.regions.push(region);
+                self.regions.push(*region);
             }
         }
     }
 
-    pub fn build_url(&self) -> String {
+    pub fn build_url(&self) -> ElspotResult<String> {
+        self.build_url_with_base(NORDPOOL_BASE_URL)
+    }
+
+    /// Same as `build_url`, but for another endpoint than `NORDPOOL_BASE_URL`, e.g. a caching proxy.
+    pub fn build_url_with_base(&self, base_url: &str) -> ElspotResult<String> {
         let date = match self.date {
-            None => panic!("No date was set"),
+            None => return Err(ElspotError::DataPortalDayaheadPricesMissingDate),
             Some(date) => date,
         };
 
         let currency = match self.currency {
-            None => panic!("No currency was set"),
+            None => return Err(ElspotError::DataPortalDayaheadPricesMissingCurrency),
             Some(currency) => currency,
         };
 
         let regions = match self.regions.is_empty() {
-            true => panic!("No regions where added"),
-            false => self.regions.join(","),
+            true => return Err(ElspotError::DataPortalDayaheadPricesNoRegionsSupplied),
+            false => self.regions.iter().map(|r| r.as_str()).collect::<Vec<_>>().join(","),
         };
 
-        let mut url = Url::parse(NORDPOOL_BASE_URL).unwrap();
+        let mut url = match Url::parse(base_url) {
+            Ok(url) => url,
+            Err(e) => return Err(ElspotError::InvalidUrl { url: base_url.to_string(), source: e }),
+        };
         url.query_pairs_mut().append_pair("market", "DayAhead");
         url.query_pairs_mut().append_pair("currency", currency);
         url.query_pairs_mut().append_pair("date", date);
         url.query_pairs_mut().append_pair("deliveryArea", regions.as_ref());
 
-        url.as_str().to_string()
+        Ok(url.as_str().to_string())
+    }
+}
+
+impl Default for QueryOptions<'_> {
+    fn default() -> Self {
+        Self::new()
     }
 }
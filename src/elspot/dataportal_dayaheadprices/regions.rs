@@ -1,49 +1,10 @@
 use std::fmt;
 
-use serde::{Deserialize, Serialize};
+use chrono_tz::Tz;
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
-pub enum Region {
-    // Baltic
-    EE,
-    LT,
-    LV,
-
-    // Central Western Europe
-    AT,
-    BE,
-    FR,
-    GER,
-    NL,
-    PL,
-
-    // Nordic
-    DK1,
-    DK2,
-    FI,
-    NO1,
-    NO2,
-    NO3,
-    NO4,
-    NO5,
-    SE1,
-    SE2,
-    SE3,
-    SE4,
-
-    // South East Europe (SEE)
-    BG,
-    TEL,
+pub use crate::region::Region;
 
-    // System
-    SYS,
-}
-
-impl fmt::Display for Region {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
-    }
-}
+use super::currencies::Currency;
 
 pub const SUPPORTED_REGIONS: [&str; 24] = [
     // Baltic
@@ -81,10 +42,344 @@ pub const SUPPORTED_REGIONS: [&str; 24] = [
     "SYS",
 ];
 
+/// Market group of a region, the grouping used in `SUPPORTED_REGIONS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MarketGroup {
+    Baltic,
+    CentralWesternEurope,
+    Nordic,
+    SouthEastEurope,
+    System,
+}
+
+impl MarketGroup {
+    pub fn of(region: Region) -> Self {
+        match region {
+            Region::EE | Region::LT | Region::LV => Self::Baltic,
+            Region::AT | Region::BE | Region::FR | Region::GER | Region::NL | Region::PL => Self::CentralWesternEurope,
+            Region::DK1 | Region::DK2 | Region::FI => Self::Nordic,
+            Region::NO1 | Region::NO2 | Region::NO3 | Region::NO4 | Region::NO5 => Self::Nordic,
+            Region::SE1 | Region::SE2 | Region::SE3 | Region::SE4 => Self::Nordic,
+            Region::BG | Region::TEL => Self::SouthEastEurope,
+            Region::SYS => Self::System,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Baltic => "Baltic",
+            Self::CentralWesternEurope => "Central Western Europe",
+            Self::Nordic => "Nordic",
+            Self::SouthEastEurope => "South East Europe",
+            Self::System => "System",
+        }
+    }
+}
+
+impl fmt::Display for MarketGroup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Metadata for a bidding zone (or the system price), see `zones`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Zone {
+    pub region: Region,
+    /// ISO 3166 country code, `None` for the system price.
+    pub country: Option<&'static str>,
+    /// English display name.
+    pub name: &'static str,
+    /// Display name in the (main) local language.
+    pub local_name: &'static str,
+    /// Energy Identification Code, `None` for the system price.
+    pub eic: Option<&'static str>,
+    /// Currency the zone is settled in.
+    pub currency: Currency,
+    /// Zones with an interconnection to this one, only zones in `SUPPORTED_REGIONS` are listed.
+    pub neighbours: &'static [Region],
+}
+
+impl Zone {
+    /// Area code, e.g. "NO3".
+    pub fn code(&self) -> &'static str {
+        self.region.as_str()
+    }
+
+    pub fn tz(&self) -> Tz {
+        self.region.tz()
+    }
+
+    pub fn group(&self) -> MarketGroup {
+        MarketGroup::of(self.region)
+    }
+}
+
+impl fmt::Display for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}, {})", self.code(), self.name, self.group())
+    }
+}
+
+// In the same order as `SUPPORTED_REGIONS` and the variants of `Region`.
+static ZONES: [Zone; 24] = [
+    Zone {
+        region: Region::EE,
+        country: Some("EE"),
+        name: "Estonia",
+        local_name: "Eesti",
+        eic: Some("10Y1001A1001A39I"),
+        currency: Currency::EUR,
+        neighbours: &[Region::FI, Region::LV],
+    },
+    Zone {
+        region: Region::LT,
+        country: Some("LT"),
+        name: "Lithuania",
+        local_name: "Lietuva",
+        eic: Some("10YLT-1001A0008Q"),
+        currency: Currency::EUR,
+        neighbours: &[Region::LV, Region::PL, Region::SE4],
+    },
+    Zone {
+        region: Region::LV,
+        country: Some("LV"),
+        name: "Latvia",
+        local_name: "Latvija",
+        eic: Some("10YLV-1001A00074"),
+        currency: Currency::EUR,
+        neighbours: &[Region::EE, Region::LT],
+    },
+    Zone {
+        region: Region::AT,
+        country: Some("AT"),
+        name: "Austria",
+        local_name: "Österreich",
+        eic: Some("10YAT-APG------L"),
+        currency: Currency::EUR,
+        neighbours: &[Region::GER],
+    },
+    Zone {
+        region: Region::BE,
+        country: Some("BE"),
+        name: "Belgium",
+        local_name: "België / Belgique",
+        eic: Some("10YBE----------2"),
+        currency: Currency::EUR,
+        neighbours: &[Region::FR, Region::GER, Region::NL],
+    },
+    Zone {
+        region: Region::FR,
+        country: Some("FR"),
+        name: "France",
+        local_name: "France",
+        eic: Some("10YFR-RTE------C"),
+        currency: Currency::EUR,
+        neighbours: &[Region::BE, Region::GER],
+    },
+    Zone {
+        region: Region::GER,
+        country: Some("DE"),
+        name: "Germany-Luxembourg",
+        local_name: "Deutschland-Luxemburg",
+        eic: Some("10Y1001A1001A82H"),
+        currency: Currency::EUR,
+        neighbours: &[Region::AT, Region::BE, Region::DK1, Region::DK2, Region::FR, Region::NL, Region::NO2, Region::PL, Region::SE4],
+    },
+    Zone {
+        region: Region::NL,
+        country: Some("NL"),
+        name: "Netherlands",
+        local_name: "Nederland",
+        eic: Some("10YNL----------L"),
+        currency: Currency::EUR,
+        neighbours: &[Region::BE, Region::DK1, Region::GER, Region::NO2],
+    },
+    Zone {
+        region: Region::PL,
+        country: Some("PL"),
+        name: "Poland",
+        local_name: "Polska",
+        eic: Some("10YPL-AREA-----S"),
+        currency: Currency::PLN,
+        neighbours: &[Region::GER, Region::LT, Region::SE4],
+    },
+    Zone {
+        region: Region::DK1,
+        country: Some("DK"),
+        name: "Western Denmark",
+        local_name: "Vestdanmark",
+        eic: Some("10YDK-1--------W"),
+        currency: Currency::DKK,
+        neighbours: &[Region::DK2, Region::GER, Region::NL, Region::NO2, Region::SE3],
+    },
+    Zone {
+        region: Region::DK2,
+        country: Some("DK"),
+        name: "Eastern Denmark",
+        local_name: "Østdanmark",
+        eic: Some("10YDK-2--------M"),
+        currency: Currency::DKK,
+        neighbours: &[Region::DK1, Region::GER, Region::SE4],
+    },
+    Zone {
+        region: Region::FI,
+        country: Some("FI"),
+        name: "Finland",
+        local_name: "Suomi",
+        eic: Some("10YFI-1--------U"),
+        currency: Currency::EUR,
+        neighbours: &[Region::EE, Region::NO4, Region::SE1, Region::SE3],
+    },
+    Zone {
+        region: Region::NO1,
+        country: Some("NO"),
+        name: "Eastern Norway",
+        local_name: "Øst-Norge",
+        eic: Some("10YNO-1--------2"),
+        currency: Currency::NOK,
+        neighbours: &[Region::NO2, Region::NO3, Region::NO5, Region::SE3],
+    },
+    Zone {
+        region: Region::NO2,
+        country: Some("NO"),
+        name: "Southern Norway",
+        local_name: "Sør-Norge",
+        eic: Some("10YNO-2--------T"),
+        currency: Currency::NOK,
+        neighbours: &[Region::DK1, Region::GER, Region::NL, Region::NO1, Region::NO5],
+    },
+    Zone {
+        region: Region::NO3,
+        country: Some("NO"),
+        name: "Central Norway",
+        local_name: "Midt-Norge",
+        eic: Some("10YNO-3--------J"),
+        currency: Currency::NOK,
+        neighbours: &[Region::NO1, Region::NO4, Region::NO5, Region::SE2],
+    },
+    Zone {
+        region: Region::NO4,
+        country: Some("NO"),
+        name: "Northern Norway",
+        local_name: "Nord-Norge",
+        eic: Some("10YNO-4--------9"),
+        currency: Currency::NOK,
+        neighbours: &[Region::FI, Region::NO3, Region::SE1, Region::SE2],
+    },
+    Zone {
+        region: Region::NO5,
+        country: Some("NO"),
+        name: "Western Norway",
+        local_name: "Vest-Norge",
+        eic: Some("10Y1001A1001A48H"),
+        currency: Currency::NOK,
+        neighbours: &[Region::NO1, Region::NO2, Region::NO3],
+    },
+    Zone {
+        region: Region::SE1,
+        country: Some("SE"),
+        name: "Luleå",
+        local_name: "Luleå",
+        eic: Some("10Y1001A1001A44P"),
+        currency: Currency::SEK,
+        neighbours: &[Region::FI, Region::NO4, Region::SE2],
+    },
+    Zone {
+        region: Region::SE2,
+        country: Some("SE"),
+        name: "Sundsvall",
+        local_name: "Sundsvall",
+        eic: Some("10Y1001A1001A45N"),
+        currency: Currency::SEK,
+        neighbours: &[Region::NO3, Region::NO4, Region::SE1, Region::SE3],
+    },
+    Zone {
+        region: Region::SE3,
+        country: Some("SE"),
+        name: "Stockholm",
+        local_name: "Stockholm",
+        eic: Some("10Y1001A1001A46L"),
+        currency: Currency::SEK,
+        neighbours: &[Region::DK1, Region::FI, Region::NO1, Region::SE2, Region::SE4],
+    },
+    Zone {
+        region: Region::SE4,
+        country: Some("SE"),
+        name: "Malmö",
+        local_name: "Malmö",
+        eic: Some("10Y1001A1001A47J"),
+        currency: Currency::SEK,
+        neighbours: &[Region::DK2, Region::GER, Region::LT, Region::PL, Region::SE3],
+    },
+    Zone {
+        region: Region::BG,
+        country: Some("BG"),
+        name: "Bulgaria",
+        local_name: "България",
+        eic: Some("10YCA-BULGARIA-R"),
+        currency: Currency::BGN,
+        neighbours: &[Region::TEL],
+    },
+    Zone {
+        region: Region::TEL,
+        country: Some("RO"),
+        name: "Romania",
+        local_name: "România",
+        eic: Some("10YRO-TEL------P"),
+        currency: Currency::RON,
+        neighbours: &[Region::BG],
+    },
+    Zone {
+        region: Region::SYS,
+        country: None,
+        name: "System price",
+        local_name: "System price",
+        eic: None,
+        currency: Currency::EUR,
+        neighbours: &[],
+    },
+];
+
+/// Returns metadata for all supported regions, in the order of `SUPPORTED_REGIONS`.
+pub fn zones() -> &'static [Zone] {
+    &ZONES
+}
+
+/// Returns metadata for `region`.
+pub fn zone(region: Region) -> &'static Zone {
+    // `ZONES` is in the same order as the variants of `Region`.
+    &ZONES[region as usize]
+}
+
+/// Looks up a zone by area code or legacy name (e.g. "NO3" or "Tr.heim").
+pub fn zone_by_code(code: &str) -> Option<&'static Zone> {
+    code.parse().ok().map(zone)
+}
+
+/// Looks up a zone by its Energy Identification Code (e.g. "10YNO-3--------J").
+pub fn zone_by_eic(eic: &str) -> Option<&'static Zone> {
+    ZONES.iter().find(|z| z.eic == Some(eic))
+}
+
+/// Returns the zones in a country by ISO 3166 code (e.g. "NO"), case insensitive.
+pub fn zones_by_country(country: &str) -> Vec<&'static Zone> {
+    ZONES
+        .iter()
+        .filter(|z| z.country.is_some_and(|c| c.eq_ignore_ascii_case(country)))
+        .collect()
+}
+
+/// Returns the zones in `group`.
+pub fn zones_in_group(group: MarketGroup) -> Vec<&'static Zone> {
+    ZONES.iter().filter(|z| z.group() == group).collect()
+}
+
+/// Prints all supported regions with their metadata, see `zones` to get them as data.
 pub fn list_supported() {
     println!("Supported regions");
-    for r in SUPPORTED_REGIONS {
-        println!("'{}' ", r);
+    for z in zones() {
+        println!("'{}' {} / {} ({}, {}, {})", z.code(), z.name, z.local_name, z.group(), z.currency, z.tz());
     }
     println!();
 }
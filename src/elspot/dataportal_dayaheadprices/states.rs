@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum State {
     Final,
     Preliminary,
@@ -14,4 +14,11 @@ impl State {
     pub fn is_preliminary(&self) -> bool {
         matches!(self, Self::Preliminary)
     }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Final => "Final",
+            Self::Preliminary => "Preliminary",
+        }
+    }
 }
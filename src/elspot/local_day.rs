@@ -0,0 +1,108 @@
+//! Prices for a calendar day in the timezone of a region.
+//!
+//! Nord Pool delivery days are defined in CET, which is the same as the calendar day for regions in CET (e.g. NO3 or DK1).
+//! For regions in UTC+2 (FI, EE, LV, LT, BG and TEL) a calendar day starts at 23:00 CET the day before,
+//! so it is assembled from the last hour of one delivery day and the rest of the next.
+
+use chrono::{DateTime, Days, Duration, NaiveDate, NaiveTime, Utc};
+use chrono_tz::CET;
+
+use crate::error::{
+    ElspotError,
+    ElspotResult,
+};
+use crate::region_time::dt_tz_from_naive_dt;
+
+use super::transport::Transport;
+use super::{Price, PriceExtractor, from_json, nordpool_url};
+
+/// Returns start and end (UTC) of `date` in the timezone of `region`, 23 or 25 hours on DST transition days.
+pub fn bounds(region: &str, date: NaiveDate) -> ElspotResult<(DateTime<Utc>, DateTime<Utc>)> {
+    let next = date.checked_add_days(Days::new(1)).ok_or(ElspotError::InvalidInputData)?;
+    // Midnight is never skipped or repeated by DST in the regions we support.
+    let start = dt_tz_from_naive_dt(date.and_time(NaiveTime::MIN), region)?;
+    let end = dt_tz_from_naive_dt(next.and_time(NaiveTime::MIN), region)?;
+
+    Ok((start.with_timezone(&Utc), end.with_timezone(&Utc)))
+}
+
+/// Returns the delivery days (CET) needed for `date` in the timezone of `region`, one or two.
+pub fn delivery_dates(region: &str, date: NaiveDate) -> ElspotResult<Vec<NaiveDate>> {
+    let (start, end) = bounds(region, date)?;
+    let first = start.with_timezone(&CET).date_naive();
+    let last = (end - Duration::seconds(1)).with_timezone(&CET).date_naive();
+
+    Ok(if first == last { vec![first] } else { vec![first, last] })
+}
+
+/// Returns the prices for `region` on `date` (local calendar day) from the delivery days in `datasets`.
+/// Returns `None` if the datasets do not cover the whole day, e.g. when a delivery day is missing.
+pub fn prices_in(datasets: &[&dyn PriceExtractor], region: &str, date: NaiveDate) -> ElspotResult<Option<Vec<Price>>> {
+    let (start, end) = bounds(region, date)?;
+
+    let mut prices = vec![];
+    for data in datasets.iter() {
+        prices.extend(
+            data.extract_prices_for_region(region)?
+                .into_iter()
+                .filter(|p| start <= p.from && p.to <= end),
+        );
+    }
+    prices.sort_by_key(|p| p.from);
+    // The same delivery day may be passed more than once.
+    prices.dedup_by_key(|p| p.from);
+
+    let contiguous = prices.windows(2).all(|w| w[0].to == w[1].from);
+    let complete = matches!((prices.first(), prices.last()), (Some(first), Some(last)) if first.from == start && last.to == end);
+    if !contiguous || !complete {
+        return Ok(None);
+    }
+
+    Ok(Some(prices))
+}
+
+/// Fetches the delivery days needed for `date` in the timezone of `region` and returns the prices for that day.
+/// Returns `None` if one of the delivery days is not published yet.
+pub fn prices_with<T: Transport>(
+    transport: &T,
+    base_url: &str,
+    currency: &str,
+    region: &str,
+    date: NaiveDate,
+) -> ElspotResult<Option<Vec<Price>>> {
+    let mut datasets = vec![];
+    for delivery_date in delivery_dates(region, date)? {
+        match fetch_day(transport, base_url, currency, delivery_date, region)? {
+            Some(data) => datasets.push(data),
+            None => return Ok(None),
+        }
+    }
+    let datasets: Vec<&dyn PriceExtractor> = datasets.iter().map(|d| d.as_ref()).collect();
+
+    prices_in(&datasets, region, date)
+}
+
+/// Same as `prices_with`, using reqwest defaults against `NORDPOOL_BASE_URL`.
+#[cfg(feature = "blocking")]
+pub fn prices(currency: &str, region: &str, date: NaiveDate) -> ElspotResult<Option<Vec<Price>>> {
+    use super::{dataportal_dayaheadprices::query::NORDPOOL_BASE_URL, transport::ReqwestTransport};
+
+    prices_with(&ReqwestTransport, NORDPOOL_BASE_URL, currency, region, date)
+}
+
+// Returns `None` when the delivery day is not published yet (the data portal answers with an empty body).
+pub(super) fn fetch_day<T: Transport>(
+    transport: &T,
+    base_url: &str,
+    currency: &str,
+    date: NaiveDate,
+    region: &str,
+) -> ElspotResult<Option<Box<dyn PriceExtractor>>> {
+    let url = nordpool_url(base_url, currency, &date.format("%Y-%m-%d").to_string(), &[region])?;
+    let json_str = transport.get(&url)?;
+    if json_str.trim().is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(from_json(&json_str)?))
+}
@@ -1,4 +1,4 @@
-use std::{fs, fmt};
+use std::fmt;
 
 use chrono::{
     DateTime,
@@ -15,20 +15,28 @@ use crate::error::{
     ElspotError,
     ElspotResult,
 };
+use crate::region::Region;
 use crate::region_time::dt_tz_from_naive_dt;
+use crate::price_value::PriceValue;
 use crate::units;
 
-use super::{PriceExtractor, Price};
+use super::{PriceExtractor, Price, write_json_file};
+use super::transport::Transport;
+#[cfg(feature = "blocking")]
+use super::transport::ReqwestTransport;
 
 mod hour_count;
 mod unit_string;
 
 use hour_count::HoursForDate;
 
+#[cfg(feature = "blocking")]
 pub fn from_url(url: &str) -> ElspotResult<PriceData> {
-    let r = reqwest::blocking::get(url).unwrap();
+    from_url_with(&ReqwestTransport, url)
+}
 
-    let json_str = r.text().unwrap();
+pub fn from_url_with<T: Transport>(transport: &T, url: &str) -> ElspotResult<PriceData> {
+    let json_str = transport.get(url)?;
 
     PriceData::new(&json_str)
 }
@@ -71,7 +79,7 @@ impl PriceExtractor for PriceData {
             Ok(data) => {
                 // Page id for hourly elspot is 10.
                 if data.page_id != 10 {
-                    return Err(ElspotError::MarketdataPage10InvalidPageId);
+                    return Err(ElspotError::MarketdataPage10InvalidPageId(data.page_id));
                 }
 
                 // Check if 'unit_string' is in the array.
@@ -80,18 +88,11 @@ impl PriceExtractor for PriceData {
                 }
 
                 // Test 'unit_string' to ensure it is valid.
-                let unit_string = &data.data.Units[0];
-                if let Err(e) = unit_string::test_unit_string(unit_string) {
-                    panic!("{}: '{}'", e, unit_string);
-                }
+                unit_string::test_unit_string(&data.data.Units[0])?;
 
                 Ok(data)
             }
-            Err(_e) => {
-                // FIXME: show this error if user wants to..
-                // eprintln!("{_e}");
-                Err(ElspotError::MarketdataPage10InvalidJson)
-            }
+            Err(e) => Err(ElspotError::MarketdataPage10InvalidJson(e)),
         }
     }
 
@@ -105,23 +106,21 @@ impl PriceExtractor for PriceData {
 
     fn print_regions(&self) {
         println!("Available regions:");
-        for col in &self.data.Rows[0].Columns {
-            println!("'{}' ", col.Name);
+        for region in self.regions() {
+            println!("'{}' ", region);
         }
         println!();
     }
 
     fn regions(&self) -> Vec<&str> {
-        self.data.Rows[0].Columns
+        self.columns()
             .iter()
             .map(|col| col.Name.as_ref())
             .collect()
     }
 
     fn has_region(&self, region: &str) -> bool {
-        let columns = &self.data.Rows[0].Columns;
-
-        let res: Option<&ColEntry> = columns
+        let res: Option<&ColEntry> = self.columns()
             .iter()
             .find(|v| v.Name == region);
 
@@ -136,25 +135,25 @@ impl PriceExtractor for PriceData {
         self.data.DataStartdate.date()
     }
 
-    fn extract_prices_for_region(&self, region: &str) -> Vec<Price> {
+    fn extract_prices_for_region(&self, region: &str) -> ElspotResult<Vec<Price>> {
         // Verify that the amount of prices extracted matches the amount of hours in that particular day.
-        let index: usize = match self.data.Rows[0].Columns.iter().find(|col| col.Name == region) {
+        let index: usize = match self.columns().iter().find(|col| col.Name == region) {
             None => {
                 eprintln!("Prices for {region} not found.");
-                return vec![];
+                return Ok(vec![]);
             }
             Some(entry) => entry.Index.into(),
         };
         let raw_prices: Vec<&ColEntry> = self.data.Rows
             .iter()
-            .filter(|row| !row.IsExtraRow && row.Columns[index].Value != "-")
-            .map(|row| &row.Columns[index])
+            .filter_map(|row| row.Columns.get(index).filter(|col| !row.IsExtraRow && col.Value != "-"))
             .collect();
         if raw_prices.is_empty() {
             // no prices where found, that is ok..
-            return vec![];
+            return Ok(vec![]);
         }
-        let hours_for_date = HoursForDate::new(self.date(), region);
+        let area: Region = region.parse()?;
+        let hours_for_date = HoursForDate::new(self.date(), region)?;
         let verified = match raw_prices.len() {
             23 => matches!(hours_for_date, HoursForDate::TwentyThree),
             25 => matches!(hours_for_date, HoursForDate::TwentyFive),
@@ -162,41 +161,59 @@ impl PriceExtractor for PriceData {
             _ => false,
         };
         if !verified {
-            eprintln!("FATAL: Non matching price count for {region}.");
-            eprintln!("Got '{}' prices, expected 23, 24 or 25", raw_prices.len());
-            eprintln!("Dumping data: 'raw_prices'\n{:?}", raw_prices);
-            panic!();
+            return Err(ElspotError::MarketdataPage10InvalidPriceCount {
+                region: region.to_string(),
+                date: self.date(),
+                count: raw_prices.len(),
+            });
         }
 
         // Now we can start assembling the real price data.
+        let unit_error = |e| ElspotError::InvalidUnit {
+            region: region.to_string(),
+            date: self.date(),
+            source: e,
+        };
+
         let cur_unit = unit_string::extract_currency_unit(&self.data.Units[0]);
-        let e_cur_unit = units::Currency::new(cur_unit).unwrap_or_else(|e| panic!("{}", e));
+        let e_cur_unit = units::Currency::new(cur_unit).map_err(unit_error)?;
 
         let pwr_unit = unit_string::extract_power_unit(&self.data.Units[0]);
-        let e_pwr_unit = units::Power::new(pwr_unit).unwrap_or_else(|e| panic!("{}", e));
+        let e_pwr_unit = units::Power::new(pwr_unit).map_err(unit_error)?;
 
         // Always 60 minutes for this old nordpool api.
         let mtu = units::Mtu::Sixty;
 
-        let mut start_time: DateTime<Tz> = dt_tz_from_naive_dt(self.data.Rows[0].StartTime, region);
-        let mut end_time: DateTime<Tz> = dt_tz_from_naive_dt(self.data.Rows[0].EndTime, region);
+        let mut start_time: DateTime<Tz> = dt_tz_from_naive_dt(self.data.Rows[0].StartTime, region)?;
+        let mut end_time: DateTime<Tz> = dt_tz_from_naive_dt(self.data.Rows[0].EndTime, region)?;
+
+        let price_error = |e| ElspotError::InvalidPrice {
+            region: region.to_string(),
+            date: self.date(),
+            source: e,
+        };
 
         let mut extr_prices: Vec<Price> = Vec::with_capacity(raw_prices.len());
 
         for price in raw_prices {
-            if region != "SYS" {
-                assert_eq!(self.date(), start_time.date_naive());
+            if region != "SYS" && self.date() != start_time.date_naive() {
+                return Err(ElspotError::MarketdataPage10DateMismatch {
+                    region: region.to_string(),
+                    date: self.date(),
+                    found: start_time.date_naive(),
+                });
             }
 
             let p = Price {
-                value: price.Value.to_string().replace(',', ".").replace(' ', ""),
+                value: price.Value.parse::<PriceValue>().map_err(price_error)?,
                 from: start_time.to_utc(),
                 to: end_time.to_utc(),
                 date: self.data.DataStartdate.date(),
-                region: region.to_string(),
+                region: area,
                 currency_unit: e_cur_unit.clone(),
                 market_time_unit: mtu,
                 power_unit: e_pwr_unit.clone(),
+                exchange_rate: None,
             };
 
             extr_prices.push(p);
@@ -205,25 +222,35 @@ impl PriceExtractor for PriceData {
             end_time += Duration::hours(1);
         }
 
-        extr_prices
+        Ok(extr_prices)
     }
 
-    fn extract_prices_all_regions(&self) -> Vec<Vec<Price>> {
+    fn extract_prices_all_regions(&self) -> ElspotResult<Vec<Vec<Price>>> {
         let mut prices_all: Vec<Vec<Price>> = vec![];
         for region in self.regions() {
-            prices_all.push(self.extract_prices_for_region(region));
+            prices_all.push(self.extract_prices_for_region(region)?);
         }
 
-        prices_all
+        Ok(prices_all)
+    }
+
+    fn to_json_string(&self) -> ElspotResult<String> {
+        serde_json::to_string(&self).map_err(ElspotError::JsonSerializationFailed)
     }
 
-    fn to_json_string(&self) -> String {
-        serde_json::to_string(&self).unwrap_or_else(|e| panic!("{}", e))
+    fn to_file(&self, path: &str) -> ElspotResult<()> {
+        let s = self.to_json_string()?;
+
+        write_json_file(path, &s)
     }
+}
 
-    fn to_file(&self, path: &str) {
-        let s = serde_json::to_string(&self).unwrap_or_else(|e| panic!("{}", e));
-        fs::write(path, s.as_bytes()).unwrap_or_else(|e| panic!("{}", e));
+impl PriceData {
+    fn columns(&self) -> &[ColEntry] {
+        match self.data.Rows.first() {
+            Some(row) => &row.Columns,
+            None => &[],
+        }
     }
 }
 
@@ -2,7 +2,8 @@ use chrono::{DateTime, NaiveDate, NaiveDateTime, Timelike, Duration};
 
 use chrono_tz::Tz;
 
-use crate::region_time::tz_from_region;
+use crate::error::RegionResult;
+use crate::region_time::dt_tz_from_naive_dt;
 
 const NORDPOOL_DEFAULT_TZ_REGION: &str = "Oslo";
 
@@ -12,6 +13,7 @@ const NORDPOOL_DEFAULT_TZ_REGION: &str = "Oslo";
 /// Due to weird structure of the dataset, it is not given how many hours we have..
 /// E.g., there are no clear indicators in the dataset to go after.
 /// We use region and some time calculations to determine this.
+#[allow(clippy::enum_variant_names)]
 pub enum HoursForDate {
     TwentyThree,
     TwentyFour,
@@ -19,22 +21,19 @@ pub enum HoursForDate {
 }
 
 impl HoursForDate {
-    pub fn new(d: NaiveDate, region: &str) -> Self {
+    pub fn new(d: NaiveDate, region: &str) -> RegionResult<Self> {
         let region = if region == "SYS" {
             NORDPOOL_DEFAULT_TZ_REGION
         } else {
             region
         };
 
-        let dt: DateTime<Tz> = match tz_from_region(region) {
-            Ok(tz) => NaiveDateTime::from(d).and_local_timezone(tz).unwrap(),
-            Err(e) => panic!("{:?}", e),
-        };
+        let dt: DateTime<Tz> = dt_tz_from_naive_dt(NaiveDateTime::from(d), region)?;
 
         match dt.with_hour(0) {
-            Some(dt) if (dt+Duration::hours(23)).hour() == 0 => Self::TwentyThree,
-            Some(dt) if (dt+Duration::hours(25)).hour() == 0 => Self::TwentyFive,
-            _ => Self::TwentyFour,
+            Some(dt) if (dt+Duration::hours(23)).hour() == 0 => Ok(Self::TwentyThree),
+            Some(dt) if (dt+Duration::hours(25)).hour() == 0 => Ok(Self::TwentyFive),
+            _ => Ok(Self::TwentyFour),
         }
     }
 }
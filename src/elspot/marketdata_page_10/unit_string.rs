@@ -2,20 +2,21 @@ use crate::error::{
     ElspotError,
     ElspotResult,
 };
+use crate::units;
 
-// The unit string is found somewhere inside the data-set from nordpool.
-const EXPECTED_UNIT_SRINGS: [&str; 4] = [
-    "EUR/MWh",
-    "DKK/MWh",
-    "NOK/MWh",
-    "SEK/MWh",
-];
-
+// The unit string is found somewhere inside the data-set from nordpool, e.g. "EUR/MWh".
 pub fn test_unit_string(unit_string: &str) -> ElspotResult<()> {
-    if EXPECTED_UNIT_SRINGS.contains(&unit_string) {
+    let valid = match unit_string.split_once('/') {
+        Some((currency, power)) => {
+            currency.parse::<units::CurrencyCode>().is_ok() && units::Power::new(power).is_ok_and(|p| p.is_mwh())
+        }
+        None => false,
+    };
+
+    if valid {
         Ok(())
     } else {
-        Err(ElspotError::MarketdataPage10InvalidUnitString)
+        Err(ElspotError::MarketdataPage10InvalidUnitString(unit_string.to_string()))
     }
 }
 
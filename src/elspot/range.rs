@@ -0,0 +1,152 @@
+//! Fetch day-ahead prices for a range of delivery days, one data portal request per day.
+//!
+//! A day that fails does not abort the range, it is reported in `PriceRange::failures`
+//! next to the prices for the days that succeeded.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use chrono::NaiveDate;
+
+use crate::error::{
+    ElspotError,
+    ElspotResult,
+};
+use crate::region::Region;
+
+use super::dataportal_dayaheadprices;
+use super::transport::Transport;
+use super::{Price, PriceExtractor, nordpool_url};
+
+/// A delivery day that could not be fetched or extracted.
+#[derive(Debug)]
+pub struct DayFailure {
+    pub date: NaiveDate,
+    pub error: ElspotError,
+}
+
+/// Prices for a range of delivery days.
+#[derive(Debug, Default)]
+pub struct PriceRange {
+    /// Prices per region, in time ascending order across all days.
+    pub prices: BTreeMap<Region, Vec<Price>>,
+    /// Delivery days that were fetched.
+    pub dates: Vec<NaiveDate>,
+    /// Delivery days that failed, in date order.
+    pub failures: Vec<DayFailure>,
+}
+
+impl PriceRange {
+    /// Check if every day in the range was fetched.
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Returns prices for region across all fetched days (empty if region is not in the range).
+    pub fn prices_for(&self, region: Region) -> &[Price] {
+        self.prices.get(&region).map_or(&[], |p| p.as_slice())
+    }
+
+    /// Same as `prices_for`, for a region name (empty if it is not a known region).
+    pub fn prices_for_region(&self, region: &str) -> &[Price] {
+        region.parse().map_or(&[], |region| self.prices_for(region))
+    }
+}
+
+/// Fetches every delivery day from `start` to `end` (both included, "YYYY-MM-DD") using reqwest defaults.
+#[cfg(feature = "blocking")]
+pub fn fetch_range(currency: &str, start: &str, end: &str, regions: &[&str]) -> ElspotResult<PriceRange> {
+    use super::{dataportal_dayaheadprices::query::NORDPOOL_BASE_URL, transport::ReqwestTransport};
+
+    fetch_range_with(&ReqwestTransport, NORDPOOL_BASE_URL, currency, start, end, regions, 1)
+}
+
+/// Fetches every delivery day from `start` to `end` (both included, "YYYY-MM-DD") with `transport`.
+///
+/// At most `concurrency` requests are in flight at the same time (0 is treated as 1).
+/// Invalid input (currency, dates or regions) returns an error before any request is made,
+/// while errors for single days end up in `PriceRange::failures`.
+pub fn fetch_range_with<T: Transport + Sync>(
+    transport: &T,
+    base_url: &str,
+    currency: &str,
+    start: &str,
+    end: &str,
+    regions: &[&str],
+    concurrency: usize,
+) -> ElspotResult<PriceRange> {
+    let dates = dates_in_range(start, end)?;
+    // Validates currency and regions once, so that every failure left is about a single day.
+    nordpool_url(base_url, currency, start, regions)?;
+
+    let fetch_day = |date: &NaiveDate| -> ElspotResult<Vec<Vec<Price>>> {
+        let date = date.format("%Y-%m-%d").to_string();
+        let url = nordpool_url(base_url, currency, &date, regions)?;
+        let json_str = transport.get(&url)?;
+
+        dataportal_dayaheadprices::PriceData::new(&json_str)?.extract_prices_all_regions()
+    };
+
+    let results: Vec<ElspotResult<Vec<Vec<Price>>>> = if concurrency <= 1 {
+        dates.iter().map(fetch_day).collect()
+    } else {
+        let next = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel();
+
+        thread::scope(|scope| {
+            for _ in 0..concurrency.min(dates.len()) {
+                let sender = sender.clone();
+                scope.spawn(|| {
+                    let sender = sender;
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(date) = dates.get(i) else { break };
+                        let _ = sender.send((i, fetch_day(date)));
+                    }
+                });
+            }
+        });
+        drop(sender);
+
+        let mut results: Vec<(usize, ElspotResult<Vec<Vec<Price>>>)> = receiver.into_iter().collect();
+        results.sort_by_key(|(i, _)| *i);
+        results.into_iter().map(|(_, res)| res).collect()
+    };
+
+    let mut range = PriceRange::default();
+    for (date, res) in dates.into_iter().zip(results) {
+        match res {
+            Ok(prices_all) => {
+                for prices in prices_all {
+                    if let Some(region) = prices.first().map(|p| p.region) {
+                        range.prices.entry(region).or_default().extend(prices);
+                    }
+                }
+                range.dates.push(date);
+            }
+            Err(error) => range.failures.push(DayFailure { date, error }),
+        }
+    }
+
+    for prices in range.prices.values_mut() {
+        prices.sort_by_key(|p| p.from);
+    }
+
+    Ok(range)
+}
+
+fn dates_in_range(start: &str, end: &str) -> ElspotResult<Vec<NaiveDate>> {
+    let parse = |date: &str| {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|e| ElspotError::DataPortalDayaheadPricesInvalidDate { date: date.to_string(), source: e })
+    };
+    let (start, end) = (parse(start)?, parse(end)?);
+
+    if start > end {
+        return Err(ElspotError::InvalidDateRange { start, end });
+    }
+
+    Ok(start.iter_days().take_while(|d| *d <= end).collect())
+}
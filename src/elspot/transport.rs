@@ -0,0 +1,132 @@
+//! Transports fetch the raw json for a request url, the fetch functions in `elspot` are generic over them.
+//!
+//! `ReqwestTransport` (feature `blocking`) downloads from the network and is used by `from_url` and `from_nordpool`.
+//! `FileTransport` records responses to a directory and replays them keyed by the request url,
+//! which lets us run `from_nordpool_with` end-to-end without network access.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{
+    ElspotError,
+    ElspotResult,
+};
+
+/// Returns the response body for a GET request to `url`.
+pub trait Transport {
+    fn get(&self, url: &str) -> ElspotResult<String>;
+}
+
+impl<T: Transport + ?Sized> Transport for &T {
+    fn get(&self, url: &str) -> ElspotResult<String> {
+        (**self).get(url)
+    }
+}
+
+/// Downloads using `reqwest::blocking`.
+#[cfg(feature = "blocking")]
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestTransport;
+
+#[cfg(feature = "blocking")]
+impl Transport for ReqwestTransport {
+    fn get(&self, url: &str) -> ElspotResult<String> {
+        match reqwest::blocking::get(url) {
+            Ok(r) if !r.status().is_success() => {
+                Err(ElspotError::HttpStatus { url: url.to_string(), status: r.status().as_u16() })
+            }
+            Ok(r) => {
+                match r.text() {
+                    Ok(s) => Ok(s),
+                    Err(e) => Err(ElspotError::InvalidHttpResponse { url: url.to_string(), source: e }),
+                }
+            }
+            Err(e) => Err(ElspotError::HttpRequestFailed { url: url.to_string(), source: e }),
+        }
+    }
+}
+
+/// Never touches the network, every request fails with `ElspotError::NetworkDisabled`.
+/// Used by `FileTransport::replay` so that a missing recording is an error and not a download.
+#[derive(Clone, Debug, Default)]
+pub struct NoNetwork;
+
+impl Transport for NoNetwork {
+    fn get(&self, url: &str) -> ElspotResult<String> {
+        Err(ElspotError::NetworkDisabled(url.to_string()))
+    }
+}
+
+/// Serves responses from files in `dir`, one file per request url.
+/// When no file exists for a url, the request is passed on to `inner` and the response is written to `dir`.
+#[derive(Clone, Debug)]
+pub struct FileTransport<T: Transport> {
+    dir: PathBuf,
+    inner: T,
+}
+
+impl<T: Transport> FileTransport<T> {
+    /// Replays recorded responses and records new ones fetched with `inner`.
+    pub fn record<P: AsRef<Path>>(dir: P, inner: T) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            inner,
+        }
+    }
+
+    /// Returns the path to the file that holds the response for `url`.
+    pub fn path_for(&self, url: &str) -> PathBuf {
+        self.dir.join(file_name_for(url))
+    }
+}
+
+impl FileTransport<NoNetwork> {
+    /// Only replays recorded responses.
+    pub fn replay<P: AsRef<Path>>(dir: P) -> Self {
+        Self::record(dir, NoNetwork)
+    }
+}
+
+impl<T: Transport> Transport for FileTransport<T> {
+    fn get(&self, url: &str) -> ElspotResult<String> {
+        let path = self.path_for(url);
+        if path.is_file() {
+            return fs::read_to_string(&path)
+                .map_err(|e| ElspotError::IOError { path: path.display().to_string(), source: e });
+        }
+
+        let body = self.inner.get(url)?;
+
+        let io_error = |e| ElspotError::IOError { path: path.display().to_string(), source: e };
+        fs::create_dir_all(&self.dir).map_err(io_error)?;
+        fs::write(&path, body.as_bytes()).map_err(io_error)?;
+
+        Ok(body)
+    }
+}
+
+// Urls can be longer than what file systems allow for a file name,
+// so we keep a readable (truncated) prefix and make it unique with a hash of the full url.
+fn file_name_for(url: &str) -> String {
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+
+    let mut name: String = url
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .take(160)
+        .collect();
+    name.push_str(&format!("-{:016x}.json", fnv1a(url.as_bytes())));
+
+    name
+}
+
+// FNV-1a is stable across platforms and compiler versions, recordings must keep their name.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in bytes {
+        hash ^= u64::from(*b);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    hash
+}
@@ -1,29 +1,186 @@
-use std::fmt;
+use std::{error, fmt, io};
 use core::result::Result;
 
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+use crate::price_value::PriceValue;
+use crate::region::Region;
+use crate::units::CurrencyCode;
+
 pub type ElspotResult<T> = Result<T, ElspotError>;
 
 #[derive(Debug)]
 pub enum ElspotError {
-    HttpRequestFailed,
-    InvalidHttpResponse,
-    IOError,
+    HttpRequestFailed { url: String, source: reqwest::Error },
+    InvalidHttpResponse { url: String, source: reqwest::Error },
+    /// The server answered with a status code that is not 2xx.
+    HttpStatus { url: String, status: u16 },
+    HttpClientBuildFailed(reqwest::Error),
+    InvalidHttpHeader { name: String, value: String },
+    IOError { path: String, source: io::Error },
     InvalidInputData,
+    InvalidDateRange { start: NaiveDate, end: NaiveDate },
+    InvalidUrl { url: String, source: url::ParseError },
+    JsonSerializationFailed(serde_json::Error),
+    /// A request to the url was made with a transport that does not allow network access.
+    NetworkDisabled(String),
+
+    #[cfg(feature = "sqlite")]
+    Sqlite(rusqlite::Error),
+    /// A column in the price store holds a value that can not be turned back into a price.
+    StoreInvalidColumn { column: &'static str, value: String },
+
+    #[cfg(feature = "arrow")]
+    Arrow(arrow::error::ArrowError),
+    #[cfg(feature = "arrow")]
+    Parquet(parquet::errors::ParquetError),
+    /// A record batch is missing `column`, or it does not have the type of the price schema.
+    ArrowInvalidColumn(&'static str),
+    /// A value in `column` can not be turned back into a price (or a price into a value for `column`).
+    ArrowInvalidValue { column: &'static str, value: String },
+
+    /// A unit (currency, power or market time unit) could not be resolved for prices in `region` on `date`.
+    InvalidUnit { region: String, date: NaiveDate, source: UnitError },
+    /// A price in `region` on `date` is not a valid decimal number.
+    InvalidPrice { region: String, date: NaiveDate, source: UnitError },
+    Region(RegionError),
+    Series(SeriesError),
+    Exchange(ExchangeError),
 
-    DataPortalDayaheadPricesInvalidJson,
-    DataPortalDayaheadPricesInvalidMarket,
-    DataPortalDayaheadPricesInvalidVersion,
+    DataPortalDayaheadPricesInvalidJson(serde_json::Error),
+    DataPortalDayaheadPricesInvalidMarket(String),
+    DataPortalDayaheadPricesInvalidVersion(u8),
     DataPortalDayaheadPricesNoRegionsSupplied,
+    DataPortalDayaheadPricesUnsupportedCurrency(String),
+    DataPortalDayaheadPricesUnsupportedRegion(String),
+    DataPortalDayaheadPricesInvalidDate { date: String, source: chrono::ParseError },
+    DataPortalDayaheadPricesMissingCurrency,
+    DataPortalDayaheadPricesMissingDate,
 
-    MarketdataPage10InvalidJson,
-    MarketdataPage10InvalidPageId,
+    MarketdataPage10InvalidJson(serde_json::Error),
+    MarketdataPage10InvalidPageId(usize),
     MarketdataPage10MissingUnitString,
-    MarketdataPage10InvalidUnitString,
+    MarketdataPage10InvalidUnitString(String),
+    /// The amount of prices does not match the amount of hours (23, 24 or 25) in that day for `region`.
+    MarketdataPage10InvalidPriceCount { region: String, date: NaiveDate, count: usize },
+    /// A price row for `region` starts on `found` while the dataset is for `date`.
+    MarketdataPage10DateMismatch { region: String, date: NaiveDate, found: NaiveDate },
 }
 
 impl fmt::Display for ElspotError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Self::HttpRequestFailed { url, .. } => write!(f, "HTTP request to '{url}' failed"),
+            Self::InvalidHttpResponse { url, .. } => write!(f, "invalid HTTP response from '{url}'"),
+            Self::HttpStatus { url, status } => write!(f, "HTTP status {status} from '{url}'"),
+            Self::HttpClientBuildFailed(_) => write!(f, "could not build HTTP client"),
+            Self::InvalidHttpHeader { name, value } => write!(f, "'{name}: {value}' is not a valid HTTP header"),
+            Self::IOError { path, .. } => write!(f, "I/O error for '{path}'"),
+            Self::InvalidInputData => write!(f, "could not extract price data from input, is it valid?"),
+            Self::InvalidDateRange { start, end } => write!(f, "start date {start} is after end date {end}"),
+            Self::InvalidUrl { url, .. } => write!(f, "'{url}' is not a valid url"),
+            Self::JsonSerializationFailed(_) => write!(f, "could not serialize price data to json"),
+            Self::NetworkDisabled(url) => write!(f, "no recorded response for '{url}' and network access is disabled"),
+
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(_) => write!(f, "sqlite error"),
+            Self::StoreInvalidColumn { column, value } => write!(f, "'{value}' is not a valid value for column '{column}'"),
+
+            #[cfg(feature = "arrow")]
+            Self::Arrow(_) => write!(f, "arrow error"),
+            #[cfg(feature = "arrow")]
+            Self::Parquet(_) => write!(f, "parquet error"),
+            Self::ArrowInvalidColumn(column) => write!(f, "column '{column}' is missing or has an unexpected type"),
+            Self::ArrowInvalidValue { column, value } => write!(f, "'{value}' is not a valid value for column '{column}'"),
+
+            Self::InvalidUnit { region, date, .. } => write!(f, "invalid unit for prices in {region} on {date}"),
+            Self::InvalidPrice { region, date, .. } => write!(f, "invalid price in {region} on {date}"),
+            Self::Region(_) => write!(f, "region lookup failed"),
+            Self::Series(_) => write!(f, "could not build price series"),
+            Self::Exchange(_) => write!(f, "could not convert currency"),
+
+            Self::DataPortalDayaheadPricesInvalidJson(_) => write!(f, "invalid dataportal dayaheadprices json"),
+            Self::DataPortalDayaheadPricesInvalidMarket(market) => write!(f, "'{market}' is not a supported market"),
+            Self::DataPortalDayaheadPricesInvalidVersion(version) => write!(f, "'{version}' is not a supported version"),
+            Self::DataPortalDayaheadPricesNoRegionsSupplied => write!(f, "no regions where supplied"),
+            Self::DataPortalDayaheadPricesUnsupportedCurrency(currency) => write!(f, "'{currency}' is not a supported currency"),
+            Self::DataPortalDayaheadPricesUnsupportedRegion(region) => write!(f, "'{region}' is not a supported region"),
+            Self::DataPortalDayaheadPricesInvalidDate { date, .. } => {
+                write!(f, "'{date}' is not a valid date, use the format '%Y-%m-%d' for example: '2018-01-26'")
+            }
+            Self::DataPortalDayaheadPricesMissingCurrency => write!(f, "no currency was set"),
+            Self::DataPortalDayaheadPricesMissingDate => write!(f, "no date was set"),
+
+            Self::MarketdataPage10InvalidJson(_) => write!(f, "invalid marketdata page 10 json"),
+            Self::MarketdataPage10InvalidPageId(page_id) => write!(f, "'{page_id}' is not page id 10"),
+            Self::MarketdataPage10MissingUnitString => write!(f, "unit string is missing"),
+            Self::MarketdataPage10InvalidUnitString(unit_string) => write!(f, "'{unit_string}' is not a supported unit string"),
+            Self::MarketdataPage10InvalidPriceCount { region, date, count } => {
+                write!(f, "got {count} prices for {region} on {date}, expected 23, 24 or 25 matching the hours in that day")
+            }
+            Self::MarketdataPage10DateMismatch { region, date, found } => {
+                write!(f, "price for {region} starts on {found}, expected {date}")
+            }
+        }
+    }
+}
+
+impl error::Error for ElspotError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::HttpRequestFailed { source, .. } => Some(source),
+            Self::InvalidHttpResponse { source, .. } => Some(source),
+            Self::HttpClientBuildFailed(e) => Some(e),
+            Self::IOError { source, .. } => Some(source),
+            Self::InvalidUrl { source, .. } => Some(source),
+            Self::JsonSerializationFailed(e) => Some(e),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(e) => Some(e),
+            #[cfg(feature = "arrow")]
+            Self::Arrow(e) => Some(e),
+            #[cfg(feature = "arrow")]
+            Self::Parquet(e) => Some(e),
+            Self::InvalidUnit { source, .. } => Some(source),
+            Self::InvalidPrice { source, .. } => Some(source),
+            Self::Region(e) => Some(e),
+            Self::Series(e) => Some(e),
+            Self::Exchange(e) => Some(e),
+            Self::DataPortalDayaheadPricesInvalidJson(e) => Some(e),
+            Self::DataPortalDayaheadPricesInvalidDate { source, .. } => Some(source),
+            Self::MarketdataPage10InvalidJson(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl ElspotError {
+    /// Check if the error is likely temporary, so that the same request might succeed if we retry later.
+    /// This is the case for timeouts, connection errors, 429 (Too Many Requests) and 5xx server errors.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::HttpRequestFailed { source, .. } => source.is_timeout() || source.is_connect() || source.is_request(),
+            Self::InvalidHttpResponse { .. } => true,
+            Self::HttpStatus { status, .. } => *status == 429 || (500..600).contains(status),
+            _ => false,
+        }
+    }
+}
+
+impl From<RegionError> for ElspotError {
+    fn from(e: RegionError) -> Self {
+        Self::Region(e)
+    }
+}
+
+impl From<SeriesError> for ElspotError {
+    fn from(e: SeriesError) -> Self {
+        Self::Series(e)
+    }
+}
+
+impl From<ExchangeError> for ElspotError {
+    fn from(e: ExchangeError) -> Self {
+        Self::Exchange(e)
     }
 }
 
@@ -33,26 +190,191 @@ pub type RegionResult<T> = Result<T, RegionError>;
 pub enum RegionError {
     // RegionIndexNotFound,
     // RegionNotSupported,
-    RegionTzNotSupported,
+    RegionTzNotSupported(String),
+    /// Neither an area code nor a legacy region name, see `Region`.
+    UnknownRegion(String),
+    /// The local time does not exist (or is ambiguous) in the timezone for `region`, e.g. during DST transitions.
+    InvalidLocalTime { region: String, datetime: NaiveDateTime },
 }
 
 impl fmt::Display for RegionError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Self::RegionTzNotSupported(region) => write!(f, "no timezone is supported for region '{region}'"),
+            Self::UnknownRegion(region) => write!(f, "unknown region '{region}'"),
+            Self::InvalidLocalTime { region, datetime } => {
+                write!(f, "'{datetime}' is not a valid local time for region '{region}'")
+            }
+        }
     }
 }
 
+impl error::Error for RegionError {}
+
 pub type UnitResult<T> = Result<T, UnitError>;
 
 #[derive(Debug)]
 pub enum UnitError {
-    InvalidCurrencyUnit,
-    InvalidPowerUnit,
-    InvalidMtuUnit,
+    InvalidCurrencyUnit(String),
+    InvalidPowerUnit(String),
+    /// Duration in minutes that does not match any market time unit.
+    InvalidMtuUnit(i64),
+    /// The string is not a decimal number.
+    InvalidPriceValue(String),
+    /// The price does not fit in the requested integer type.
+    PriceValueOutOfRange(String),
 }
 
 impl fmt::Display for UnitError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Self::InvalidCurrencyUnit(currency) => write!(f, "'{currency}' is not a supported currency unit"),
+            Self::InvalidPowerUnit(power) => write!(f, "'{power}' is not a supported power unit"),
+            Self::InvalidMtuUnit(minutes) => write!(f, "{minutes} minutes is not a supported market time unit"),
+            Self::InvalidPriceValue(value) => write!(f, "'{value}' could not be parsed into a decimal number"),
+            Self::PriceValueOutOfRange(value) => write!(f, "'{value}' does not fit in the integer type"),
+        }
+    }
+}
+
+impl error::Error for UnitError {}
+
+pub type StatsResult<T> = Result<T, StatsError>;
+
+#[derive(Debug)]
+pub enum StatsError {
+    /// There are no prices to compute anything from.
+    NoPrices,
+    /// Prices are for more than one region, e.g. `first` and `other`.
+    MixedRegions { first: Region, other: Region },
+    /// Prices are in more than one unit (currency, currency unit or power unit).
+    MixedUnits { region: Region },
+    /// Percentile must be in the range 0 to 100.
+    InvalidPercentile(f64),
+    /// A price has a delivery period that ends before it starts.
+    InvalidDuration { region: Region, from: DateTime<Utc> },
+    /// Prices do not cover the whole interval starting at `from`, e.g. a missing quarter when resampling to hourly.
+    IncompleteInterval { region: Region, from: DateTime<Utc> },
+    /// The result does not fit in a `PriceValue`.
+    Overflow,
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoPrices => write!(f, "no prices to compute statistics from"),
+            Self::MixedRegions { first, other } => write!(f, "prices are for more than one region ({first} and {other})"),
+            Self::MixedUnits { region } => write!(f, "prices for {region} are in more than one unit"),
+            Self::InvalidPercentile(p) => write!(f, "{p} is not a percentile in the range 0 to 100"),
+            Self::InvalidDuration { region, from } => write!(f, "price for {region} at {from} ends before it starts"),
+            Self::IncompleteInterval { region, from } => write!(f, "prices for {region} do not cover the interval starting at {from}"),
+            Self::Overflow => write!(f, "statistic does not fit in a price value"),
+        }
+    }
+}
+
+impl error::Error for StatsError {}
+
+pub type SeriesResult<T> = Result<T, SeriesError>;
+
+#[derive(Debug)]
+pub enum SeriesError {
+    /// A series needs at least one price (it is where region and units come from).
+    NoPrices,
+    /// Prices are for more than one region, e.g. `first` and `other`.
+    MixedRegions { first: Region, other: Region },
+    /// Prices are in more than one unit (currency, currency unit or power unit).
+    MixedUnits { region: Region },
+    /// Two prices for `region` cover the same time at `from`.
+    Overlap { region: Region, from: DateTime<Utc> },
+    /// There are no prices for `region` from `from` to `to`.
+    Gap { region: Region, from: DateTime<Utc>, to: DateTime<Utc> },
+}
+
+impl fmt::Display for SeriesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoPrices => write!(f, "a price series needs at least one price"),
+            Self::MixedRegions { first, other } => write!(f, "prices are for more than one region ({first} and {other})"),
+            Self::MixedUnits { region } => write!(f, "prices for {region} are in more than one unit"),
+            Self::Overlap { region, from } => write!(f, "prices for {region} overlap at {from}"),
+            Self::Gap { region, from, to } => write!(f, "no prices for {region} from {from} to {to}"),
+        }
+    }
+}
+
+impl error::Error for SeriesError {}
+
+pub type ExchangeResult<T> = Result<T, ExchangeError>;
+
+#[derive(Debug)]
+pub enum ExchangeError {
+    /// The rate table has no rate for `currency` on `date`.
+    NoRate { currency: CurrencyCode, date: NaiveDate },
+    /// Rates are units of a currency per 1 EUR, so they must be above zero.
+    InvalidRate { currency: CurrencyCode, rate: PriceValue },
+    /// The converted price does not fit in a `PriceValue`.
+    Overflow,
+    IOError { path: String, source: io::Error },
+    /// Neither an ECB reference rate XML nor CSV file (by extension).
+    EcbUnsupportedFile(String),
+    /// The ECB reference rate data could not be parsed, `line` is 1-based.
+    EcbInvalidData { line: usize, reason: String },
+}
+
+impl fmt::Display for ExchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoRate { currency, date } => write!(f, "no exchange rate for {currency} on {date}"),
+            Self::InvalidRate { currency, rate } => write!(f, "{rate} is not a valid exchange rate for {currency}"),
+            Self::Overflow => write!(f, "converted price does not fit in a price value"),
+            Self::IOError { path, .. } => write!(f, "I/O error for '{path}'"),
+            Self::EcbUnsupportedFile(path) => write!(f, "'{path}' is not an ECB reference rate .xml or .csv file"),
+            Self::EcbInvalidData { line, reason } => write!(f, "invalid ECB reference rate data on line {line}: {reason}"),
+        }
+    }
+}
+
+impl error::Error for ExchangeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::IOError { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+pub type CsvResult<T> = Result<T, CsvError>;
+
+#[derive(Debug)]
+pub enum CsvError {
+    /// The delimiter and decimal separator must differ, and neither can be part of a value or timestamp.
+    InvalidOptions { delimiter: char, decimal_separator: char },
+    IOError { path: String, source: io::Error },
+    /// The header of the long format is missing `column`.
+    MissingColumn(&'static str),
+    /// A row could not be turned into a price, `line` is 1-based.
+    InvalidRow { line: usize, reason: String },
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidOptions { delimiter, decimal_separator } => {
+                write!(f, "'{delimiter}' and '{decimal_separator}' can not be used as delimiter and decimal separator")
+            }
+            Self::IOError { path, .. } => write!(f, "I/O error for '{path}'"),
+            Self::MissingColumn(column) => write!(f, "column '{column}' is missing"),
+            Self::InvalidRow { line, reason } => write!(f, "invalid row on line {line}: {reason}"),
+        }
+    }
+}
+
+impl error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::IOError { source, .. } => Some(source),
+            _ => None,
+        }
     }
 }
@@ -0,0 +1,137 @@
+//! Convert prices between currencies with EUR based exchange rates.
+//!
+//! A `RateTable` holds rates as units of a currency per 1 EUR by date, which is how both the data portal
+//! (`exchangeRate` for the currency of the dataset) and the ECB publish them.
+//! Prices are converted with the rate for their delivery `date`, and the applied rate is kept on the price.
+//! Converting between two currencies that are not EUR goes through EUR (cross rate).
+//!
+//! Rates are only published on business days, a table can fall back to the most recent earlier date
+//! (e.g. Friday for a price on Sunday), see `RateTable::fallback_days` and the `ecb` importer.
+
+use std::collections::BTreeMap;
+
+use chrono::{Days, NaiveDate};
+
+use crate::elspot::Price;
+use crate::error::{
+    ExchangeError,
+    ExchangeResult,
+};
+use crate::price_value::PriceValue;
+use crate::units::{Currency, CurrencyCode};
+
+pub mod ecb;
+
+/// The rate that was applied when converting a price, `to` per 1 `from`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExchangeRate {
+    pub from: CurrencyCode,
+    pub to: CurrencyCode,
+    pub rate: PriceValue,
+    /// The date the rate is for.
+    pub date: NaiveDate,
+}
+
+/// Units of a currency per 1 EUR, by date.
+#[derive(Debug, Clone, Default)]
+pub struct RateTable {
+    rates: BTreeMap<NaiveDate, BTreeMap<CurrencyCode, PriceValue>>,
+    fallback_days: u32,
+}
+
+impl RateTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// When there is no rate on a date, use the most recent rate up to `days` before it (default 0, exact date only).
+    pub fn fallback_days(mut self, days: u32) -> Self {
+        self.fallback_days = days;
+        self
+    }
+
+    /// Adds (or replaces) the rate for `currency` on `date`, rates for EUR are ignored (always 1).
+    pub fn insert(&mut self, date: NaiveDate, currency: CurrencyCode, rate: PriceValue) -> ExchangeResult<()> {
+        if rate.is_zero() || rate.is_negative() {
+            return Err(ExchangeError::InvalidRate { currency, rate });
+        }
+        if currency != CurrencyCode::EUR {
+            self.rates.entry(date).or_default().insert(currency, rate);
+        }
+
+        Ok(())
+    }
+
+    /// Adds all rates in `other`, replacing rates for the same currency and date.
+    pub fn extend(&mut self, other: Self) {
+        for (date, rates) in other.rates {
+            self.rates.entry(date).or_default().extend(rates);
+        }
+    }
+
+    /// Returns the rate for `currency` on exactly `date`, EUR is always 1.
+    pub fn get(&self, currency: CurrencyCode, date: NaiveDate) -> Option<PriceValue> {
+        if currency == CurrencyCode::EUR {
+            return Some(PriceValue::from(1));
+        }
+
+        self.rates.get(&date)?.get(&currency).copied()
+    }
+
+    /// Dates with at least one rate, in ascending order.
+    pub fn dates(&self) -> Vec<NaiveDate> {
+        self.rates.keys().copied().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rates.is_empty()
+    }
+
+    /// Returns the rate `to` per 1 `from` on `date`, or on the most recent earlier date within `fallback_days`
+    /// that has rates for both (the returned `date` is the date of the rate).
+    pub fn exchange_rate(&self, from: CurrencyCode, to: CurrencyCode, date: NaiveDate) -> ExchangeResult<ExchangeRate> {
+        // EUR has a rate on every date, even without an entry in the table.
+        let earliest = date.checked_sub_days(Days::new(self.fallback_days.into())).unwrap_or(NaiveDate::MIN);
+        let found = self.rates
+            .range(earliest..=date)
+            .rev()
+            .map(|(d, _)| *d)
+            .chain([date])
+            .find_map(|d| Some((d, self.get(from, d)?, self.get(to, d)?)));
+        let Some((rate_date, from_rate, to_rate)) = found else {
+            let has_rate = |c| self.rates.range(earliest..=date).any(|(d, _)| self.get(c, *d).is_some());
+            let currency = if from != CurrencyCode::EUR && !has_rate(from) { from } else { to };
+            return Err(ExchangeError::NoRate { currency, date });
+        };
+
+        let rate = if from == CurrencyCode::EUR {
+            to_rate
+        } else {
+            to_rate.checked_div(from_rate).ok_or(ExchangeError::Overflow)?.normalize()
+        };
+
+        Ok(ExchangeRate { from, to, rate, date: rate_date })
+    }
+}
+
+/// Returns `p` converted to `to` with the rate for its delivery date, the currency unit (e.g. "Øre") is kept.
+/// A price that already is in `to` is returned as is.
+pub fn convert(p: &Price, to: CurrencyCode, rates: &RateTable) -> ExchangeResult<Price> {
+    let from = p.currency_unit.code();
+    if from == to {
+        return Ok(p.clone());
+    }
+    let rate = rates.exchange_rate(from, to, p.date)?;
+
+    Ok(Price {
+        value: p.value.checked_mul(rate.rate).ok_or(ExchangeError::Overflow)?.normalize(),
+        currency_unit: Currency::from_code(to, p.currency_unit.unit()),
+        exchange_rate: Some(rate),
+        ..p.clone()
+    })
+}
+
+/// Same as `convert`, for all `prices`.
+pub fn convert_all(prices: &[Price], to: CurrencyCode, rates: &RateTable) -> ExchangeResult<Vec<Price>> {
+    prices.iter().map(|p| convert(p, to, rates)).collect()
+}
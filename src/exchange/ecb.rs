@@ -0,0 +1,136 @@
+//! Import the ECB euro foreign exchange reference rates into a `RateTable`.
+//!
+//! The ECB publishes the rates as units of a currency per 1 EUR on business days, as XML
+//! (`eurofxref-daily.xml`, `eurofxref-hist.xml`) and as CSV inside zip files (`eurofxref.zip`, `eurofxref-hist.zip`).
+//! The zip files have to be extracted first, the CSV file inside is read as is.
+//! Only currencies in `CurrencyCode` are kept, others (e.g. USD) are skipped.
+//!
+//! There are no rates on weekends and TARGET holidays, so the tables fall back to the most recent previous
+//! business day within `FALLBACK_DAYS`.
+
+use std::fs;
+use std::path::Path;
+
+use chrono::NaiveDate;
+
+use crate::error::{
+    ExchangeError,
+    ExchangeResult,
+};
+use crate::exchange::RateTable;
+use crate::price_value::PriceValue;
+use crate::units::CurrencyCode;
+
+/// Covers weekends and the longest run of TARGET holidays (Easter).
+pub const FALLBACK_DAYS: u32 = 7;
+
+/// Reads an ECB reference rate file, XML or CSV by extension.
+pub fn from_file(path: &str) -> ExchangeResult<RateTable> {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    let parse = match extension.as_deref() {
+        Some("xml") => from_xml_str,
+        Some("csv") => from_csv_str,
+        _ => return Err(ExchangeError::EcbUnsupportedFile(path.to_string())),
+    };
+    let data = fs::read_to_string(path)
+        .map_err(|e| ExchangeError::IOError { path: path.to_string(), source: e })?;
+
+    parse(&data)
+}
+
+/// Parses the XML format, `<Cube time='...'>` elements with `<Cube currency='...' rate='...'/>` elements inside.
+pub fn from_xml_str(xml: &str) -> ExchangeResult<RateTable> {
+    let mut table = RateTable::new().fallback_days(FALLBACK_DAYS);
+    let mut date = None;
+
+    for (pos, _) in xml.match_indices("<Cube") {
+        let line = xml[..pos].matches('\n').count() + 1;
+        let tag = &xml[pos + "<Cube".len()..];
+        let tag = tag.find('>').map(|end| &tag[..end]).ok_or_else(|| invalid(line, "unterminated Cube element"))?;
+
+        if let Some(time) = attribute(tag, "time") {
+            date = Some(parse_date(time).ok_or_else(|| invalid(line, format!("'{time}' is not a valid date")))?);
+        }
+        let (Some(currency), Some(rate)) = (attribute(tag, "currency"), attribute(tag, "rate")) else {
+            continue;
+        };
+        let date = date.ok_or_else(|| invalid(line, "rate outside of a dated Cube element"))?;
+        insert(&mut table, line, date, currency, rate)?;
+    }
+
+    if table.is_empty() {
+        return Err(invalid(1, "no reference rates found"));
+    }
+
+    Ok(table)
+}
+
+/// Parses the CSV format, a `Date` column followed by one column per currency and one row per date.
+/// Both the daily ("23 September 2024") and historical ("2024-09-23") date formats are accepted,
+/// and "N/A" (the currency did not exist or had no rate) is skipped.
+pub fn from_csv_str(csv: &str) -> ExchangeResult<RateTable> {
+    let mut table = RateTable::new().fallback_days(FALLBACK_DAYS);
+    let mut lines = csv.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+
+    let Some((_, header)) = lines.next() else {
+        return Err(invalid(1, "no reference rates found"));
+    };
+    let header: Vec<&str> = header.split(',').map(str::trim).collect();
+    if header.first() != Some(&"Date") {
+        return Err(invalid(1, "expected a header starting with 'Date'"));
+    }
+
+    for (i, row) in lines {
+        let line = i + 1;
+        let mut fields = row.split(',').map(str::trim);
+        let date = fields.next().unwrap_or_default();
+        let date = parse_date(date).ok_or_else(|| invalid(line, format!("'{date}' is not a valid date")))?;
+
+        for (currency, rate) in header[1..].iter().zip(fields) {
+            insert(&mut table, line, date, currency, rate)?;
+        }
+    }
+
+    if table.is_empty() {
+        return Err(invalid(1, "no reference rates found"));
+    }
+
+    Ok(table)
+}
+
+fn insert(table: &mut RateTable, line: usize, date: NaiveDate, currency: &str, rate: &str) -> ExchangeResult<()> {
+    let Ok(currency) = currency.parse::<CurrencyCode>() else {
+        return Ok(());
+    };
+    if rate.is_empty() || rate == "N/A" {
+        return Ok(());
+    }
+    let rate: PriceValue = rate
+        .parse()
+        .map_err(|_| invalid(line, format!("'{rate}' is not a valid rate for {currency}")))?;
+
+    table.insert(date, currency, rate)
+}
+
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(s, "%d %B %Y"))
+        .ok()
+}
+
+/// Value of the attribute `name` in the (start) tag, in single or double quotes.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    tag.split_whitespace().find_map(|a| {
+        let value = a.trim_end_matches('/').strip_prefix(name)?.strip_prefix('=')?;
+        value
+            .strip_prefix('\'').and_then(|v| v.strip_suffix('\''))
+            .or_else(|| value.strip_prefix('"').and_then(|v| v.strip_suffix('"')))
+    })
+}
+
+fn invalid(line: usize, reason: impl Into<String>) -> ExchangeError {
+    ExchangeError::EcbInvalidData { line, reason: reason.into() }
+}
@@ -0,0 +1,285 @@
+//! A Prometheus exporter that keeps the prices of today and tomorrow in memory, used by the
+//! `eb-nordpool-exporter` binary (feature `exporter`).
+//!
+//! `Exporter::refresh` fetches the delivery days (CET) of today and tomorrow for the configured currencies and regions,
+//! the same request as `from_nordpool` against any base url (e.g. a caching proxy or a local stand-in in tests).
+//! Days with final prices are not fetched again (a response without any area states is not known to be final),
+//! and days before today are dropped.
+//!
+//! `Exporter::serve` answers `GET /metrics` with `Exporter::metrics` and `GET /healthz` with `Exporter::health`,
+//! while `refresh` is called periodically from another thread:
+//! `elspot_price{region="NO3",currency="NOK",unit="Kr./MWh"} 179.08`
+//! `elspot_price_next{region="NO3",currency="NOK",unit="Kr./MWh"} 179.43`
+//! `elspot_price_day_max{region="NO3",currency="NOK",unit="Kr./MWh"} 218`
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::clock::Clock;
+use crate::elspot::{self, Price, PriceExtractor, current, dataportal_dayaheadprices::PriceData, transport::Transport};
+use crate::error::ElspotResult;
+use crate::metrics::{Exposition, MetricsOptions};
+use crate::price_value::PriceValue;
+use crate::stats::{self, Stat};
+
+/// Content type of the Prometheus text exposition format.
+pub const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Connections that do not send their request within this time are dropped.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// Requests never carry a body, so the request line and headers are all we read.
+const MAX_REQUEST_BYTES: u64 = 16 * 1024;
+
+#[derive(Default)]
+struct State {
+    days: BTreeMap<(String, NaiveDate), Box<dyn PriceExtractor>>,
+    last_success: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+}
+
+pub struct Exporter<T: Transport, C: Clock> {
+    transport: T,
+    clock: C,
+    base_url: String,
+    currencies: Vec<String>,
+    regions: Vec<String>,
+    options: MetricsOptions,
+    state: Mutex<State>,
+}
+
+impl<T: Transport, C: Clock> Exporter<T, C> {
+    /// Fails if a currency or region is not supported by the data portal, nothing is fetched before `refresh`.
+    pub fn new(transport: T, clock: C, base_url: &str, currencies: &[&str], regions: &[&str]) -> ElspotResult<Self> {
+        let date = current::delivery_date(clock.now()).format("%Y-%m-%d").to_string();
+        for currency in currencies {
+            elspot::nordpool_url(base_url, currency, &date, regions)?;
+        }
+
+        Ok(Self {
+            transport,
+            clock,
+            base_url: base_url.to_string(),
+            currencies: currencies.iter().map(|c| c.to_string()).collect(),
+            regions: regions.iter().map(|r| r.to_string()).collect(),
+            options: MetricsOptions::new(),
+            state: Mutex::new(State::default()),
+        })
+    }
+
+    /// Metric name (used as prefix for the other price metrics) and units, see `MetricsOptions`.
+    pub fn options(mut self, options: MetricsOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Fetches today and tomorrow (delivery days in CET) for every currency, unless they are in memory with final prices.
+    /// A day that is not published yet is not an error, it is fetched again on the next refresh.
+    /// All days are attempted, the first error is returned (and reported by `health`).
+    pub fn refresh(&self) -> ElspotResult<()> {
+        let now = self.clock.now();
+        let today = current::delivery_date(now);
+        let dates: Vec<NaiveDate> = std::iter::once(today).chain(today.succ_opt()).collect();
+        self.lock().days.retain(|(_, date), _| *date >= today);
+
+        let mut result = Ok(());
+        for currency in &self.currencies {
+            for date in &dates {
+                let key = (currency.clone(), *date);
+                if self.lock().days.get(&key).is_some_and(|data| is_known_final(data.as_ref())) {
+                    continue;
+                }
+                // The lock is not held while fetching, so that metrics can be served meanwhile.
+                match self.fetch(currency, *date) {
+                    Ok(Some(data)) => {
+                        self.lock().days.insert(key, data);
+                    }
+                    Ok(None) => (),
+                    Err(e) => result = result.and(Err(e)),
+                }
+            }
+        }
+
+        let mut state = self.lock();
+        match &result {
+            Ok(()) => {
+                state.last_success = Some(now);
+                state.last_error = None;
+            }
+            Err(e) => state.last_error = Some(e.to_string()),
+        }
+
+        result
+    }
+
+    /// Ok when the prices of today are in memory for every currency,
+    /// otherwise what is missing followed by the error of the last refresh (if it failed).
+    pub fn health(&self) -> Result<(), String> {
+        let today = current::delivery_date(self.clock.now());
+        let state = self.lock();
+
+        let missing: Vec<&str> = self
+            .currencies
+            .iter()
+            .filter(|c| !state.days.contains_key(&(c.to_string(), today)))
+            .map(String::as_str)
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let mut message = format!("no prices for {today} in {}", missing.join(", "));
+        if let Some(e) = &state.last_error {
+            let _ = write!(message, ": {e}");
+        }
+
+        Err(message)
+    }
+
+    /// Prometheus text exposition with, per currency and region, the price now, the price of the next delivery period
+    /// and the min, max and mean of today, followed by the time of the last successful refresh.
+    pub fn metrics(&self) -> String {
+        let now = self.clock.now();
+        let today = current::delivery_date(now);
+
+        let (mut prices_now, mut prices_next) = (vec![], vec![]);
+        let (mut day_min, mut day_max, mut day_avg) = (vec![], vec![], vec![]);
+        let state = self.lock();
+        for currency in &self.currencies {
+            for region in &self.regions {
+                let mut prices: Vec<Price> = vec![];
+                let mut day: Vec<Price> = vec![];
+                for ((_, date), data) in state.days.iter().filter(|((c, _), _)| c == currency) {
+                    if !data.has_region(region) {
+                        continue;
+                    }
+                    let Ok(extracted) = data.extract_prices_for_region(region) else { continue };
+                    let extracted: Vec<Price> = extracted.iter().map(|p| self.options.convert(p)).collect();
+                    if *date == today {
+                        day.extend_from_slice(&extracted);
+                    }
+                    prices.extend(extracted);
+                }
+
+                if let Some(p) = prices.iter().find(|p| p.from <= now && now < p.to) {
+                    prices_next.extend(prices.iter().find(|next| next.from == p.to).cloned());
+                    prices_now.push(p.clone());
+                }
+                day_min.extend(stats::min(&day).ok());
+                day_max.extend(stats::max(&day).ok());
+                day_avg.extend(stats::mean(&day).ok());
+            }
+        }
+        let last_success = state.last_success;
+        drop(state);
+
+        let name = &self.options.name;
+        let mut exposition = Exposition::new();
+        exposition.gauge(name, "Day-ahead price for the current delivery period.");
+        for p in &prices_now {
+            exposition.price(name, p, &[]);
+        }
+        let next = format!("{name}_next");
+        exposition.gauge(&next, "Day-ahead price for the next delivery period.");
+        for p in &prices_next {
+            exposition.price(&next, p, &[]);
+        }
+        let day_stats = [
+            ("min", "Lowest day-ahead price of today (delivery day in CET).", &day_min),
+            ("max", "Highest day-ahead price of today (delivery day in CET).", &day_max),
+            ("avg", "Mean day-ahead price of today (delivery day in CET).", &day_avg),
+        ];
+        for (suffix, help, stats) in day_stats {
+            let metric = format!("{name}_day_{suffix}");
+            exposition.gauge(&metric, help);
+            for s in stats {
+                stat(&mut exposition, &metric, s);
+            }
+        }
+        let refreshed = "elspot_exporter_last_refresh_timestamp_seconds";
+        exposition.gauge(refreshed, "Unix time of the last refresh where all prices could be fetched.");
+        if let Some(t) = last_success {
+            exposition.sample(refreshed, &[], PriceValue::from(t.timestamp()));
+        }
+
+        exposition.into_string()
+    }
+
+    /// Answers requests on `listener` one connection at a time, returns only if accepting connections stops.
+    pub fn serve(&self, listener: &TcpListener) {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            // A client that goes away before it has its response is its own problem.
+            let _ = self.handle(&stream);
+        }
+    }
+
+    fn handle(&self, stream: &TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.take(MAX_REQUEST_BYTES));
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+                break;
+            }
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default();
+        let path = parts.next().unwrap_or_default().split('?').next().unwrap_or_default();
+        let (status, content_type, body) = match (method, path) {
+            ("GET", "/metrics") => ("200 OK", METRICS_CONTENT_TYPE, self.metrics()),
+            ("GET", "/healthz") => match self.health() {
+                Ok(()) => ("200 OK", "text/plain; charset=utf-8", "ok\n".to_string()),
+                Err(e) => ("503 Service Unavailable", "text/plain; charset=utf-8", format!("{e}\n")),
+            },
+            (_, "/metrics" | "/healthz") => ("405 Method Not Allowed", "text/plain; charset=utf-8", "method not allowed\n".to_string()),
+            _ => ("404 Not Found", "text/plain; charset=utf-8", "not found\n".to_string()),
+        };
+
+        let mut stream = stream;
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len(),
+        )?;
+        stream.flush()
+    }
+
+    fn fetch(&self, currency: &str, date: NaiveDate) -> ElspotResult<Option<Box<dyn PriceExtractor>>> {
+        let regions: Vec<&str> = self.regions.iter().map(String::as_str).collect();
+        let url = elspot::nordpool_url(&self.base_url, currency, &date.format("%Y-%m-%d").to_string(), &regions)?;
+        let json_str = self.transport.get(&url)?;
+        if json_str.trim().is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(elspot::from_json(&json_str)?))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // Every update of the state is a single insert or assignment, so a poisoned lock is fine to use.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// See `PriceData::is_known_final`, other formats are final when they say so.
+fn is_known_final(data: &dyn PriceExtractor) -> bool {
+    data.as_dataportal().map_or(data.is_final(), PriceData::is_known_final)
+}
+
+/// A sample labeled like `Exposition::price`.
+fn stat(exposition: &mut Exposition, name: &str, s: &Stat) {
+    let currency = s.currency_unit.code();
+    let unit = format!("{}/{}", s.currency_unit.as_str(), s.power_unit.as_str());
+    let labels = [("region", s.region.as_str()), ("currency", currency.as_str()), ("unit", unit.as_str())];
+
+    exposition.sample(name, &labels, s.value);
+}
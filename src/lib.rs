@@ -31,9 +31,192 @@
 //! // NOTE: you can easily print out all supported currencies and regions..
 //! dataportal_dayaheadprices::currencies::list_supported();
 //! dataportal_dayaheadprices::regions::list_supported();
+//! // or get them as data, with country, names, EIC code, timezone, currency and neighbours.
+//! let zone = dataportal_dayaheadprices::regions::zone_by_code("NO3").unwrap();
 //!
 //! // Finally, download data.
-//! let data = elspot::from_nordpool(currency, date, regions).unwrap();
+//! let data = elspot::from_nordpool(currency, date, &regions).unwrap();
+//! ```
+//!
+//! # Regions
+//!
+//! Prices carry a typed `region::Region`, names are parsed with `FromStr` which also accepts
+//! the legacy marketdata page 10 names (e.g. "Tr.heim" is `Region::NO3`).
+//! Functions that take a region as `&str` are kept as convenience wrappers.
+//! ```
+//! use eb_nordpool::region::Region;
+//!
+//! let region: Region = "Tr.heim".parse().unwrap();
+//! assert_eq!(Region::NO3, region);
+//! let tz = region.tz();
+//! let prices = data.extract_prices(Region::NO3).unwrap();
+//! ```
+
+//! # Fetch a range of days
+//!
+//! One request per delivery day, failed days are reported without aborting the rest of the range.
+//!
+//! ```
+//! use eb_nordpool::elspot::range;
+//!
+//! let range = range::fetch_range("NOK", "2024-10-01", "2024-10-31", &["NO1", "SE3"]).unwrap();
+//! for failure in range.failures.iter() {
+//!     eprintln!("{}: {}", failure.date, failure.error);
+//! }
+//! // Time ascending prices for all days in the range.
+//! let prices = range.prices_for_region("NO1");
+//! ```
+
+//! # Configurable client
+//!
+//! `elspot::client::Client` owns base url, timeout, retry policy (exponential backoff with jitter),
+//! user agent and custom headers. Use `ElspotError::is_retryable()` to tell temporary failures from fatal ones.
+//!
+//! ```
+//! use std::time::Duration;
+//! use eb_nordpool::elspot::client::{Client, RetryPolicy};
+//!
+//! let client = Client::builder()
+//!     .base_url("http://localhost:8080/api/DayAheadPrices")
+//!     .timeout(Some(Duration::from_secs(10)))
+//!     .retry_policy(RetryPolicy { max_retries: 5, ..RetryPolicy::default() })
+//!     .user_agent("my-app/1.0")
+//!     .header("X-Api-Key", "secret")
+//!     .build()
+//!     .unwrap();
+//!
+//! let data = client.from_nordpool("NOK", "2024-10-24", &["NO1"]).unwrap();
+//! ```
+
+//! # Response cache
+//!
+//! Opt-in on-disk cache for `from_nordpool`, final prices are served from disk for as long as they are cached.
+//!
+//! ```
+//! use std::time::Duration;
+//! use eb_nordpool::elspot::{cache::ResponseCache, transport::ReqwestTransport};
+//!
+//! let cache = ResponseCache::new("cache/nordpool", ReqwestTransport)
+//!     .ttl(Some(Duration::from_secs(15 * 60))) // serve preliminary prices updated less than 15 minutes ago
+//!     .max_entries(Some(1000))
+//!     .bypass(false);
+//! let data = cache.from_nordpool("NOK", "2024-10-24", &["NO1"]).unwrap();
+//! ```
+
+//! # SQLite price store
+//!
+//! With the `sqlite` feature enabled, `store::PriceStore` keeps prices in a normalized table.
+//! Storing the same day again upgrades preliminary prices to final, but never the other way around.
+//!
+//! ```
+//! use eb_nordpool::{elspot::dataportal_dayaheadprices::{PriceData, states::State}, store::{PriceStore, Source}};
+//!
+//! let mut store = PriceStore::open("prices.sqlite").unwrap();
+//! store.insert_data(&data, chrono::Utc::now()).unwrap();
+//! // ..or any extracted prices with their source.
+//! let source = Source { state: State::Final, version: 1, fetched_at: chrono::Utc::now() };
+//! store.insert(&prices, &source).unwrap();
+//!
+//! let prices = store.query("NO1", "NOK", from, to).unwrap();
+//! ```
+
+//! # CSV
+//!
+//! Prices as CSV for spreadsheets, long (one row per region and period) or wide (one column per region).
+//! The long format can be read back into prices.
+//!
+//! ```
+//! use eb_nordpool::{csv::{self, CsvOptions, Timestamps}, units::{CurrencyUnit, Power}};
+//!
+//! let prices: Vec<_> = data.extract_prices_all_regions().unwrap().into_iter().flatten().collect();
+//! // ';' as delimiter and ',' as decimal separator, local time and Øre/kWh.
+//! let options = CsvOptions::nordic()
+//!     .timestamps(Timestamps::Region)
+//!     .currency_unit(CurrencyUnit::Fraction)
+//!     .power_unit(Power::kWh);
+//! csv::to_file("prices.csv", &csv::long(&prices, &options).unwrap()).unwrap();
+//! csv::to_file("prices_wide.csv", &csv::wide(&prices, &options).unwrap()).unwrap();
+//!
+//! let prices = csv::from_file("prices.csv", &options).unwrap();
+//! ```
+
+//! # InfluxDB and Prometheus
+//!
+//! `metrics` renders prices as InfluxDB line protocol, or the price of the current delivery period per region
+//! as Prometheus text exposition. Prices are converted to the configured units first.
+//!
+//! ```
+//! use eb_nordpool::{metrics::{self, MetricsOptions}, units::{CurrencyUnit, Power}};
+//!
+//! let options = MetricsOptions::new().currency_unit(CurrencyUnit::Fraction).power_unit(Power::kWh);
+//! let lines = metrics::line_protocol(&prices, &options);
+//! let exposition = metrics::prometheus(&prices, chrono::Utc::now(), &options);
+//! ```
+//!
+//! With the `exporter` feature enabled, `exporter::Exporter` keeps the prices of today and tomorrow in memory
+//! and serves `/metrics` (price now, next price and min, max and mean of today per region) and `/healthz`,
+//! see the `eb-nordpool-exporter` binary.
+//!
+//! ```
+//! use std::{net::TcpListener, sync::Arc, thread, time::Duration};
+//! use eb_nordpool::{clock::SystemClock, elspot::transport::ReqwestTransport, exporter::Exporter};
+//!
+//! let base_url = "https://dataportal-api.nordpoolgroup.com/api/DayAheadPrices";
+//! let exporter = Arc::new(Exporter::new(ReqwestTransport, SystemClock, base_url, &["NOK"], &["NO1", "NO3"]).unwrap());
+//!
+//! let refresher = Arc::clone(&exporter);
+//! thread::spawn(move || loop {
+//!     let _ = refresher.refresh();
+//!     thread::sleep(Duration::from_secs(300));
+//! });
+//! exporter.serve(&TcpListener::bind("127.0.0.1:9796").unwrap());
+//! ```
+
+//! # Arrow and Parquet
+//!
+//! With the `arrow` feature enabled, prices convert to an Arrow `RecordBatch` with typed columns
+//! (UTC timestamps, region dictionary, decimal value, units, MTU minutes and state), for DataFusion, Polars etc.
+//!
+//! ```
+//! use eb_nordpool::arrow;
+//!
+//! let batch = arrow::record_batch_all_regions(data.as_ref()).unwrap();
+//! arrow::write_parquet("prices.parquet", &[batch]).unwrap();
+//!
+//! for archived in arrow::prices_from_parquet("prices.parquet").unwrap() {
+//!     println!("{} {:?}", archived.price.value, archived.state);
+//! }
+//! ```
+
+//! # Transports
+//!
+//! The `*_with` functions are generic over `elspot::transport::Transport`.
+//! `FileTransport` records responses to a directory and replays them keyed by the request url,
+//! e.g. for running tests without network access.
+//!
+//! ```
+//! use eb_nordpool::elspot::{self, transport::{FileTransport, ReqwestTransport}};
+//!
+//! // Download once and keep the responses in "recordings/".
+//! let transport = FileTransport::record("recordings", ReqwestTransport);
+//! let data = elspot::from_nordpool_with(&transport, "NOK", "2024-10-24", &["NO1"]).unwrap();
+//!
+//! // Only serve what is recorded, never touch the network.
+//! let transport = FileTransport::replay("recordings");
+//! let data = elspot::from_nordpool_with(&transport, "NOK", "2024-10-24", &["NO1"]).unwrap();
+//! ```
+
+//! # Async
+//!
+//! With the `async` feature enabled, the download functions are mirrored in `elspot::r#async`.
+//! The blocking functions are behind the `blocking` feature (enabled by default).
+//!
+//! ```
+//! use eb_nordpool::elspot;
+//!
+//! let data = elspot::r#async::from_nordpool("NOK", "2024-10-24", &["NO1"]).await.unwrap();
+//! let data = elspot::r#async::from_url("http..").await.unwrap();
+//! let data = elspot::r#async::marketdata_page_10::from_url("http..").await.unwrap();
 //! ```
 
 //! # Load data from file, json-string or url
@@ -55,25 +238,50 @@
 //! let data = elspot::from_url("http..").unwrap();
 //! ```
 
+//! # Errors
+//!
+//! Nothing in the fetch, parse and extract path panics on bad input.
+//! Functions return `ElspotResult`, `UnitResult` or `RegionResult` and the error types implement `std::error::Error`,
+//! carrying the region, date or offending value along with the underlying error via `source()`.
+//!
+//! ```
+//! use eb_nordpool::{elspot, error::ElspotError};
+//!
+//! match elspot::from_nordpool("NOK", "2024-10-24", &["NO1"]) {
+//!     Ok(data) => println!("{}", data.date()),
+//!     Err(ElspotError::HttpRequestFailed { url, source }) => eprintln!("{url}: {source}"),
+//!     Err(e) => eprintln!("{e}"),
+//! }
+//! ```
+
 //! # Extract data (and more stuff..)
 //!
 //! Once you have loaded the data with one of the two workflows above, we can do stuff.
 //! ```
 //! // Get all prices for specific region (always in time ascending order starting at 00:00).
-//! let prices = data.extract_prices_for_region("NO1");
+//! let prices = data.extract_prices_for_region("NO1").unwrap();
 //! // ..returns a Vec<elspot::Price>
 //!
-//! // Get price as &str.
+//! // Get price as exact decimal (price_value::PriceValue), e.g. "167.68".
 //! let p = &prices[8];
-//! let v = &p.value;
+//! let v = p.value;
+//! println!("{}", v);
+//! // Exact arithmetic and rounding, no floats involved.
+//! let sum = v.checked_add("1 234,56".parse().unwrap()).unwrap();
+//! let rounded = sum.round(1);
 //! // Get price as numeric data types.
 //! let p = &prices[8];
 //! let f = p.as_f32();
-//! let i = p.as_i32();
+//! let i = p.as_i32().unwrap();
 //!
 //! // Pretty print price (label like). Looks like this: "NOK 167,68 Kr./MWh".
 //! let p = &prices[8];
 //! println!("{}", p.price_label());
+//! // Numeric accessors and label round to 2 decimals (0 for fraction units) half away from zero,
+//! // use the *_with variants for other rounding, e.g. banker's rounding to 4 decimals.
+//! use eb_nordpool::price_value::{Rounding, RoundingMode};
+//! let f = p.as_f64_with(Rounding::new(4).mode(RoundingMode::HalfEven));
+//! println!("{}", p.price_label_with(Rounding::new(3).mode(RoundingMode::TowardZero)));
 //!
 //! // Get time window (from and to) for specific price in chrono's datetime type.
 //! let p = &prices[0];
@@ -85,13 +293,13 @@
 //! // Get "from" and "to" adjusted for Utc.
 //! let (from_utc_time, to_utc_time) = p.from_to_as_utc();
 //! // Get "from" and "to" adjusted for region, for example Finland using region code "FI".
-//! let (from_region_time, to_region_time) = p.from_to_with_region("FI");
+//! let (from_region_time, to_region_time) = p.from_to_with_region("FI").unwrap();
 //! // Get "from" and "to" adjusted for any timezone, for example Los Angeles using chrono_tz's tz type.
 //! use chrono_tz::America::Los_Angeles;
 //! let (from_la_time, to_la_time) = p.from_to_with_tz(Los_Angeles);
 //!
 //! // Convert to other units (includes changing price value to accommodate for new units).
-//! let mut prices = data.extract_prices_for_region("NO1");
+//! let mut prices = data.extract_prices_for_region("NO1").unwrap();
 //! for mut p in prices.iter_mut() {
 //!     // To fractional currency unit (from for example 'Kr.' to 'Øre').
 //!     units::convert_to_currency_fraction(&mut p);
@@ -108,8 +316,30 @@
 //!     println!("{}", p.price_label());
 //! }
 //!
+//! // Data portal data also has block aggregates (Off-peak 1, Peak, Off-peak 2), day averages,
+//! // the EUR exchange rate and Final/Preliminary state per region.
+//! if let Some(dataportal) = data.as_dataportal() {
+//!     for block in dataportal.block_aggregates_for_region("NO1").unwrap() {
+//!         println!("{}: {} - {} (avg {})", block.block, block.min, block.max, block.average);
+//!     }
+//!     let average = dataportal.area_average_for_region("NO1").unwrap();
+//!     let rate = dataportal.exchange_rate();
+//!     let state = dataportal.state_for_region("NO1");
+//! }
+//!
+//! // Prices with region and units attached, with lookup by time.
+//! let mut series = data.extract_series_for_region("NO1").unwrap().unwrap();
+//! let p = series.price_at(chrono::Utc::now());
+//! let morning = series.slice(series.from(), series.from() + chrono::Duration::hours(6));
+//! series.convert(units::convert_to_kwh);
+//! // ..or one series per region, extended with the next day (errors on overlaps and gaps).
+//! let mut set = data.extract_price_set().unwrap();
+//! set.concat(next_day_data.extract_price_set().unwrap()).unwrap();
+//! let p = set.price_at(eb_nordpool::region::Region::NO1, chrono::Utc::now());
+//! let p = set.price_at_str("NO1", chrono::Utc::now());
+//!
 //! // Just get all prices for all regions in a 2D Array.
-//! let regions = data.extract_prices_all_regions();
+//! let regions = data.extract_prices_all_regions().unwrap();
 //! for prices in regions.iter() {
 //!     for price in prices.iter() {
 //!         println!("Time: {} - {} ({})", price.hour(), price.price_label(), price.region)
@@ -131,11 +361,106 @@
 //! }
 //!
 //! // Save data to file.
-//! data.to_file("save/to/data.json");
+//! data.to_file("save/to/data.json").unwrap();
 //!
 //! // Serialize data to json string.
-//! let s = data.to_json_string();
+//! let s = data.to_json_string().unwrap();
+//!
+//! ```
+
+//! # Statistics
+//!
+//! The `stats` module computes min, max, mean, median, percentiles, standard deviation and
+//! time-weighted mean over prices for one region, with the units and time window attached.
+//! ```
+//! use eb_nordpool::stats;
+//!
+//! let prices = data.extract_prices_for_region("NO1").unwrap();
+//! let summary = stats::summary(&prices).unwrap();
+//! println!("{} {} - {}: avg {}", summary.region, summary.from, summary.to, summary.time_weighted_mean);
+//! let p90 = stats::percentile(&prices, 90.0).unwrap();
+//! ```
+
+//! # Cheapest time to use power
+//!
+//! The `schedule` module finds the cheapest (or most expensive) contiguous window of a given length,
+//! or the cheapest slots that do not have to be next to each other, e.g. over today and tomorrow.
+//! ```
+//! use chrono::Duration;
+//! use eb_nordpool::schedule;
+//!
+//! let (from, to) = (prices[0].from, prices[prices.len() - 1].to);
+//! if let Some(w) = schedule::cheapest_window(&prices, Duration::hours(3), from, to).unwrap() {
+//!     println!("Charge from {} to {}, average {}", w.from, w.to, w.average);
+//! }
+//! let slots = schedule::cheapest_slots(&prices, 4, from, to).unwrap();
+//! ```
+
+//! # Resample between market time units
+//!
+//! Compare 15 minute prices with hourly prices by resampling them with the `resample` module.
+//! ```
+//! use eb_nordpool::{resample, units::Mtu};
+//!
+//! // Time-weighted mean per hour, with min and max of the quarters.
+//! for hour in resample::to_hourly(&prices).unwrap() {
+//!     println!("{}: {} ({} - {})", hour.price.from, hour.price.value, hour.min, hour.max);
+//! }
+//! // Hourly prices split into quarters, or any other market time unit.
+//! let quarters = resample::to_quarter_hourly(&prices).unwrap();
+//! let halves = resample::resample(&prices, Mtu::Thirty).unwrap();
+//! ```
 //!
+//! # Price now, today and tomorrow
+//!
+//! Today and tomorrow are calendar days in the timezone of the region, `None` when not published yet.
+//! ```
+//! use eb_nordpool::elspot::current;
+//!
+//! let now = current::price_now("NOK", "NO3").unwrap();
+//! let today = current::today("NOK", "NO3").unwrap();
+//! let tomorrow = current::tomorrow("NOK", "NO3").unwrap();
+//! ```
+//! The `_with` variants take a transport and a `Clock`, e.g. `clock::FixedClock` to freeze time in tests.
+//! ```
+//! use eb_nordpool::{clock::FixedClock, elspot::{current, transport::ReqwestTransport}};
+//!
+//! let clock = FixedClock::new("2024-10-27T01:30:00Z".parse().unwrap());
+//! let price = current::price_now_with(&ReqwestTransport, url, &clock, "NOK", "NO3").unwrap();
+//! ```
+//!
+//! # Currency conversion
+//!
+//! Prices are converted with EUR based rates from a `exchange::RateTable`, for example the exchange rate of a dataset
+//! or rates of your own. The rate that was applied is kept on the converted price.
+//! ```
+//! use eb_nordpool::{exchange, units::CurrencyCode};
+//!
+//! let table = data.as_dataportal().unwrap().rate_table().unwrap();
+//! let eur = exchange::convert_all(&prices, CurrencyCode::EUR, &table).unwrap();
+//! println!("{} using {:?}", eur[0].value, eur[0].exchange_rate);
+//! ```
+//!
+//! For history, import the ECB reference rates (`eurofxref-hist.zip` extracted, or the daily XML).
+//! Weekends and holidays use the rates of the previous business day.
+//! ```
+//! use eb_nordpool::{exchange::{self, ecb}, units::CurrencyCode};
+//!
+//! let mut table = ecb::from_file("eurofxref-hist.csv").unwrap();
+//! table.extend(ecb::from_file("eurofxref-daily.xml").unwrap());
+//! let sek = exchange::convert_all(&prices, CurrencyCode::SEK, &table).unwrap();
+//! ```
+//!
+//! # Local calendar days
+//!
+//! Delivery days are defined in CET, so a calendar day in e.g. Helsinki spans two delivery days.
+//! `local_day` fetches both and returns exactly the prices for the local day (23 to 25 hours).
+//! ```
+//! use eb_nordpool::elspot::local_day;
+//!
+//! let date = "2024-10-27".parse().unwrap();
+//! let prices = local_day::prices("EUR", "FI", date).unwrap(); // `None` until both days are published
+//! let (start, end) = local_day::bounds("FI", date).unwrap();
 //! ```
 
 #![allow(non_snake_case)] // Struct naming is in "PascalCase" to map directly with data from nordpool..
@@ -143,7 +468,26 @@
 #![allow(clippy::match_same_arms)]
 #![allow(missing_docs)]
 
+pub mod clock;
+pub mod csv;
 pub mod elspot;
 pub mod error;
+pub mod exchange;
+pub mod metrics;
+pub mod price_value;
+pub mod region;
 pub mod region_time;
+pub mod resample;
+pub mod schedule;
+pub mod series;
+pub mod stats;
 pub mod units;
+
+#[cfg(feature = "sqlite")]
+pub mod store;
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+
+#[cfg(feature = "exporter")]
+pub mod exporter;
@@ -0,0 +1,187 @@
+//! Prices as InfluxDB line protocol and as Prometheus text exposition.
+//!
+//! Line protocol has one line per price, tagged with region, currency and unit and timestamped
+//! with the start of the delivery period (`Price.from`) in nanoseconds:
+//! `elspot_price,region=NO3,currency=NOK,unit=Kr./MWh value=182.94 1726956000000000000`
+//!
+//! The Prometheus exposition has the price of the current delivery period per region, as a gauge:
+//! `elspot_price{region="NO3",currency="NOK",unit="Kr./MWh"} 182.94`
+//! `Exposition` can be used to write more gauges, e.g. for an exporter.
+
+use std::fmt::{self, Write};
+
+use chrono::{DateTime, Utc};
+
+use crate::elspot::Price;
+use crate::price_value::PriceValue;
+use crate::units::{self, CurrencyUnit};
+
+pub const DEFAULT_NAME: &str = "elspot_price";
+
+#[derive(Clone, Debug)]
+pub struct MetricsOptions {
+    pub(crate) name: String,
+    currency_unit: Option<CurrencyUnit>,
+    power_unit: Option<units::Power>,
+}
+
+impl Default for MetricsOptions {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_NAME.to_string(),
+            currency_unit: None,
+            power_unit: None,
+        }
+    }
+}
+
+impl MetricsOptions {
+    /// `DEFAULT_NAME` as measurement and metric name, prices in the units they are in.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Measurement (line protocol) and metric name (exposition).
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Converts prices to `currency_unit` before formatting, see `units::convert_to`.
+    pub fn currency_unit(mut self, currency_unit: CurrencyUnit) -> Self {
+        self.currency_unit = Some(currency_unit);
+        self
+    }
+
+    /// Converts prices to `power_unit` before formatting, see `units::convert_to`.
+    pub fn power_unit(mut self, power_unit: units::Power) -> Self {
+        self.power_unit = Some(power_unit);
+        self
+    }
+
+    /// Returns `p` in the configured units.
+    pub fn convert(&self, p: &Price) -> Price {
+        let mut p = p.clone();
+        units::convert_to(&mut p, self.currency_unit.as_ref(), self.power_unit.as_ref());
+
+        p
+    }
+}
+
+/// One line per price, in the order of `prices`.
+pub fn line_protocol(prices: &[Price], options: &MetricsOptions) -> String {
+    let measurement = escape_influx(&options.name, &[',', ' ']);
+
+    let mut out = String::new();
+    for p in prices.iter().map(|p| options.convert(p)) {
+        let nanos = i128::from(p.from.timestamp()) * 1_000_000_000 + i128::from(p.from.timestamp_subsec_nanos());
+        let _ = writeln!(
+            out,
+            "{measurement},region={},currency={},unit={} value={} {nanos}",
+            escape_influx(p.region.as_str(), &[',', '=', ' ']),
+            p.currency_unit.code(),
+            escape_influx(&unit(&p), &[',', '=', ' ']),
+            p.value,
+        );
+    }
+
+    out
+}
+
+/// The price of the delivery period that `now` is in, per region and currency (regions without one are left out).
+/// When `prices` has more than one price for a region and currency, the first one is used.
+pub fn prometheus(prices: &[Price], now: DateTime<Utc>, options: &MetricsOptions) -> String {
+    let mut current: Vec<&Price> = prices.iter().filter(|p| p.from <= now && now < p.to).collect();
+    current.sort_by_key(|p| (p.region, p.currency_unit.code()));
+    current.dedup_by_key(|p| (p.region, p.currency_unit.code()));
+
+    let mut exposition = Exposition::new();
+    exposition.gauge(&options.name, "Day-ahead price for the current delivery period.");
+    for p in current {
+        exposition.price(&options.name, &options.convert(p), &[]);
+    }
+
+    exposition.into_string()
+}
+
+/// Prometheus text exposition (version 0.0.4) of gauges.
+#[derive(Clone, Debug, Default)]
+pub struct Exposition {
+    out: String,
+}
+
+impl Exposition {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a gauge with its HELP and TYPE lines, samples for it are added with `sample` or `price`.
+    pub fn gauge(&mut self, name: &str, help: &str) {
+        let name = metric_name(name);
+        let help = help.replace('\\', "\\\\").replace('\n', "\\n");
+        let _ = writeln!(self.out, "# HELP {name} {help}");
+        let _ = writeln!(self.out, "# TYPE {name} gauge");
+    }
+
+    pub fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: PriceValue) {
+        let name = metric_name(name);
+        if labels.is_empty() {
+            let _ = writeln!(self.out, "{name} {value}");
+            return;
+        }
+        let labels: Vec<String> = labels.iter().map(|(k, v)| format!("{}=\"{}\"", metric_name(k), escape_label(v))).collect();
+        let _ = writeln!(self.out, "{name}{{{}}} {value}", labels.join(","));
+    }
+
+    /// A sample of the value of `p`, labeled with its region, currency and unit followed by `labels`.
+    pub fn price(&mut self, name: &str, p: &Price, labels: &[(&str, &str)]) {
+        let unit = unit(p);
+        let mut all = vec![("region", p.region.as_str()), ("currency", p.currency_unit.code().as_str()), ("unit", unit.as_str())];
+        all.extend_from_slice(labels);
+
+        self.sample(name, &all, p.value);
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.out
+    }
+
+    pub fn into_string(self) -> String {
+        self.out
+    }
+}
+
+impl fmt::Display for Exposition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.out)
+    }
+}
+
+/// Currency and power unit as in `Price::price_label`, e.g. "Øre/kWh".
+fn unit(p: &Price) -> String {
+    format!("{}/{}", p.currency_unit.as_str(), p.power_unit.as_str())
+}
+
+fn escape_influx(s: &str, special: &[char]) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c == '\\' || special.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+fn escape_label(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Characters that are not allowed in metric and label names are replaced with '_'.
+fn metric_name(name: &str) -> String {
+    name.chars()
+        .enumerate()
+        .map(|(i, c)| if c.is_ascii_alphabetic() || c == '_' || c == ':' || (i > 0 && c.is_ascii_digit()) { c } else { '_' })
+        .collect()
+}
@@ -0,0 +1,490 @@
+//! Exact decimal numbers for prices.
+//!
+//! A `PriceValue` is an integer `mantissa` and a `scale`, the value is `mantissa * 10^-scale`.
+//! For example "182.94" is mantissa 18294 with scale 2.
+//! Changing units is only a matter of changing the scale, so converting from MWh to kWh (and back) is exact,
+//! and so is rounding, which is done on the decimal digits and not on a float.
+//! How to round is described by `Rounding` (decimals and `RoundingMode`).
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use crate::error::{
+    UnitError,
+    UnitResult,
+};
+
+/// Fractional digits kept by `checked_div` (the rest is rounded half away from zero).
+pub const DIV_SCALE: u32 = 12;
+
+/// How to round away the digits that do not fit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Ties go away from zero, e.g. 10.5 becomes 11 and -10.5 becomes -11.
+    #[default]
+    HalfAwayFromZero,
+    /// Ties go to the even neighbour (banker's rounding), e.g. 10.5 becomes 10 and 11.5 becomes 12.
+    HalfEven,
+    /// Digits are cut off (truncation), e.g. 10.99 becomes 10 and -10.99 becomes -10.
+    TowardZero,
+}
+
+/// Amount of decimals and rounding mode used by the numeric accessors and `price_label` on `Price`.
+///
+/// With `decimals` set to `None`, the number of decimals follows the currency unit of the price:
+/// 0 for fractional currency units (e.g. "Øre") and 2 for full currency units (e.g. "Kr.").
+/// Fractional units are rounded to 2 decimals before they are rounded to a whole number, so 1050.495 Øre becomes 1051,
+/// while `Rounding::new(0)` rounds once and gives 1050.
+/// `Rounding::default()` is that, rounding half away from zero.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rounding {
+    pub decimals: Option<u32>,
+    pub mode: RoundingMode,
+}
+
+impl Rounding {
+    /// Round to `decimals`, half away from zero.
+    pub fn new(decimals: u32) -> Self {
+        Self {
+            decimals: Some(decimals),
+            mode: RoundingMode::default(),
+        }
+    }
+
+    pub fn mode(mut self, mode: RoundingMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct PriceValue {
+    mantissa: i128,
+    // Negative scale means trailing zeros, e.g. mantissa 3 with scale -2 is 300.
+    scale: i32,
+}
+
+impl PriceValue {
+    pub const ZERO: Self = Self { mantissa: 0, scale: 0 };
+
+    /// Returns `mantissa * 10^-scale`, e.g. `PriceValue::new(18294, 2)` is 182.94.
+    pub fn new(mantissa: i128, scale: u32) -> Self {
+        Self {
+            mantissa,
+            scale: scale as i32,
+        }
+    }
+
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    /// Number of fractional digits (0 for whole numbers).
+    pub fn scale(&self) -> u32 {
+        self.scale.max(0) as u32
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa < 0
+    }
+
+    /// Removes trailing zeros after the comma, e.g. "5.50" becomes "5.5" and "5.00" becomes "5".
+    pub fn normalize(&self) -> Self {
+        let mut v = *self;
+        if v.mantissa == 0 {
+            return Self::ZERO;
+        }
+        while v.scale > 0 && v.mantissa % 10 == 0 {
+            v.mantissa /= 10;
+            v.scale -= 1;
+        }
+        // Trailing zeros before the comma are kept in the mantissa.
+        while v.scale < 0 {
+            match v.mantissa.checked_mul(10) {
+                Some(m) => {
+                    v.mantissa = m;
+                    v.scale += 1;
+                }
+                None => break,
+            }
+        }
+
+        v
+    }
+
+    /// Moves the comma `places` steps to the left (divides by 10^places), this is always exact.
+    pub fn move_comma_left(&self, places: u32) -> Self {
+        Self {
+            mantissa: self.mantissa,
+            scale: self.scale.saturating_add(places as i32),
+        }.normalize()
+    }
+
+    /// Moves the comma `places` steps to the right (multiplies by 10^places), this is always exact.
+    pub fn move_comma_right(&self, places: u32) -> Self {
+        Self {
+            mantissa: self.mantissa,
+            scale: self.scale.saturating_sub(places as i32),
+        }.normalize()
+    }
+
+    pub fn checked_add(&self, rhs: Self) -> Option<Self> {
+        let (a, b, scale) = align(*self, rhs)?;
+
+        Some(Self { mantissa: a.checked_add(b)?, scale })
+    }
+
+    pub fn checked_sub(&self, rhs: Self) -> Option<Self> {
+        let (a, b, scale) = align(*self, rhs)?;
+
+        Some(Self { mantissa: a.checked_sub(b)?, scale })
+    }
+
+    pub fn checked_mul(&self, rhs: Self) -> Option<Self> {
+        let (a, b) = (self.normalize(), rhs.normalize());
+
+        Some(Self {
+            mantissa: a.mantissa.checked_mul(b.mantissa)?,
+            scale: a.scale.checked_add(b.scale)?,
+        }.normalize())
+    }
+
+    /// Divides with `DIV_SCALE` fractional digits, returns `None` when dividing by zero or on overflow.
+    pub fn checked_div(&self, rhs: Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None;
+        }
+        let (a, b) = (self.normalize(), rhs.normalize());
+
+        // a/b = (ma / mb) * 10^(sb - sa), we want the quotient with DIV_SCALE fractional digits.
+        let exp = DIV_SCALE as i32 + b.scale - a.scale;
+        let (numerator, denominator) = if exp >= 0 {
+            (a.mantissa.checked_mul(pow10(exp as u32)?)?, b.mantissa)
+        } else {
+            (a.mantissa, b.mantissa.checked_mul(pow10(exp.unsigned_abs())?)?)
+        };
+
+        Some(Self {
+            mantissa: div_round(numerator, denominator, RoundingMode::HalfAwayFromZero),
+            scale: DIV_SCALE as i32,
+        }.normalize())
+    }
+
+    /// Rounds to `decimals` fractional digits, half away from zero (e.g. 10.505 becomes 10.51 and -10.5 becomes -11).
+    pub fn round(&self, decimals: u32) -> Self {
+        self.round_with(decimals, RoundingMode::HalfAwayFromZero)
+    }
+
+    /// Rounds to `decimals` fractional digits using `mode`.
+    pub fn round_with(&self, decimals: u32, mode: RoundingMode) -> Self {
+        let v = self.normalize();
+        if v.scale <= decimals as i32 {
+            return v;
+        }
+
+        let diff = (v.scale - decimals as i32) as u32;
+        let mantissa = match pow10(diff) {
+            Some(divisor) => div_round(v.mantissa, divisor, mode),
+            // Dividing by more than what fits in the mantissa leaves nothing (not even a half).
+            None => 0,
+        };
+
+        Self {
+            mantissa,
+            scale: decimals as i32,
+        }.normalize()
+    }
+
+    /// Returns the decimal number closest to `v`, `None` for NaN and infinity.
+    pub fn from_f64(v: f64) -> Option<Self> {
+        if !v.is_finite() {
+            return None;
+        }
+
+        // Display for floats never uses an exponent, and prints the shortest string that round trips.
+        v.to_string().parse().ok()
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        // Parsing the decimal string gives the closest float, which arithmetic on the mantissa would not.
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    pub fn to_f32(&self) -> f32 {
+        self.to_string().parse().unwrap_or(f32::NAN)
+    }
+}
+
+impl Default for PriceValue {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl FromStr for PriceValue {
+    type Err = UnitError;
+
+    /// Parses decimal numbers like "182.94", "-5.00" and "0167.680".
+    /// Also handles comma as decimal separator and spaces as thousands separator, e.g. "1 234,56".
+    fn from_str(s: &str) -> UnitResult<Self> {
+        let invalid = || UnitError::InvalidPriceValue(s.to_string());
+
+        let trimmed = s.trim();
+        let (negative, unsigned) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+
+        let mut mantissa: i128 = 0;
+        let mut digits = 0;
+        let mut scale: Option<i32> = None;
+        let mut last_was_digit = false;
+        for c in unsigned.chars() {
+            match c {
+                '0'..='9' => {
+                    let digit = i128::from(c as u8 - b'0');
+                    mantissa = mantissa.checked_mul(10).and_then(|m| m.checked_add(digit)).ok_or_else(invalid)?;
+                    digits += 1;
+                    if let Some(scale) = scale.as_mut() {
+                        *scale += 1;
+                    }
+                    last_was_digit = true;
+                }
+                '.' | ',' if scale.is_none() && (last_was_digit || digits == 0) => {
+                    scale = Some(0);
+                    last_was_digit = false;
+                }
+                // Thousands separator (space, no-break space and narrow no-break space) only between digits.
+                ' ' | '\u{a0}' | '\u{202f}' if last_was_digit && scale.is_none() => {
+                    last_was_digit = false;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        if digits == 0 || (!last_was_digit && scale.is_none()) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            mantissa: if negative { -mantissa } else { mantissa },
+            scale: scale.unwrap_or(0),
+        })
+    }
+}
+
+impl fmt::Display for PriceValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits = self.mantissa.unsigned_abs().to_string();
+
+        if self.scale <= 0 {
+            let zeros = if self.mantissa == 0 { 0 } else { self.scale.unsigned_abs() as usize };
+            return write!(f, "{sign}{digits}{}", "0".repeat(zeros));
+        }
+
+        let scale = self.scale as usize;
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+
+        write!(f, "{sign}{whole}.{fraction}")
+    }
+}
+
+impl fmt::Debug for PriceValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+impl PartialEq for PriceValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PriceValue {}
+
+impl PartialOrd for PriceValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PriceValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if let Some((a, b, _)) = align(*self, *other) {
+            return a.cmp(&b);
+        }
+
+        // Aligning overflowed, so the value with the smaller scale has the larger magnitude.
+        let (a, b) = (self.normalize(), other.normalize());
+        let larger = if a.scale < b.scale { a } else { b };
+        let ordering = if larger.is_negative() { Ordering::Less } else { Ordering::Greater };
+        if a.scale < b.scale { ordering } else { ordering.reverse() }
+    }
+}
+
+impl Hash for PriceValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let v = self.normalize();
+        v.mantissa.hash(state);
+        v.scale.hash(state);
+    }
+}
+
+/// Compares with the number in the string, e.g. `PriceValue::new(500, 2) == "5"` (invalid numbers are never equal).
+impl PartialEq<str> for PriceValue {
+    fn eq(&self, other: &str) -> bool {
+        other.parse::<Self>().is_ok_and(|v| *self == v)
+    }
+}
+
+impl PartialEq<&str> for PriceValue {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl PartialEq<PriceValue> for &str {
+    fn eq(&self, other: &PriceValue) -> bool {
+        *other == **self
+    }
+}
+
+impl PartialEq<PriceValue> for str {
+    fn eq(&self, other: &PriceValue) -> bool {
+        *other == *self
+    }
+}
+
+impl From<i64> for PriceValue {
+    fn from(v: i64) -> Self {
+        Self::new(i128::from(v), 0)
+    }
+}
+
+fn pow10(exp: u32) -> Option<i128> {
+    10i128.checked_pow(exp)
+}
+
+// Returns both mantissas at the same (largest) scale.
+fn align(a: PriceValue, b: PriceValue) -> Option<(i128, i128, i32)> {
+    let scale = a.scale.max(b.scale);
+    let a_m = a.mantissa.checked_mul(pow10((scale - a.scale) as u32)?)?;
+    let b_m = b.mantissa.checked_mul(pow10((scale - b.scale) as u32)?)?;
+
+    Some((a_m, b_m, scale))
+}
+
+fn div_round(numerator: i128, denominator: i128, mode: RoundingMode) -> i128 {
+    let q = numerator / denominator;
+    let r = numerator % denominator;
+    if r == 0 {
+        return q;
+    }
+
+    // Compare the remainder with the other part of the denominator to find out if we are below, at or above half.
+    let away = match r.unsigned_abs().cmp(&(denominator.unsigned_abs() - r.unsigned_abs())) {
+        Ordering::Less => false,
+        Ordering::Equal => match mode {
+            RoundingMode::HalfAwayFromZero => true,
+            RoundingMode::HalfEven => q % 2 != 0,
+            RoundingMode::TowardZero => false,
+        },
+        Ordering::Greater => mode != RoundingMode::TowardZero,
+    };
+
+    match away {
+        true if (numerator < 0) == (denominator < 0) => q + 1,
+        true => q - 1,
+        false => q,
+    }
+}
+
+/// (De)serializes a `PriceValue` as a JSON number, for `#[serde(with = "json_number")]`.
+/// The number is read from its token as written in the JSON, never as a float, so no digits are lost.
+pub(crate) mod json_number {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de, ser};
+    use serde_json::value::RawValue;
+
+    use super::PriceValue;
+
+    pub fn serialize<S: Serializer>(v: &PriceValue, s: S) -> Result<S::Ok, S::Error> {
+        to_raw(v).map_err(ser::Error::custom)?.serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<PriceValue, D::Error> {
+        let raw = Box::<RawValue>::deserialize(d)?;
+
+        parse(raw.get()).map_err(de::Error::custom)
+    }
+
+    /// Same as `json_number`, for an optional value (`null`).
+    pub mod option {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(v: &Option<PriceValue>, s: S) -> Result<S::Ok, S::Error> {
+            v.as_ref().map(to_raw).transpose().map_err(ser::Error::custom)?.serialize(s)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<PriceValue>, D::Error> {
+            let raw = Option::<Box<RawValue>>::deserialize(d)?;
+
+            raw.map(|raw| parse(raw.get()).map_err(de::Error::custom)).transpose()
+        }
+    }
+
+    /// Same as `json_number`, for the values of a map.
+    pub mod map {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(m: &HashMap<String, PriceValue>, s: S) -> Result<S::Ok, S::Error> {
+            let mut raw = HashMap::with_capacity(m.len());
+            for (k, v) in m {
+                raw.insert(k, to_raw(v).map_err(ser::Error::custom)?);
+            }
+
+            raw.serialize(s)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<HashMap<String, PriceValue>, D::Error> {
+            HashMap::<String, Box<RawValue>>::deserialize(d)?
+                .into_iter()
+                .map(|(k, raw)| parse(raw.get()).map(|v| (k, v)).map_err(de::Error::custom))
+                .collect()
+        }
+    }
+
+    fn to_raw(v: &PriceValue) -> serde_json::Result<Box<RawValue>> {
+        RawValue::from_string(v.to_string())
+    }
+
+    // Numbers written as floats by serde_json (e.g. by older versions of this crate) may have an exponent, e.g. "1e-5".
+    fn parse(token: &str) -> Result<PriceValue, String> {
+        let invalid = || format!("'{token}' is not a number");
+        let token = token.trim();
+        if token.starts_with('"') || token.contains(',') {
+            return Err(invalid());
+        }
+
+        let (number, exponent) = match token.split_once(['e', 'E']) {
+            Some((number, exponent)) => (number, exponent.parse::<i32>().map_err(|_| invalid())?),
+            None => (token, 0),
+        };
+        let v: PriceValue = number.parse().map_err(|_| invalid())?;
+
+        Ok(match exponent {
+            0 => v,
+            e if e < 0 => v.move_comma_left(e.unsigned_abs()),
+            e => v.move_comma_right(e as u32),
+        })
+    }
+}
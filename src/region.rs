@@ -0,0 +1,206 @@
+//! Regions (bidding zones) as used by Nord Pool.
+//!
+//! The data portal uses area codes (e.g. "NO3"), while the legacy marketdata page 10 used city names
+//! for the Norwegian areas (e.g. "Tr.heim") and "DE-LU" for Germany, these are accepted as aliases when parsing.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono_tz::{
+    Tz,
+    Etc::UTC, // "Etcetera" -> "UTC": some timezones cannot be attributed to any area..
+    Europe::{
+        Oslo,
+        Stockholm,
+        Copenhagen,
+        Helsinki,
+        Tallinn,
+        Riga,
+        Vilnius,
+        Vienna,
+        Brussels,
+        Paris,
+        Amsterdam,
+        Bucharest,
+        Berlin,
+        Warsaw,
+        Sofia,
+    }
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::RegionError;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Region {
+    // Baltic
+    EE,
+    LT,
+    LV,
+
+    // Central Western Europe
+    AT,
+    BE,
+    FR,
+    GER,
+    NL,
+    PL,
+
+    // Nordic
+    DK1,
+    DK2,
+    FI,
+    NO1,
+    NO2,
+    NO3,
+    NO4,
+    NO5,
+    SE1,
+    SE2,
+    SE3,
+    SE4,
+
+    // South East Europe (SEE)
+    BG,
+    TEL,
+
+    // System
+    SYS,
+}
+
+impl Region {
+    pub const ALL: [Region; 24] = [
+        Self::EE, Self::LT, Self::LV,
+        Self::AT, Self::BE, Self::FR, Self::GER, Self::NL, Self::PL,
+        Self::DK1, Self::DK2, Self::FI,
+        Self::NO1, Self::NO2, Self::NO3, Self::NO4, Self::NO5,
+        Self::SE1, Self::SE2, Self::SE3, Self::SE4,
+        Self::BG, Self::TEL,
+        Self::SYS,
+    ];
+
+    /// Area code as used by the data portal, e.g. "NO3".
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EE => "EE",
+            Self::LT => "LT",
+            Self::LV => "LV",
+            Self::AT => "AT",
+            Self::BE => "BE",
+            Self::FR => "FR",
+            Self::GER => "GER",
+            Self::NL => "NL",
+            Self::PL => "PL",
+            Self::DK1 => "DK1",
+            Self::DK2 => "DK2",
+            Self::FI => "FI",
+            Self::NO1 => "NO1",
+            Self::NO2 => "NO2",
+            Self::NO3 => "NO3",
+            Self::NO4 => "NO4",
+            Self::NO5 => "NO5",
+            Self::SE1 => "SE1",
+            Self::SE2 => "SE2",
+            Self::SE3 => "SE3",
+            Self::SE4 => "SE4",
+            Self::BG => "BG",
+            Self::TEL => "TEL",
+            Self::SYS => "SYS",
+        }
+    }
+
+    /// Parses an area code only, without the legacy aliases that `FromStr` accepts.
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|r| r.as_str() == code)
+    }
+
+    pub fn tz(&self) -> Tz {
+        match self {
+            Self::NO1 | Self::NO2 | Self::NO3 | Self::NO4 | Self::NO5 => Oslo,
+            Self::SE1 | Self::SE2 | Self::SE3 | Self::SE4 => Stockholm,
+            Self::DK1 | Self::DK2 => Copenhagen,
+            Self::FI => Helsinki,
+
+            Self::EE => Tallinn,
+            Self::LV => Riga,
+            Self::LT => Vilnius,
+
+            Self::AT => Vienna,
+            Self::BE => Brussels,
+            Self::FR => Paris,
+            Self::NL => Amsterdam,
+            Self::GER => Berlin,
+            Self::PL => Warsaw,
+
+            Self::TEL => Bucharest,
+            Self::BG => Sofia,
+
+            Self::SYS => UTC,
+        }
+    }
+}
+
+impl FromStr for Region {
+    type Err = RegionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(region) = Self::from_code(s) {
+            return Ok(region);
+        }
+
+        // Legacy names from marketdata page 10.
+        match s {
+            "Oslo" => Ok(Self::NO1),
+            "Kr.sand" => Ok(Self::NO2),
+            "Molde" | "Tr.heim" => Ok(Self::NO3),
+            "Tromsø" => Ok(Self::NO4),
+            "Bergen" => Ok(Self::NO5),
+            "DE-LU" => Ok(Self::GER),
+            _ => Err(RegionError::UnknownRegion(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// Ordered by area code, so that regions sort the same as their strings.
+impl Ord for Region {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl PartialOrd for Region {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq<str> for Region {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Region {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<Region> for str {
+    fn eq(&self, other: &Region) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<Region> for &str {
+    fn eq(&self, other: &Region) -> bool {
+        *self == other.as_str()
+    }
+}
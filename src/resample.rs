@@ -0,0 +1,114 @@
+//! Resample prices between market time units (15, 30 and 60 minutes).
+//!
+//! Intervals are aligned to whole multiples of the market time unit in UTC (e.g. 00:00, 00:15..),
+//! which lines up with local quarters and hours in every region we support.
+//! A price that is longer than the target unit is split into intervals with the same value,
+//! and prices that are shorter are combined into one interval with the time-weighted mean as value.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::elspot::Price;
+use crate::error::{
+    StatsError,
+    StatsResult,
+};
+use crate::price_value::PriceValue;
+use crate::stats::validate;
+use crate::units::Mtu;
+
+/// A resampled price, with the min and max of the prices it was computed from.
+#[derive(Debug, Clone)]
+pub struct Resampled {
+    pub price: Price,
+    pub min: PriceValue,
+    pub max: PriceValue,
+}
+
+// Sums for one interval while resampling.
+struct Interval {
+    first: Price,
+    weighted_sum: PriceValue,
+    covered: Duration,
+    min: PriceValue,
+    max: PriceValue,
+}
+
+/// Resamples `prices` (one region, one unit) to `mtu`.
+/// Returns `StatsError::IncompleteInterval` if the prices do not cover every interval they touch exactly once.
+pub fn resample(prices: &[Price], mtu: Mtu) -> StatsResult<Vec<Resampled>> {
+    validate(prices)?;
+    let length = Duration::minutes(mtu.minutes());
+
+    let mut intervals: BTreeMap<DateTime<Utc>, Interval> = BTreeMap::new();
+    for p in prices.iter() {
+        let mut start = align(p.from, length);
+        while start < p.to {
+            let end = start + length;
+            let covered = p.to.min(end) - p.from.max(start);
+            let weighted = p.value
+                .checked_mul(PriceValue::from(covered.num_seconds()))
+                .ok_or(StatsError::Overflow)?;
+
+            let interval = intervals.entry(start).or_insert_with(|| Interval {
+                first: p.clone(),
+                weighted_sum: PriceValue::ZERO,
+                covered: Duration::zero(),
+                min: p.value,
+                max: p.value,
+            });
+            interval.weighted_sum = interval.weighted_sum.checked_add(weighted).ok_or(StatsError::Overflow)?;
+            interval.covered += covered;
+            interval.min = interval.min.min(p.value);
+            interval.max = interval.max.max(p.value);
+
+            start = end;
+        }
+    }
+
+    let mut resampled = Vec::with_capacity(intervals.len());
+    for (from, interval) in intervals {
+        if interval.covered != length {
+            return Err(StatsError::IncompleteInterval { region: interval.first.region, from });
+        }
+        let value = interval.weighted_sum
+            .checked_div(PriceValue::from(length.num_seconds()))
+            .ok_or(StatsError::Overflow)?;
+
+        resampled.push(Resampled {
+            price: Price {
+                from,
+                to: from + length,
+                value,
+                market_time_unit: mtu,
+                ..interval.first
+            },
+            min: interval.min,
+            max: interval.max,
+        });
+    }
+
+    Ok(resampled)
+}
+
+/// Aggregates prices to hourly prices (time-weighted mean), see `resample`.
+pub fn to_hourly(prices: &[Price]) -> StatsResult<Vec<Resampled>> {
+    resample(prices, Mtu::Sixty)
+}
+
+/// Splits prices into 15 minute prices with the same value, see `resample`.
+pub fn to_quarter_hourly(prices: &[Price]) -> StatsResult<Vec<Price>> {
+    Ok(resample(prices, Mtu::Fifteen)?
+        .into_iter()
+        .map(|r| r.price)
+        .collect())
+}
+
+// Start of the interval of `length` that `dt` is in.
+fn align(dt: DateTime<Utc>, length: Duration) -> DateTime<Utc> {
+    let seconds = dt.timestamp();
+    let aligned = seconds - seconds.rem_euclid(length.num_seconds());
+
+    DateTime::from_timestamp(aligned, 0).unwrap_or(dt)
+}
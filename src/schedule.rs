@@ -0,0 +1,177 @@
+//! Find the cheapest (or most expensive) time to use power, e.g. for EV charging or a dishwasher.
+//!
+//! Prices are for one region in one unit (see `stats`), they may span several days (e.g. today and tomorrow)
+//! and mix market time units. All time math is done in UTC on `from` and `to` of the prices,
+//! so days with 23 or 25 hours (DST) and 15 minute prices need no special handling.
+//!
+//! `total` is the cost of using one power unit (1 MW for prices per MWh, 1 kW for prices per kWh)
+//! during the whole window, and `average` is `total` divided by the length of the window in hours.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::elspot::Price;
+use crate::error::{
+    StatsError,
+    StatsResult,
+};
+use crate::price_value::PriceValue;
+use crate::region::Region;
+use crate::stats::validate;
+use crate::units;
+
+/// Prices picked by one of the functions in this module, in time ascending order.
+#[derive(Debug, Clone)]
+pub struct Window {
+    pub region: Region,
+    /// Start of the first price.
+    pub from: DateTime<Utc>,
+    /// End of the last price.
+    pub to: DateTime<Utc>,
+    pub total: PriceValue,
+    pub average: PriceValue,
+    pub currency_unit: units::Currency,
+    pub power_unit: units::Power,
+    pub prices: Vec<Price>,
+}
+
+impl Window {
+    /// Time covered by the prices, for non-contiguous slots this is less than `to - from`.
+    pub fn duration(&self) -> Duration {
+        self.prices.iter().map(|p| p.to - p.from).sum()
+    }
+}
+
+/// Returns the cheapest contiguous window of `duration` within `from..to`,
+/// `None` if there is no such window (e.g. missing prices, or `duration` is not a multiple of the MTU).
+/// On ties the earliest window is returned.
+pub fn cheapest_window(
+    prices: &[Price],
+    duration: Duration,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> StatsResult<Option<Window>> {
+    best_window(prices, duration, from, to, |candidate, best| candidate < best)
+}
+
+/// Same as `cheapest_window`, but returns the most expensive window.
+pub fn most_expensive_window(
+    prices: &[Price],
+    duration: Duration,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> StatsResult<Option<Window>> {
+    best_window(prices, duration, from, to, |candidate, best| candidate > best)
+}
+
+/// Returns the `count` cheapest prices within `from..to`, they do not have to be next to each other.
+/// `None` if there are fewer than `count` prices. On ties the earliest prices are picked.
+pub fn cheapest_slots(
+    prices: &[Price],
+    count: usize,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> StatsResult<Option<Window>> {
+    let mut candidates = prices_within(prices, from, to)?;
+    if count == 0 || candidates.len() < count {
+        return Ok(None);
+    }
+
+    // Stable sort, so that equal prices keep their time order.
+    candidates.sort_by_key(|p| p.value);
+    candidates.truncate(count);
+    candidates.sort_by_key(|p| p.from);
+
+    window(candidates).map(Some)
+}
+
+fn best_window<F>(
+    prices: &[Price],
+    duration: Duration,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    is_better: F,
+) -> StatsResult<Option<Window>>
+where
+    F: Fn(PriceValue, PriceValue) -> bool,
+{
+    let candidates = prices_within(prices, from, to)?;
+    if duration <= Duration::zero() {
+        return Ok(None);
+    }
+
+    let mut best: Option<(usize, usize, PriceValue)> = None;
+    for start in 0..candidates.len() {
+        // Extend the window one price at a time, as long as the prices are next to each other.
+        let mut covered = Duration::zero();
+        let mut total = PriceValue::ZERO;
+        for end in start..candidates.len() {
+            if end > start && candidates[end].from != candidates[end - 1].to {
+                break;
+            }
+            covered += candidates[end].to - candidates[end].from;
+            total = total.checked_add(cost(&candidates[end])?).ok_or(StatsError::Overflow)?;
+
+            if covered > duration {
+                break;
+            }
+            if covered == duration {
+                if best.is_none_or(|(_, _, best_total)| is_better(total, best_total)) {
+                    best = Some((start, end, total));
+                }
+                break;
+            }
+        }
+    }
+
+    match best {
+        Some((start, end, _)) => window(candidates[start..=end].to_vec()).map(Some),
+        None => Ok(None),
+    }
+}
+
+// Prices that are completely within `from..to`, in time ascending order.
+fn prices_within(prices: &[Price], from: DateTime<Utc>, to: DateTime<Utc>) -> StatsResult<Vec<Price>> {
+    validate(prices)?;
+
+    let mut within: Vec<Price> = prices
+        .iter()
+        .filter(|p| p.from >= from && p.to <= to)
+        .cloned()
+        .collect();
+    within.sort_by_key(|p| p.from);
+
+    Ok(within)
+}
+
+// Cost of using one power unit for the duration of the price.
+fn cost(price: &Price) -> StatsResult<PriceValue> {
+    let minutes = PriceValue::from((price.to - price.from).num_minutes());
+
+    price.value
+        .checked_mul(minutes)
+        .and_then(|v| v.checked_div(PriceValue::from(60)))
+        .ok_or(StatsError::Overflow)
+}
+
+fn window(prices: Vec<Price>) -> StatsResult<Window> {
+    let first = prices.first().ok_or(StatsError::NoPrices)?;
+
+    let mut total = PriceValue::ZERO;
+    let mut minutes = 0;
+    for p in prices.iter() {
+        total = total.checked_add(cost(p)?).ok_or(StatsError::Overflow)?;
+        minutes += (p.to - p.from).num_minutes();
+    }
+    let hours = PriceValue::from(minutes).checked_div(PriceValue::from(60)).ok_or(StatsError::Overflow)?;
+
+    Ok(Window {
+        region: first.region,
+        from: first.from,
+        to: prices[prices.len() - 1].to,
+        total,
+        average: total.checked_div(hours).ok_or(StatsError::Overflow)?,
+        currency_unit: first.currency_unit.clone(),
+        power_unit: first.power_unit.clone(),
+        prices,
+    })
+}
@@ -0,0 +1,299 @@
+//! Prices with their region and units attached.
+//!
+//! A `PriceSeries` holds prices for one region in one unit, in time ascending order and without overlaps.
+//! A `PriceSet` holds one series per region, e.g. all regions in a dataset.
+//! Both can be extended with the next day, and converted to other units in one call.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::elspot::Price;
+use crate::error::{
+    SeriesError,
+    SeriesResult,
+};
+use crate::region::Region;
+use crate::units;
+
+/// Prices for one region in one unit, in time ascending order and without overlaps (there may be gaps).
+#[derive(Debug, Clone)]
+pub struct PriceSeries {
+    prices: Vec<Price>,
+}
+
+impl PriceSeries {
+    /// Sorts the prices by time, returns an error if they are empty, overlap, or are for more than one region or unit.
+    pub fn new(mut prices: Vec<Price>) -> SeriesResult<Self> {
+        prices.sort_by_key(|p| p.from);
+        check(&prices)?;
+
+        Ok(Self { prices })
+    }
+
+    pub fn region(&self) -> Region {
+        self.prices[0].region
+    }
+
+    pub fn currency_unit(&self) -> &units::Currency {
+        &self.prices[0].currency_unit
+    }
+
+    pub fn power_unit(&self) -> &units::Power {
+        &self.prices[0].power_unit
+    }
+
+    /// Start of the first price.
+    pub fn from(&self) -> DateTime<Utc> {
+        self.prices[0].from
+    }
+
+    /// End of the last price.
+    pub fn to(&self) -> DateTime<Utc> {
+        self.prices[self.prices.len() - 1].to
+    }
+
+    pub fn prices(&self) -> &[Price] {
+        &self.prices
+    }
+
+    pub fn into_prices(self) -> Vec<Price> {
+        self.prices
+    }
+
+    pub fn len(&self) -> usize {
+        self.prices.len()
+    }
+
+    /// Always false, a series has at least one price.
+    pub fn is_empty(&self) -> bool {
+        self.prices.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Price> {
+        self.prices.iter()
+    }
+
+    /// Returns the price that is active at `dt` (`from <= dt < to`).
+    pub fn price_at(&self, dt: DateTime<Utc>) -> Option<&Price> {
+        let i = self.prices.partition_point(|p| p.to <= dt);
+
+        self.prices.get(i).filter(|p| p.from <= dt)
+    }
+
+    /// Returns the prices that start in `from..to`, `None` if there are none.
+    pub fn slice(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Option<Self> {
+        let start = self.prices.partition_point(|p| p.from < from);
+        let end = self.prices.partition_point(|p| p.from < to).max(start);
+        if start == end {
+            return None;
+        }
+
+        Some(Self { prices: self.prices[start..end].to_vec() })
+    }
+
+    /// Returns the periods without prices between the first and the last price.
+    pub fn gaps(&self) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        self.prices
+            .windows(2)
+            .filter(|w| w[0].to < w[1].from)
+            .map(|w| (w[0].to, w[1].from))
+            .collect()
+    }
+
+    /// Appends `other`, which must start exactly where this series ends (e.g. the next delivery day).
+    pub fn concat(&mut self, other: Self) -> SeriesResult<()> {
+        check_compatible(&self.prices[0], &other.prices[0])?;
+        if other.from() < self.to() {
+            return Err(SeriesError::Overlap { region: self.region(), from: other.from() });
+        }
+        if other.from() > self.to() {
+            return Err(SeriesError::Gap { region: self.region(), from: self.to(), to: other.from() });
+        }
+
+        self.prices.extend(other.prices);
+        Ok(())
+    }
+
+    /// Merges `other` into this series, gaps are allowed.
+    /// Prices that exist in both (same `from`, `to` and value) are kept once, any other overlap is an error.
+    pub fn merge(&mut self, other: Self) -> SeriesResult<()> {
+        check_compatible(&self.prices[0], &other.prices[0])?;
+
+        let mut prices = self.prices.clone();
+        for p in other.prices {
+            let duplicate = self.prices.iter().any(|q| q.from == p.from && q.to == p.to && q.value == p.value);
+            if !duplicate {
+                prices.push(p);
+            }
+        }
+        prices.sort_by_key(|p| p.from);
+        check(&prices)?;
+
+        self.prices = prices;
+        Ok(())
+    }
+
+    /// Converts all prices, e.g. `series.convert(units::convert_to_kwh)`.
+    pub fn convert<F: FnMut(&mut Price)>(&mut self, mut f: F) {
+        for p in self.prices.iter_mut() {
+            f(p);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a PriceSeries {
+    type Item = &'a Price;
+    type IntoIter = std::slice::Iter<'a, Price>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.prices.iter()
+    }
+}
+
+impl IntoIterator for PriceSeries {
+    type Item = Price;
+    type IntoIter = std::vec::IntoIter<Price>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.prices.into_iter()
+    }
+}
+
+/// One `PriceSeries` per region, ordered by region.
+#[derive(Debug, Clone, Default)]
+pub struct PriceSet {
+    series: BTreeMap<Region, PriceSeries>,
+}
+
+impl PriceSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a set from prices per region (e.g. from `extract_prices_all_regions`), empty regions are skipped.
+    pub fn from_regions(prices_all: Vec<Vec<Price>>) -> SeriesResult<Self> {
+        let mut set = Self::new();
+        for prices in prices_all.into_iter().filter(|p| !p.is_empty()) {
+            set.merge(PriceSeries::new(prices)?)?;
+        }
+
+        Ok(set)
+    }
+
+    /// Adds `series`, merging it with the series already in the set for the same region.
+    pub fn merge(&mut self, series: PriceSeries) -> SeriesResult<()> {
+        match self.series.get_mut(&series.region()) {
+            Some(existing) => existing.merge(series),
+            None => {
+                self.series.insert(series.region(), series);
+                Ok(())
+            }
+        }
+    }
+
+    /// Appends every series in `other` to the series for the same region, see `PriceSeries::concat`.
+    /// Regions that are not in the set yet are added. On error the set is left unchanged.
+    pub fn concat(&mut self, other: Self) -> SeriesResult<()> {
+        let mut series = self.series.clone();
+        for (region, other) in other.series {
+            match series.get_mut(&region) {
+                Some(existing) => existing.concat(other)?,
+                None => {
+                    series.insert(region, other);
+                }
+            }
+        }
+
+        self.series = series;
+        Ok(())
+    }
+
+    pub fn get(&self, region: Region) -> Option<&PriceSeries> {
+        self.series.get(&region)
+    }
+
+    /// Same as `get` for a region code or alias (e.g. "NO1"), `None` if it is not a known region.
+    pub fn get_str(&self, region: &str) -> Option<&PriceSeries> {
+        self.get(region.parse().ok()?)
+    }
+
+    pub fn regions(&self) -> Vec<Region> {
+        self.series.keys().copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.series.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PriceSeries> {
+        self.series.values()
+    }
+
+    /// Returns the price for `region` that is active at `dt`.
+    pub fn price_at(&self, region: Region, dt: DateTime<Utc>) -> Option<&Price> {
+        self.get(region)?.price_at(dt)
+    }
+
+    /// Same as `price_at` for a region code or alias (e.g. "NO1"), `None` if it is not a known region.
+    pub fn price_at_str(&self, region: &str, dt: DateTime<Utc>) -> Option<&Price> {
+        self.get_str(region)?.price_at(dt)
+    }
+
+    /// Returns the prices that start in `from..to` for every region, regions without any are left out.
+    pub fn slice(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        let series = self.series
+            .iter()
+            .filter_map(|(region, s)| Some((*region, s.slice(from, to)?)))
+            .collect();
+
+        Self { series }
+    }
+
+    /// Converts all prices in all regions, e.g. `set.convert(units::convert_to_currency_fraction)`.
+    pub fn convert<F: FnMut(&mut Price)>(&mut self, mut f: F) {
+        for series in self.series.values_mut() {
+            series.convert(&mut f);
+        }
+    }
+}
+
+impl IntoIterator for PriceSet {
+    type Item = PriceSeries;
+    type IntoIter = std::collections::btree_map::IntoValues<Region, PriceSeries>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.series.into_values()
+    }
+}
+
+// Expects prices sorted by `from`.
+fn check(prices: &[Price]) -> SeriesResult<()> {
+    let Some(first) = prices.first() else { return Err(SeriesError::NoPrices) };
+
+    for p in prices.iter() {
+        check_compatible(first, p)?;
+    }
+    for w in prices.windows(2) {
+        if w[1].from < w[0].to {
+            return Err(SeriesError::Overlap { region: first.region, from: w[1].from });
+        }
+    }
+
+    Ok(())
+}
+
+fn check_compatible(a: &Price, b: &Price) -> SeriesResult<()> {
+    if a.region != b.region {
+        return Err(SeriesError::MixedRegions { first: a.region, other: b.region });
+    }
+    if a.currency_unit != b.currency_unit || a.power_unit != b.power_unit {
+        return Err(SeriesError::MixedUnits { region: a.region });
+    }
+
+    Ok(())
+}
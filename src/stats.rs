@@ -0,0 +1,241 @@
+//! Statistics over prices, e.g. from `extract_prices_for_region`.
+//!
+//! Every function takes prices for one region in one unit (currency, currency unit and power unit),
+//! the prices may span several days and mix market time units (15 and 60 minutes).
+//! Results carry the region, units and the time window (first start to last end) they were computed over.
+//!
+//! `min`, `max`, `mean`, `median`, `percentile` and `std_dev` treat every price as one sample,
+//! while `time_weighted_mean` weights every price by its duration (`to - from`),
+//! which is what we want when 15 minute prices are mixed with hourly ones.
+
+use chrono::{DateTime, Utc};
+
+use crate::elspot::Price;
+use crate::error::{
+    StatsError,
+    StatsResult,
+};
+use crate::price_value::{PriceValue, RoundingMode};
+use crate::region::Region;
+use crate::units;
+
+/// A single statistic (e.g. the mean) with the units and window it was computed over.
+#[derive(Debug, Clone)]
+pub struct Stat {
+    pub value: PriceValue,
+    pub region: Region,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub currency_unit: units::Currency,
+    pub power_unit: units::Power,
+}
+
+/// All statistics at once, see `summary`.
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub region: Region,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub currency_unit: units::Currency,
+    pub power_unit: units::Power,
+    /// Amount of prices.
+    pub count: usize,
+    pub min: PriceValue,
+    pub max: PriceValue,
+    pub mean: PriceValue,
+    pub median: PriceValue,
+    pub std_dev: PriceValue,
+    pub time_weighted_mean: PriceValue,
+}
+
+pub fn min(prices: &[Price]) -> StatsResult<Stat> {
+    let value = values(prices)?.into_iter().min().ok_or(StatsError::NoPrices)?;
+
+    stat(prices, value)
+}
+
+pub fn max(prices: &[Price]) -> StatsResult<Stat> {
+    let value = values(prices)?.into_iter().max().ok_or(StatsError::NoPrices)?;
+
+    stat(prices, value)
+}
+
+/// Arithmetic mean, every price counts the same regardless of its duration.
+pub fn mean(prices: &[Price]) -> StatsResult<Stat> {
+    let value = mean_of(&values(prices)?)?;
+
+    stat(prices, value)
+}
+
+pub fn median(prices: &[Price]) -> StatsResult<Stat> {
+    percentile(prices, 50.0)
+}
+
+/// Percentile `p` (0 to 100), linearly interpolated between the two closest prices.
+pub fn percentile(prices: &[Price], p: f64) -> StatsResult<Stat> {
+    let mut values = values(prices)?;
+    values.sort_unstable();
+    let value = percentile_of(&values, p)?;
+
+    stat(prices, value)
+}
+
+/// Population standard deviation.
+pub fn std_dev(prices: &[Price]) -> StatsResult<Stat> {
+    let value = std_dev_of(&values(prices)?)?;
+
+    stat(prices, value)
+}
+
+/// Mean where every price is weighted by its duration (`to - from`).
+pub fn time_weighted_mean(prices: &[Price]) -> StatsResult<Stat> {
+    let value = time_weighted_mean_of(prices)?;
+
+    stat(prices, value)
+}
+
+/// Computes all statistics in one go.
+pub fn summary(prices: &[Price]) -> StatsResult<Summary> {
+    let mut values = values(prices)?;
+    values.sort_unstable();
+    let first = &prices[0];
+    let (from, to) = window(prices);
+
+    Ok(Summary {
+        region: first.region,
+        from,
+        to,
+        currency_unit: first.currency_unit.clone(),
+        power_unit: first.power_unit.clone(),
+        count: values.len(),
+        min: values[0],
+        max: values[values.len() - 1],
+        mean: mean_of(&values)?,
+        median: percentile_of(&values, 50.0)?,
+        std_dev: std_dev_of(&values)?,
+        time_weighted_mean: time_weighted_mean_of(prices)?,
+    })
+}
+
+// Returns the values after checking that the prices can be combined.
+fn values(prices: &[Price]) -> StatsResult<Vec<PriceValue>> {
+    validate(prices)?;
+
+    Ok(prices.iter().map(|p| p.value).collect())
+}
+
+/// Checks that prices are for one region, in one unit, and that every price ends after it starts.
+pub(crate) fn validate(prices: &[Price]) -> StatsResult<()> {
+    let Some(first) = prices.first() else { return Err(StatsError::NoPrices) };
+
+    for p in prices.iter() {
+        if p.region != first.region {
+            return Err(StatsError::MixedRegions { first: first.region, other: p.region });
+        }
+        if p.currency_unit != first.currency_unit || p.power_unit != first.power_unit {
+            return Err(StatsError::MixedUnits { region: first.region });
+        }
+        if p.to <= p.from {
+            return Err(StatsError::InvalidDuration { region: p.region, from: p.from });
+        }
+    }
+
+    Ok(())
+}
+
+fn stat(prices: &[Price], value: PriceValue) -> StatsResult<Stat> {
+    let first = prices.first().ok_or(StatsError::NoPrices)?;
+    let (from, to) = window(prices);
+
+    Ok(Stat {
+        value,
+        region: first.region,
+        from,
+        to,
+        currency_unit: first.currency_unit.clone(),
+        power_unit: first.power_unit.clone(),
+    })
+}
+
+// Prices are not required to be sorted.
+fn window(prices: &[Price]) -> (DateTime<Utc>, DateTime<Utc>) {
+    let from = prices.iter().map(|p| p.from).min().unwrap_or_default();
+    let to = prices.iter().map(|p| p.to).max().unwrap_or_default();
+
+    (from, to)
+}
+
+fn sum_of(values: &[PriceValue]) -> StatsResult<PriceValue> {
+    values
+        .iter()
+        .try_fold(PriceValue::ZERO, |sum, v| sum.checked_add(*v))
+        .ok_or(StatsError::Overflow)
+}
+
+fn mean_of(values: &[PriceValue]) -> StatsResult<PriceValue> {
+    if values.is_empty() {
+        return Err(StatsError::NoPrices);
+    }
+    let count = PriceValue::from(values.len() as i64);
+
+    sum_of(values)?.checked_div(count).ok_or(StatsError::Overflow)
+}
+
+// Expects sorted values.
+fn percentile_of(sorted: &[PriceValue], p: f64) -> StatsResult<PriceValue> {
+    if !(0.0..=100.0).contains(&p) {
+        return Err(StatsError::InvalidPercentile(p));
+    }
+    if sorted.is_empty() {
+        return Err(StatsError::NoPrices);
+    }
+
+    // Computed on decimals, so that e.g. the 90th percentile of 4 prices is exactly at rank 2.7.
+    let rank = PriceValue::from_f64(p)
+        .and_then(|p| p.checked_mul(PriceValue::from(sorted.len() as i64 - 1)))
+        .ok_or(StatsError::InvalidPercentile(p))?
+        .move_comma_left(2);
+    let lower = rank.round_with(0, RoundingMode::TowardZero);
+    let fraction = rank.checked_sub(lower).ok_or(StatsError::Overflow)?;
+    let lower = lower.to_f64() as usize;
+    if fraction.is_zero() {
+        return Ok(sorted[lower]);
+    }
+
+    sorted[lower + 1]
+        .checked_sub(sorted[lower])
+        .and_then(|diff| diff.checked_mul(fraction))
+        .and_then(|part| sorted[lower].checked_add(part))
+        .ok_or(StatsError::Overflow)
+}
+
+fn std_dev_of(values: &[PriceValue]) -> StatsResult<PriceValue> {
+    let mean = mean_of(values)?;
+
+    let mut squares = Vec::with_capacity(values.len());
+    for v in values.iter() {
+        let diff = v.checked_sub(mean).ok_or(StatsError::Overflow)?;
+        squares.push(diff.checked_mul(diff).ok_or(StatsError::Overflow)?);
+    }
+    let variance = mean_of(&squares)?;
+
+    // The square root is the only step that is done with floats.
+    PriceValue::from_f64(variance.to_f64().sqrt()).ok_or(StatsError::Overflow)
+}
+
+fn time_weighted_mean_of(prices: &[Price]) -> StatsResult<PriceValue> {
+    validate(prices)?;
+
+    let mut weighted_sum = PriceValue::ZERO;
+    let mut total_seconds = PriceValue::ZERO;
+    for p in prices.iter() {
+        let seconds = PriceValue::from((p.to - p.from).num_seconds());
+        weighted_sum = p.value
+            .checked_mul(seconds)
+            .and_then(|v| weighted_sum.checked_add(v))
+            .ok_or(StatsError::Overflow)?;
+        total_seconds = total_seconds.checked_add(seconds).ok_or(StatsError::Overflow)?;
+    }
+
+    weighted_sum.checked_div(total_seconds).ok_or(StatsError::Overflow)
+}
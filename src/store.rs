@@ -0,0 +1,245 @@
+//! SQLite price store, enabled with the `sqlite` feature.
+//!
+//! Prices are stored normalized to full currency unit per MWh, one row per region, currency, delivery start and MTU.
+//! The value is kept as the exact decimal text of `Price::value`, it is never converted to a float.
+//!
+//! Storing the same prices again is an upsert: preliminary rows are replaced by what comes in,
+//! while final rows are only replaced by final rows with the same or a newer source version.
+//! That way a day fetched as `Preliminary` is upgraded once it is published as `Final`, and never downgraded.
+//!
+//! The `exchange_rate` of a price is not stored, prices read back always have `exchange_rate: None`.
+//! Store prices as published and convert them after reading, see `exchange::convert`.
+
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::{Connection, params};
+
+use crate::elspot::{Price, PriceExtractor, dataportal_dayaheadprices::{self, states::State}};
+use crate::error::{
+    ElspotError,
+    ElspotResult,
+};
+use crate::price_value::PriceValue;
+use crate::region::Region;
+use crate::units;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS prices (
+    region          TEXT    NOT NULL,
+    delivery_start  TEXT    NOT NULL,
+    delivery_end    TEXT    NOT NULL,
+    delivery_date   TEXT    NOT NULL,
+    mtu_minutes     INTEGER NOT NULL,
+    currency        TEXT    NOT NULL,
+    value           TEXT    NOT NULL,
+    state           TEXT    NOT NULL CHECK (state IN ('Final', 'Preliminary')),
+    source_version  INTEGER NOT NULL,
+    fetched_at      TEXT    NOT NULL,
+    PRIMARY KEY (region, currency, delivery_start, mtu_minutes)
+);
+";
+
+// Timestamps are stored as RFC 3339 in UTC with a fixed format, so that they sort as text.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+/// Where a set of prices came from, stored next to every price.
+#[derive(Clone, Debug)]
+pub struct Source {
+    pub state: State,
+    pub version: u8,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// A price as stored, with its state and source.
+#[derive(Clone, Debug)]
+pub struct StoredPrice {
+    pub price: Price,
+    pub source: Source,
+}
+
+pub struct PriceStore {
+    conn: Connection,
+}
+
+impl PriceStore {
+    /// Opens (or creates) the database at `path` and creates the schema if needed.
+    pub fn open<P: AsRef<Path>>(path: P) -> ElspotResult<Self> {
+        let conn = Connection::open(path).map_err(ElspotError::Sqlite)?;
+
+        Self::with_connection(conn)
+    }
+
+    pub fn open_in_memory() -> ElspotResult<Self> {
+        let conn = Connection::open_in_memory().map_err(ElspotError::Sqlite)?;
+
+        Self::with_connection(conn)
+    }
+
+    /// Uses an existing connection, the schema is created if needed.
+    pub fn with_connection(conn: Connection) -> ElspotResult<Self> {
+        conn.execute_batch(SCHEMA).map_err(ElspotError::Sqlite)?;
+
+        Ok(Self { conn })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Upserts `prices` (for example from `extract_prices_for_region`), returns the amount of rows written.
+    /// Prices in other units are converted back to full currency unit per MWh before they are stored.
+    /// The `exchange_rate` of the prices is not stored.
+    pub fn insert(&mut self, prices: &[Price], source: &Source) -> ElspotResult<usize> {
+        let tx = self.conn.transaction().map_err(ElspotError::Sqlite)?;
+
+        let mut written = 0;
+        {
+            let mut stmt = tx.prepare_cached("
+                INSERT INTO prices (
+                    region, delivery_start, delivery_end, delivery_date, mtu_minutes,
+                    currency, value, state, source_version, fetched_at
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                ON CONFLICT (region, currency, delivery_start, mtu_minutes) DO UPDATE SET
+                    delivery_end = excluded.delivery_end,
+                    delivery_date = excluded.delivery_date,
+                    value = excluded.value,
+                    state = excluded.state,
+                    source_version = excluded.source_version,
+                    fetched_at = excluded.fetched_at
+                WHERE prices.state = 'Preliminary'
+                    OR (excluded.state = 'Final' AND excluded.source_version >= prices.source_version)
+            ").map_err(ElspotError::Sqlite)?;
+
+            for p in prices.iter() {
+                let mut p = p.clone();
+                units::convert_to_currency_full(&mut p);
+                units::convert_to_mwh(&mut p);
+
+                written += stmt.execute(params![
+                    p.region.as_str(),
+                    p.from.format(TIMESTAMP_FORMAT).to_string(),
+                    p.to.format(TIMESTAMP_FORMAT).to_string(),
+                    p.date.to_string(),
+                    p.market_time_unit as i64,
+                    p.currency_unit.country_code_as_str(),
+                    p.value.to_string(),
+                    source.state.as_str(),
+                    source.version,
+                    source.fetched_at.format(TIMESTAMP_FORMAT).to_string(),
+                ]).map_err(ElspotError::Sqlite)?;
+            }
+        }
+
+        tx.commit().map_err(ElspotError::Sqlite)?;
+
+        Ok(written)
+    }
+
+    /// Upserts all regions in `data`, using the state of each region and the version of `data` as source.
+    /// A region without a state is stored as `Preliminary`, so that it is replaced once it is known to be final.
+    pub fn insert_data(&mut self, data: &dataportal_dayaheadprices::PriceData, fetched_at: DateTime<Utc>) -> ElspotResult<usize> {
+        let mut written = 0;
+        for prices in data.extract_prices_all_regions()? {
+            let state = data.state_for_region(prices[0].region.as_str()).unwrap_or(State::Preliminary);
+            let source = Source { state, version: data.version(), fetched_at };
+            written += self.insert(&prices, &source)?;
+        }
+
+        Ok(written)
+    }
+
+    /// Returns prices for `region` in `currency` that start in `from..to`, in time ascending order.
+    pub fn query(&self, region: &str, currency: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> ElspotResult<Vec<Price>> {
+        Ok(self.query_with_source(region, currency, from, to)?
+            .into_iter()
+            .map(|stored| stored.price)
+            .collect())
+    }
+
+    /// Same as `query`, but includes state and source for every price.
+    pub fn query_with_source(
+        &self,
+        region: &str,
+        currency: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> ElspotResult<Vec<StoredPrice>> {
+        // Legacy names are stored as their area code, e.g. "Tr.heim" as "NO3".
+        let region: Region = region.parse()?;
+        let mut stmt = self.conn.prepare_cached("
+            SELECT region, delivery_start, delivery_end, delivery_date, currency, value, state, source_version, fetched_at
+            FROM prices
+            WHERE region = ?1 AND currency = ?2 AND delivery_start >= ?3 AND delivery_start < ?4
+            ORDER BY delivery_start, mtu_minutes
+        ").map_err(ElspotError::Sqlite)?;
+
+        let rows = stmt.query_map(
+            params![
+                region.as_str(),
+                currency,
+                from.format(TIMESTAMP_FORMAT).to_string(),
+                to.format(TIMESTAMP_FORMAT).to_string(),
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, String>(5)?,
+                    row.get::<_, String>(6)?,
+                    row.get::<_, u8>(7)?,
+                    row.get::<_, String>(8)?,
+                ))
+            },
+        ).map_err(ElspotError::Sqlite)?;
+
+        let mut prices = vec![];
+        for row in rows {
+            let (area, start, end, date, currency, value, state, version, fetched_at) = row.map_err(ElspotError::Sqlite)?;
+
+            let from = parse_timestamp(&start)?;
+            let to = parse_timestamp(&end)?;
+            let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .map_err(|_| ElspotError::StoreInvalidColumn { column: "delivery_date", value: date.clone() })?;
+            let unit_error = |e| ElspotError::InvalidUnit { region: area.clone(), date, source: e };
+            let value = value
+                .parse::<PriceValue>()
+                .map_err(|e| ElspotError::InvalidPrice { region: area.clone(), date, source: e })?;
+
+            let price = Price {
+                from,
+                to,
+                date,
+                region: area
+                    .parse()
+                    .map_err(|_| ElspotError::StoreInvalidColumn { column: "region", value: area.clone() })?,
+                value,
+                currency_unit: units::Currency::new(&currency).map_err(unit_error)?,
+                market_time_unit: units::Mtu::new(from, to).map_err(unit_error)?,
+                power_unit: units::Power::MWh,
+                exchange_rate: None,
+            };
+            let state = match state.as_str() {
+                "Final" => State::Final,
+                "Preliminary" => State::Preliminary,
+                _ => return Err(ElspotError::StoreInvalidColumn { column: "state", value: state }),
+            };
+            let source = Source { state, version, fetched_at: parse_timestamp(&fetched_at)? };
+
+            prices.push(StoredPrice { price, source });
+        }
+
+        Ok(prices)
+    }
+}
+
+fn parse_timestamp(s: &str) -> ElspotResult<DateTime<Utc>> {
+    match DateTime::parse_from_rfc3339(s) {
+        Ok(dt) => Ok(dt.to_utc()),
+        Err(_) => Err(ElspotError::StoreInvalidColumn { column: "timestamp", value: s.to_string() }),
+    }
+}
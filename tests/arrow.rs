@@ -0,0 +1,106 @@
+#![cfg(feature = "arrow")]
+
+mod common;
+
+use arrow::datatypes::DataType;
+
+use eb_nordpool::{
+    arrow::{self as price_arrow, VALUE_SCALE},
+    elspot::{self, Price, dataportal_dayaheadprices::states::State},
+    error::ElspotError,
+    units,
+};
+
+fn all_prices() -> Vec<Price> {
+    let data = elspot::from_json(&common::dataportal_json()).unwrap();
+
+    data.extract_prices_all_regions().unwrap().into_iter().flatten().collect()
+}
+
+fn assert_same(a: &[Price], b: &[Price]) {
+    assert_eq!(a.len(), b.len());
+    for (a, b) in a.iter().zip(b) {
+        assert_eq!((a.region, a.from, a.to, a.date), (b.region, b.from, b.to, b.date));
+        assert_eq!(a.value, b.value);
+        assert_eq!(a.currency_unit, b.currency_unit);
+        assert_eq!(a.power_unit, b.power_unit);
+        assert_eq!(a.market_time_unit, b.market_time_unit);
+    }
+}
+
+#[test]
+fn typed_columns() {
+    let data = elspot::from_json(&common::dataportal_json()).unwrap();
+    let batch = price_arrow::record_batch_all_regions(data.as_ref()).unwrap();
+    assert_eq!(48, batch.num_rows());
+    assert_eq!(price_arrow::schema(), batch.schema());
+
+    let schema = batch.schema();
+    assert!(matches!(schema.field_with_name("region").unwrap().data_type(), DataType::Dictionary(_, _)));
+    assert!(matches!(schema.field_with_name("from").unwrap().data_type(), DataType::Timestamp(_, Some(tz)) if tz.as_ref() == "UTC"));
+    assert_eq!(&DataType::Decimal128(38, VALUE_SCALE), schema.field_with_name("value").unwrap().data_type());
+
+    let archived = price_arrow::prices_from_record_batch(&batch).unwrap();
+    let prices: Vec<Price> = archived.iter().map(|a| a.price.clone()).collect();
+    assert_same(&all_prices(), &prices);
+    assert!(archived.iter().all(|a| a.state == Some(State::Final)));
+}
+
+#[test]
+fn parquet_round_trip() {
+    let mut converted = all_prices();
+    converted.iter_mut().for_each(units::convert_to_kwh);
+    converted.iter_mut().for_each(units::convert_to_currency_fraction);
+
+    let dir = common::temp_dir("parquet");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("prices.parquet");
+    let path = path.to_str().unwrap();
+
+    let batches = [
+        price_arrow::record_batch(&all_prices(), Some(State::Preliminary)).unwrap(),
+        price_arrow::record_batch(&converted, None).unwrap(),
+    ];
+    price_arrow::write_parquet(path, &batches).unwrap();
+
+    let read = price_arrow::read_parquet(path).unwrap();
+    assert_eq!(96, read.iter().map(|b| b.num_rows()).sum::<usize>());
+
+    let archived = price_arrow::prices_from_parquet(path).unwrap();
+    let prices: Vec<Price> = archived.iter().map(|a| a.price.clone()).collect();
+    assert_same(&all_prices(), &prices[..48]);
+    assert_same(&converted, &prices[48..]);
+    assert_eq!(Some(State::Preliminary), archived[0].state);
+    assert_eq!(None, archived[48].state);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn values_are_rounded_to_scale() {
+    let mut prices = all_prices();
+    prices[0].value = "0.1234567890125".parse().unwrap();
+    prices[1].value = "-0.1234567890125".parse().unwrap();
+
+    let batch = price_arrow::record_batch(&prices[..2], None).unwrap();
+    let archived = price_arrow::prices_from_record_batch(&batch).unwrap();
+    assert_eq!("0.123456789013", archived[0].price.value.to_string());
+    assert_eq!("-0.123456789013", archived[1].price.value.to_string());
+
+    // Does not fit in 38 digits with 12 decimals.
+    prices[0].value = "1000000000000000000000000000000".parse().unwrap();
+    let res = price_arrow::record_batch(&prices[..1], None);
+    assert!(matches!(res, Err(ElspotError::ArrowInvalidValue { column: "value", .. })));
+}
+
+#[test]
+fn invalid() {
+    let batch = price_arrow::record_batch(&all_prices(), None).unwrap();
+    let res = price_arrow::prices_from_record_batch(&batch.project(&[0, 1]).unwrap());
+    assert!(matches!(res, Err(ElspotError::ArrowInvalidColumn("to"))));
+
+    let res = price_arrow::read_parquet("./tests/data/missing.parquet");
+    assert!(matches!(res, Err(ElspotError::IOError { .. })));
+    let res = price_arrow::read_parquet("./tests/data/dataportal_dayaheadprices_NOK.json");
+    assert!(matches!(res, Err(ElspotError::Parquet(_))));
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "async")]
+
+mod common;
+
+use eb_nordpool::{
+    elspot::{self, PriceExtractor},
+    error::ElspotError,
+};
+
+use common::StandIn;
+
+#[tokio::test]
+async fn from_url() {
+    let server = StandIn::serve(200, common::dataportal_json());
+
+    let data = elspot::r#async::from_url(&server.url).await.unwrap();
+    assert_eq!(data.currency(), "NOK");
+    assert_eq!(data.date().to_string(), "2024-09-22");
+
+    let prices = data.extract_prices_for_region("NO3").unwrap();
+    assert_eq!("182.94", prices[5].value);
+}
+
+#[tokio::test]
+async fn marketdata_page_10_from_url() {
+    let server = StandIn::serve(200, common::page_10_json());
+
+    let data = elspot::r#async::marketdata_page_10::from_url(&server.url).await.unwrap();
+    let prices = data.extract_prices_for_region("Tr.heim").unwrap();
+    assert_eq!("5.82", prices[1].value);
+}
+
+#[tokio::test]
+async fn invalid_response() {
+    let server = StandIn::serve(200, String::from("{}"));
+
+    let res = elspot::r#async::from_url(&server.url).await;
+    assert!(matches!(res, Err(ElspotError::InvalidInputData)));
+}
+
+#[tokio::test]
+async fn from_nordpool_invalid_input() {
+    let res = elspot::r#async::from_nordpool("NOK", "2024-09-22", &[]).await;
+    assert!(matches!(res, Err(ElspotError::DataPortalDayaheadPricesNoRegionsSupplied)));
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "blocking")]
+
+mod common;
+
+use eb_nordpool::elspot::{self, PriceExtractor};
+
+use common::StandIn;
+
+#[test]
+fn from_url() {
+    let server = StandIn::serve(200, common::dataportal_json());
+
+    let data = elspot::from_url(&server.url).unwrap();
+    assert_eq!(data.currency(), "NOK");
+    assert_eq!(server.request_count(), 1);
+}
+
+#[test]
+fn marketdata_page_10_from_url() {
+    let server = StandIn::serve(200, common::page_10_json());
+
+    let data = elspot::marketdata_page_10::from_url(&server.url).unwrap();
+    assert_eq!(data.currency(), "EUR");
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use std::fs;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{SecondsFormat, Utc};
+
+use eb_nordpool::{
+    elspot::{cache::ResponseCache, transport::Transport},
+    error::ElspotResult,
+};
+
+/// Serves `json` and counts requests.
+struct CountingTransport {
+    json: String,
+    requests: Mutex<usize>,
+}
+
+impl CountingTransport {
+    fn new(json: String) -> Self {
+        Self { json, requests: Mutex::new(0) }
+    }
+
+    fn count(&self) -> usize {
+        *self.requests.lock().unwrap()
+    }
+}
+
+impl Transport for CountingTransport {
+    fn get(&self, _url: &str) -> ElspotResult<String> {
+        *self.requests.lock().unwrap() += 1;
+        Ok(self.json.clone())
+    }
+}
+
+fn preliminary_json(updated_at: &str) -> String {
+    common::dataportal_json()
+        .replace("\"Final\"", "\"Preliminary\"")
+        .replace("2024-09-23T07:24:06.5919533Z", updated_at)
+}
+
+#[test]
+fn final_prices_are_served_from_cache() {
+    let dir = common::temp_dir("cache_final");
+    let transport = CountingTransport::new(common::dataportal_json());
+    let cache = ResponseCache::new(&dir, &transport);
+
+    let data = cache.from_nordpool("NOK", "2024-09-22", &["NO3", "DK1"]).unwrap();
+    assert!(data.is_final());
+    // Regions are part of the key regardless of order.
+    let data = cache.from_nordpool("NOK", "2024-09-22", &["DK1", "NO3"]).unwrap();
+    assert_eq!(transport.count(), 1);
+    assert_eq!("182.94", data.extract_prices_for_region("NO3").unwrap()[5].value);
+
+    // The entry is in the same format as to_file writes, so from_file can read it.
+    let path = cache.path_for("NOK", "2024-09-22", &["DK1", "NO3"]).unwrap();
+    eb_nordpool::elspot::from_file(path.to_str().unwrap()).unwrap();
+
+    // Bypass always fetches.
+    let cache = cache.bypass(true);
+    cache.from_nordpool("NOK", "2024-09-22", &["DK1", "NO3"]).unwrap();
+    assert_eq!(transport.count(), 2);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn preliminary_prices_are_refetched() {
+    let dir = common::temp_dir("cache_preliminary");
+
+    // Without a TTL, preliminary prices are always re-fetched.
+    let now = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+    let transport = CountingTransport::new(preliminary_json(&now));
+    let cache = ResponseCache::new(&dir, &transport);
+    assert!(cache.from_nordpool("NOK", "2024-09-22", &["NO3"]).unwrap().is_preliminary());
+    cache.from_nordpool("NOK", "2024-09-22", &["NO3"]).unwrap();
+    assert_eq!(transport.count(), 2);
+
+    // Recently updated preliminary prices are served within the TTL.
+    let cache = cache.ttl(Some(Duration::from_secs(600)));
+    cache.from_nordpool("NOK", "2024-09-22", &["NO3"]).unwrap();
+    assert_eq!(transport.count(), 2);
+
+    // ..but not when updatedAt is older than the TTL.
+    let transport = CountingTransport::new(preliminary_json("2024-09-21T10:00:00Z"));
+    let cache = ResponseCache::new(&dir, &transport).ttl(Some(Duration::from_secs(600)));
+    cache.clear().unwrap();
+    cache.from_nordpool("NOK", "2024-09-22", &["NO3"]).unwrap();
+    cache.from_nordpool("NOK", "2024-09-22", &["NO3"]).unwrap();
+    assert_eq!(transport.count(), 2);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn prices_without_states_are_refetched() {
+    let dir = common::temp_dir("cache_no_states");
+    let json = common::dataportal_json().replace("\"areaStates\":[{\"state\":\"Final\",\"areas\":[\"DK1\",\"NO3\"]}]", "\"areaStates\":[]");
+    let transport = CountingTransport::new(json);
+    let cache = ResponseCache::new(&dir, &transport);
+
+    // is_final() is true for a response without any states, but it is not known to be final.
+    let data = cache.from_nordpool("NOK", "2024-09-22", &["NO3"]).unwrap();
+    assert!(data.is_final());
+    assert!(!data.as_dataportal().unwrap().is_known_final());
+    cache.from_nordpool("NOK", "2024-09-22", &["NO3"]).unwrap();
+    assert_eq!(transport.count(), 2);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn path_for_only_takes_known_codes() {
+    let dir = common::temp_dir("cache_path_for");
+    let transport = CountingTransport::new(common::dataportal_json());
+    let cache = ResponseCache::new(&dir, &transport);
+
+    assert_eq!(dir.join("DayAhead_NOK_2024-09-22_DK1-NO3.json"), cache.path_for("NOK", "2024-09-22", &["NO3", "DK1"]).unwrap());
+    assert!(cache.path_for("../../NOK", "2024-09-22", &["NO3"]).is_err());
+    assert!(cache.path_for("NOK", "2024-09-22/../..", &["NO3"]).is_err());
+    assert!(cache.path_for("NOK", "2024-09-22", &["NO3/../../etc"]).is_err());
+    assert!(cache.from_nordpool("NOK", "2024-09-22", &["../NO3"]).is_err());
+    assert_eq!(transport.count(), 0);
+    assert!(!dir.exists());
+}
+
+#[test]
+fn evicts_least_recently_used() {
+    let dir = common::temp_dir("cache_evict");
+    let transport = CountingTransport::new(common::dataportal_json());
+    let cache = ResponseCache::new(&dir, &transport).max_entries(Some(2));
+
+    cache.from_nordpool("NOK", "2024-09-20", &["NO3"]).unwrap();
+    std::thread::sleep(Duration::from_millis(20));
+    cache.from_nordpool("NOK", "2024-09-21", &["NO3"]).unwrap();
+    std::thread::sleep(Duration::from_millis(20));
+    // Using the first entry makes the second one the least recently used.
+    cache.from_nordpool("NOK", "2024-09-20", &["NO3"]).unwrap();
+    std::thread::sleep(Duration::from_millis(20));
+    cache.from_nordpool("NOK", "2024-09-22", &["NO3"]).unwrap();
+
+    assert!(cache.path_for("NOK", "2024-09-20", &["NO3"]).unwrap().is_file());
+    assert!(!cache.path_for("NOK", "2024-09-21", &["NO3"]).unwrap().is_file());
+    assert!(cache.path_for("NOK", "2024-09-22", &["NO3"]).unwrap().is_file());
+    assert_eq!(transport.count(), 3);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
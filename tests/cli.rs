@@ -0,0 +1,97 @@
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Output};
+
+use eb_nordpool::{elspot, region::Region, units::{self, CurrencyCode}};
+
+const DATAPORTAL_FILE: &str = "./tests/data/dataportal_dayaheadprices_NOK.json";
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_eb-nordpool")).args(args).output().unwrap()
+}
+
+fn stdout(args: &[&str]) -> String {
+    let out = run(args);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+
+    String::from_utf8(out.stdout).unwrap()
+}
+
+#[test]
+fn show_table() {
+    let prices = elspot::from_file(DATAPORTAL_FILE).unwrap().extract_prices(Region::NO3).unwrap();
+
+    let out = stdout(&["show", DATAPORTAL_FILE, "--regions", "NO3"]);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(prices.len() + 1, lines.len());
+    assert!(lines[0].starts_with("REGION"));
+    assert!(lines[6].starts_with("NO3"));
+    assert!(lines[6].ends_with(&prices[5].price_label()));
+    assert!(lines[6].contains(&prices[5].from_to().0.format("%Y-%m-%dT%H:%M%:z").to_string()));
+}
+
+#[test]
+fn show_json_and_csv() {
+    let data = elspot::from_file(DATAPORTAL_FILE).unwrap();
+    let count: usize = data.extract_prices_all_regions().unwrap().iter().map(Vec::len).sum();
+
+    let json: serde_json::Value = serde_json::from_str(&stdout(&["show", DATAPORTAL_FILE, "-f", "json"])).unwrap();
+    let rows = json.as_array().unwrap();
+    assert_eq!(count, rows.len());
+    assert_eq!("NO3", rows.iter().find(|r| r["region"] == "NO3").unwrap()["region"]);
+
+    let prices = data.extract_prices(Region::NO3).unwrap();
+    let no3: Vec<&serde_json::Value> = rows.iter().filter(|r| r["region"] == "NO3").collect();
+    assert_eq!(prices[5].value.to_string(), no3[5]["value"].to_string());
+
+    let csv = stdout(&["show", DATAPORTAL_FILE, "-f", "csv", "-r", "NO3,DK1"]);
+    let mut lines = csv.lines();
+    assert_eq!(Some("region,from,to,value,unit,price"), lines.next());
+    assert_eq!(prices.len() * 2, lines.count());
+}
+
+#[test]
+fn convert_units() {
+    let mut prices = elspot::from_file(DATAPORTAL_FILE).unwrap().extract_prices(Region::NO3).unwrap();
+    prices.iter_mut().for_each(units::convert_to_kwh);
+    prices.iter_mut().for_each(units::convert_to_currency_fraction);
+
+    let csv = stdout(&["convert", DATAPORTAL_FILE, "--kwh", "--fraction", "-r", "NO3", "-f", "csv"]);
+    let row: Vec<&str> = csv.lines().nth(6).unwrap().split(',').collect();
+    assert_eq!(prices[5].value.to_string(), row[3]);
+    assert_eq!("Øre/kWh", row[4]);
+    assert_eq!(prices[5].price_label(), row[5]);
+
+    // At least one unit to convert to.
+    assert_eq!(Some(2), run(&["convert", DATAPORTAL_FILE]).status.code());
+}
+
+#[test]
+fn regions_and_currencies() {
+    let json: serde_json::Value = serde_json::from_str(&stdout(&["regions", "--format", "json"])).unwrap();
+    let rows = json.as_array().unwrap();
+    assert_eq!(Region::ALL.len(), rows.len());
+    let no3 = rows.iter().find(|r| r["code"] == "NO3").unwrap();
+    assert_eq!("Europe/Oslo", no3["timezone"]);
+    assert_eq!("Nordic", no3["group"]);
+    assert!(rows.iter().find(|r| r["code"] == "SYS").unwrap()["eic"].is_null());
+
+    let csv = stdout(&["currencies", "--format", "csv"]);
+    assert_eq!(CurrencyCode::ALL.len() + 1, csv.lines().count());
+    assert!(csv.lines().any(|l| l == "SEK,Kr.,Öre"));
+}
+
+#[test]
+fn exit_codes() {
+    assert_eq!(Some(2), run(&["show"]).status.code());
+    assert_eq!(Some(4), run(&["show", "./tests/data/missing.json"]).status.code());
+    assert_eq!(Some(5), run(&["show", "./Cargo.toml"]).status.code());
+    assert_eq!(Some(6), run(&["show", DATAPORTAL_FILE, "-r", "XX"]).status.code());
+
+    // Invalid queries fail before any request is made.
+    assert_eq!(Some(6), run(&["fetch", "-r", "XX"]).status.code());
+    assert_eq!(Some(6), run(&["fetch", "-r", "NO3", "-c", "USD"]).status.code());
+    let out = run(&["fetch", "-r", "NO3", "-d", "2024-13-01"]);
+    assert_eq!(Some(6), out.status.code());
+    assert!(String::from_utf8_lossy(&out.stderr).starts_with("error: "));
+}
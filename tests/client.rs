@@ -0,0 +1,159 @@
+#![cfg(feature = "blocking")]
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use chrono::{TimeZone, Utc};
+
+use eb_nordpool::{
+    elspot::client::{Client, RetryPolicy},
+    error::ElspotError,
+};
+
+use common::StandIn;
+
+fn fast_retries(max_retries: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_retries,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(5),
+        jitter: true,
+    }
+}
+
+#[test]
+fn from_nordpool_with_base_url() {
+    let server = StandIn::serve(200, common::dataportal_json());
+    let client = Client::builder()
+        .base_url(&format!("{}/api/DayAheadPrices", server.url))
+        .build()
+        .unwrap();
+
+    let data = client.from_nordpool("NOK", "2024-09-22", &["DK1", "NO3"]).unwrap();
+    assert_eq!(data.currency(), "NOK");
+
+    let requests = server.requests.lock().unwrap();
+    assert_eq!(
+        requests[0],
+        "GET /api/DayAheadPrices?market=DayAhead&currency=NOK&date=2024-09-22&deliveryArea=DK1%2CNO3 HTTP/1.1",
+    );
+}
+
+#[test]
+fn retries_server_errors() {
+    let server = StandIn::serve_responses(vec![
+        (503, String::new()),
+        (429, String::new()),
+        (200, common::dataportal_json()),
+    ]);
+    let client = Client::builder().retry_policy(fast_retries(3)).build().unwrap();
+
+    client.from_url(&server.url).unwrap();
+    assert_eq!(server.request_count(), 3);
+}
+
+#[test]
+fn gives_up_after_max_retries() {
+    let server = StandIn::serve(500, String::new());
+    let client = Client::builder().retry_policy(fast_retries(2)).build().unwrap();
+
+    match client.from_url(&server.url) {
+        Err(e @ ElspotError::HttpStatus { status: 500, .. }) => assert!(e.is_retryable()),
+        _ => panic!("expected ElspotError::HttpStatus"),
+    }
+    assert_eq!(server.request_count(), 3);
+}
+
+#[test]
+fn does_not_retry_fatal_errors() {
+    let server = StandIn::serve(404, String::new());
+    let client = Client::builder().retry_policy(fast_retries(3)).build().unwrap();
+
+    match client.from_url(&server.url) {
+        Err(e @ ElspotError::HttpStatus { status: 404, .. }) => assert!(!e.is_retryable()),
+        _ => panic!("expected ElspotError::HttpStatus"),
+    }
+    assert_eq!(server.request_count(), 1);
+
+    // Invalid data is not fixed by asking again.
+    let server = StandIn::serve(200, String::from("{}"));
+    let res = client.from_url(&server.url);
+    assert!(matches!(&res, Err(e) if !e.is_retryable()));
+    assert_eq!(server.request_count(), 1);
+}
+
+#[test]
+fn invalid_configuration() {
+    let res = Client::builder().base_url("not a url").build();
+    assert!(matches!(res, Err(ElspotError::InvalidUrl { .. })));
+
+    let res = Client::builder().header("X Bad", "value").build();
+    assert!(matches!(res, Err(ElspotError::InvalidHttpHeader { .. })));
+}
+
+#[test]
+fn backoff() {
+    let policy = RetryPolicy {
+        max_retries: 5,
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(1000),
+        jitter: false,
+    };
+    assert_eq!(policy.backoff(0), Duration::from_millis(100));
+    assert_eq!(policy.backoff(2), Duration::from_millis(400));
+    assert_eq!(policy.backoff(4), Duration::from_millis(1000));
+
+    let policy = RetryPolicy { jitter: true, ..policy };
+    for retry in 0..5 {
+        let b = policy.backoff(retry);
+        let max = Duration::from_millis(100 * 2u64.pow(retry)).min(Duration::from_millis(1000));
+        assert!(b >= max / 2 && b <= max);
+    }
+}
+
+#[test]
+fn honours_retry_after() {
+    // The backoff alone would wait far longer than the test takes.
+    let policy = RetryPolicy {
+        max_retries: 2,
+        initial_backoff: Duration::from_secs(60),
+        max_backoff: Duration::from_millis(200),
+        jitter: false,
+    };
+    let server = StandIn::serve_responses_with_headers(vec![
+        (429, vec![("Retry-After", "0".to_string())], String::new()),
+        (503, vec![("Retry-After", "Wed, 21 Oct 2015 07:28:00 GMT".to_string())], String::new()),
+        (200, vec![], common::dataportal_json()),
+    ]);
+    let client = Client::builder().retry_policy(policy.clone()).build().unwrap();
+
+    let started = Instant::now();
+    client.from_url(&server.url).unwrap();
+    assert_eq!(server.request_count(), 3);
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    // A longer wait than the policy allows is capped at `max_backoff`.
+    let server = StandIn::serve_responses_with_headers(vec![
+        (429, vec![("Retry-After", "3600".to_string())], String::new()),
+        (200, vec![], common::dataportal_json()),
+    ]);
+    let started = Instant::now();
+    client.from_url(&server.url).unwrap();
+    assert_eq!(server.request_count(), 2);
+    assert!(started.elapsed() >= Duration::from_millis(200) && started.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn retry_after() {
+    let policy = RetryPolicy { max_backoff: Duration::from_secs(30), ..RetryPolicy::default() };
+    let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 27, 50).unwrap();
+
+    assert_eq!(policy.retry_after("5", now), Some(Duration::from_secs(5)));
+    assert_eq!(policy.retry_after(" 120 ", now), Some(Duration::from_secs(30)));
+    assert_eq!(policy.retry_after("Wed, 21 Oct 2015 07:28:00 GMT", now), Some(Duration::from_secs(10)));
+    assert_eq!(policy.retry_after("Wed, 21 Oct 2015 08:28:00 GMT", now), Some(Duration::from_secs(30)));
+    assert_eq!(policy.retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now), Some(Duration::ZERO));
+    assert_eq!(policy.retry_after("-1", now), None);
+    assert_eq!(policy.retry_after("soon", now), None);
+}
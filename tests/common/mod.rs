@@ -0,0 +1,173 @@
+//! Local HTTP stand-in for the Nord Pool servers.
+
+#![allow(dead_code)]
+
+use std::{env, fs};
+use std::path::PathBuf;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Status, extra headers (e.g. `("Retry-After", "1")`) and body of a stand-in response.
+pub type Response = (u16, Vec<(&'static str, String)>, String);
+
+/// A minimal HTTP server answering every request with `status` and `body`.
+/// Each request line (e.g. "GET /api/DayAheadPrices?.. HTTP/1.1") is recorded in `requests`.
+pub struct StandIn {
+    pub url: String,
+    pub requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl StandIn {
+    pub fn serve(status: u16, body: String) -> Self {
+        Self::serve_responses(vec![(status, body)])
+    }
+
+    /// Answers requests with `responses` in order, the last one is repeated when we run out.
+    pub fn serve_responses(responses: Vec<(u16, String)>) -> Self {
+        Self::serve_responses_with_headers(responses.into_iter().map(|(status, body)| (status, vec![], body)).collect())
+    }
+
+    /// Same as `serve_responses`, with extra headers for each response.
+    pub fn serve_responses_with_headers(responses: Vec<Response>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+
+        let recorded = Arc::clone(&requests);
+        thread::spawn(move || {
+            for (i, stream) in listener.incoming().enumerate() {
+                let Ok(mut stream) = stream else { continue };
+
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                // Drain headers, requests from the clients never carry a body.
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                        break;
+                    }
+                }
+                recorded.lock().unwrap().push(request_line.trim_end().to_string());
+
+                let (status, headers, body) = &responses[i.min(responses.len() - 1)];
+                let headers: String = headers.iter().map(|(name, value)| format!("{name}: {value}\r\n")).collect();
+                let response = format!(
+                    "HTTP/1.1 {status} STAND-IN\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{headers}Connection: close\r\n\r\n{body}",
+                    body.len(),
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    pub fn request_count(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+}
+
+pub fn dataportal_json() -> String {
+    fs::read_to_string("./tests/data/dataportal_dayaheadprices_NOK.json").unwrap()
+}
+
+pub fn page_10_json() -> String {
+    fs::read_to_string("./tests/data/marketdata_page_10_EUR_24H.json").unwrap()
+}
+
+/// Returns an empty directory (that does not exist yet) under the systems temp dir.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("eb_nordpool_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+
+    dir
+}
+
+/// Returns the dataportal test data (delivery day 2024-09-22) moved `days` ahead,
+/// e.g. `dataportal_json_for_day(1)` is the same prices for delivery day 2024-09-23.
+pub fn dataportal_json_for_day(days: i64) -> String {
+    use chrono::{Duration, NaiveDate};
+
+    let json = dataportal_json();
+    let day = NaiveDate::from_ymd_opt(2024, 9, 21).unwrap();
+
+    // Replace the dates furthest in the direction we move first, so that we do not move the same date twice.
+    let order: Vec<i64> = if days > 0 { (0..3).rev().collect() } else { (0..3).collect() };
+    order.into_iter().fold(json, |json, i| {
+        let from = (day + Duration::days(i)).format("%Y-%m-%d").to_string();
+        let to = (day + Duration::days(i + days)).format("%Y-%m-%d").to_string();
+        json.replace(&from, &to)
+    })
+}
+
+/// Returns prices for `region` in NOK/MWh starting at `start` (UTC), one per value, each `minutes` long.
+pub fn prices(region: &str, start: &str, minutes: i64, values: &[&str]) -> Vec<eb_nordpool::elspot::Price> {
+    use chrono::{DateTime, Duration, Utc};
+    use eb_nordpool::{elspot::Price, units};
+
+    let start: DateTime<Utc> = start.parse().unwrap();
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let from = start + Duration::minutes(minutes * i as i64);
+            let to = from + Duration::minutes(minutes);
+            Price {
+                from,
+                to,
+                date: from.date_naive(),
+                region: region.parse().unwrap(),
+                value: v.parse().unwrap(),
+                currency_unit: units::Currency::new("NOK").unwrap(),
+                market_time_unit: units::Mtu::new(from, to).unwrap(),
+                power_unit: units::Power::MWh,
+                exchange_rate: None,
+            }
+        })
+        .collect()
+}
+
+/// Returns a minimal dataportal response for delivery day `date` (CET) with hourly prices for `areas`.
+/// The price for the n'th hour of the day is `day of month * 100 + n`, e.g. 2700 to 2724 on a 25 hour 27th.
+pub fn dataportal_json_for_date(date: &str, areas: &[&str]) -> String {
+    use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Utc};
+    use chrono_tz::CET;
+
+    let day: NaiveDate = date.parse().unwrap();
+    let start = day.and_time(NaiveTime::MIN).and_local_timezone(CET).unwrap().with_timezone(&Utc);
+    let end = day.succ_opt().unwrap().and_time(NaiveTime::MIN).and_local_timezone(CET).unwrap().with_timezone(&Utc);
+
+    let hours = (end - start).num_hours();
+    let entries: Vec<serde_json::Value> = (0..hours)
+        .map(|n| {
+            let from = start + Duration::hours(n);
+            let price = day.day() as i64 * 100 + n;
+            let per_area: serde_json::Map<String, serde_json::Value> = areas
+                .iter()
+                .map(|a| (a.to_string(), serde_json::json!(price)))
+                .collect();
+            serde_json::json!({
+                "deliveryStart": from,
+                "deliveryEnd": from + Duration::hours(1),
+                "entryPerArea": per_area,
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "deliveryDateCET": date,
+        "version": 3,
+        "deliveryAreas": areas,
+        "market": "DayAhead",
+        "multiAreaEntries": entries,
+        "blockPriceAggregates": [],
+        "currency": "EUR",
+        "exchangeRate": 1,
+        "areaStates": [{"state": "Final", "areas": areas}],
+        "areaAverages": [],
+    })
+    .to_string()
+}
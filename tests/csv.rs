@@ -0,0 +1,120 @@
+mod common;
+
+use chrono_tz::Europe::Helsinki;
+
+use eb_nordpool::{
+    csv::{self, CsvOptions, Timestamps},
+    elspot::{self, Price},
+    error::CsvError,
+    region::Region,
+    units::{self, CurrencyUnit, Power},
+};
+
+fn all_prices() -> Vec<Price> {
+    let data = elspot::from_json(&common::dataportal_json()).unwrap();
+
+    data.extract_prices_all_regions().unwrap().into_iter().flatten().collect()
+}
+
+fn assert_same(a: &[Price], b: &[Price]) {
+    assert_eq!(a.len(), b.len());
+    for (a, b) in a.iter().zip(b) {
+        assert_eq!((a.region, a.from, a.to, a.date), (b.region, b.from, b.to, b.date));
+        assert_eq!(a.value, b.value);
+        assert_eq!(a.currency_unit, b.currency_unit);
+        assert_eq!(a.power_unit, b.power_unit);
+        assert_eq!(a.market_time_unit, b.market_time_unit);
+    }
+}
+
+#[test]
+fn long_round_trip() {
+    let prices = all_prices();
+    let s = csv::long(&prices, &CsvOptions::new()).unwrap();
+
+    let mut lines = s.lines();
+    assert_eq!(Some("region,date,from,to,value,currency,currency_unit,power_unit"), lines.next());
+    assert_eq!(Some("DK1,2024-09-22,2024-09-21T22:00:00Z,2024-09-21T23:00:00Z,1142.39,NOK,Kr.,MWh"), lines.next());
+    assert_eq!(prices.len() - 1, lines.count());
+
+    assert_same(&prices, &csv::from_long(&s, &CsvOptions::new()).unwrap());
+}
+
+#[test]
+fn nordic_excel() {
+    let prices = all_prices();
+    let options = CsvOptions::nordic()
+        .timestamps(Timestamps::Region)
+        .currency_unit(CurrencyUnit::Fraction)
+        .power_unit(Power::kWh);
+    let s = csv::long(&prices, &options).unwrap();
+    assert_eq!(
+        Some("DK1;2024-09-22;2024-09-22T00:00:00+02:00;2024-09-22T01:00:00+02:00;114,239;NOK;Øre;kWh"),
+        s.lines().nth(1),
+    );
+
+    let mut converted = prices.clone();
+    converted.iter_mut().for_each(units::convert_to_currency_fraction);
+    converted.iter_mut().for_each(units::convert_to_kwh);
+    assert_same(&converted, &csv::from_long(&s, &options).unwrap());
+
+    // A different order of columns, and quoted fields.
+    let s = "\"value\";\"region\";currency;currency_unit;power_unit;date;from;to\n\
+             \"114,239\";\"DK1\";NOK;Øre;kWh;2024-09-22;2024-09-22T00:00:00+02:00;2024-09-22T01:00:00+02:00\n";
+    assert_same(&converted[..1], &csv::from_long(s, &options).unwrap());
+}
+
+#[test]
+fn wide() {
+    let prices = all_prices();
+    let s = csv::wide(&prices, &CsvOptions::new().timestamps(Timestamps::Tz(Helsinki))).unwrap();
+
+    let lines: Vec<&str> = s.lines().collect();
+    assert_eq!("from,to,DK1,NO3", lines[0]);
+    assert_eq!(25, lines.len());
+
+    let no3: Vec<&Price> = prices.iter().filter(|p| p.region == Region::NO3).collect();
+    let row: Vec<&str> = lines[6].split(',').collect();
+    assert_eq!("2024-09-22T06:00:00+03:00", row[0]);
+    assert_eq!(no3[5].value.to_string(), row[3]);
+
+    // A region without a price for a period is left empty.
+    let s = csv::wide(&prices[1..], &CsvOptions::new()).unwrap();
+    assert_eq!(Some(""), s.lines().nth(1).unwrap().split(',').nth(2));
+}
+
+#[test]
+fn file_round_trip() {
+    let prices = all_prices();
+    let dir = common::temp_dir("csv");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("prices.csv");
+    let path = path.to_str().unwrap();
+
+    csv::to_file(path, &csv::long(&prices, &CsvOptions::nordic()).unwrap()).unwrap();
+    assert_same(&prices, &csv::from_file(path, &CsvOptions::nordic()).unwrap());
+
+    assert!(matches!(csv::from_file("./tests/data/missing.csv", &CsvOptions::new()), Err(CsvError::IOError { .. })));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn invalid() {
+    let prices = all_prices();
+    let same = CsvOptions::new().decimal_separator(',');
+    assert!(matches!(csv::long(&prices, &same), Err(CsvError::InvalidOptions { delimiter: ',', decimal_separator: ',' })));
+    assert!(csv::wide(&prices, &CsvOptions::new().delimiter('-')).is_err());
+
+    let res = csv::from_long("region,date,from,to,value\n", &CsvOptions::new());
+    assert!(matches!(res, Err(CsvError::MissingColumn("currency"))));
+    assert!(matches!(csv::from_long("", &CsvOptions::new()), Err(CsvError::MissingColumn(_))));
+
+    let s = csv::long(&prices[..2], &CsvOptions::new()).unwrap();
+    let rows: Vec<&str> = s.lines().collect();
+    let res = csv::from_long(&[rows[0], rows[1], &rows[2].replace("NOK,Kr.", "NOK,Cent")].join("\n"), &CsvOptions::new());
+    assert!(matches!(res, Err(CsvError::InvalidRow { line: 3, .. })));
+    let res = csv::from_long(&[rows[0], &rows[1].replacen("DK1", "XX", 1)].join("\n"), &CsvOptions::new());
+    assert!(matches!(res, Err(CsvError::InvalidRow { line: 2, .. })));
+    let res = csv::from_long(&[rows[0], &rows[1].replace("1142.39", "11x")].join("\n"), &CsvOptions::new());
+    assert!(matches!(res, Err(CsvError::InvalidRow { line: 2, .. })));
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use eb_nordpool::{
+    clock::{Clock, FixedClock},
+    elspot::{self, current, transport::Transport},
+    error::ElspotResult,
+};
+
+const BASE_URL: &str = "http://stand-in/api/DayAheadPrices";
+
+/// Answers every request with `body` and records the urls.
+struct Recorder {
+    body: String,
+    urls: Mutex<Vec<String>>,
+}
+
+impl Recorder {
+    fn new(body: String) -> Self {
+        Self { body, urls: Mutex::new(vec![]) }
+    }
+
+    fn urls(&self) -> Vec<String> {
+        self.urls.lock().unwrap().clone()
+    }
+}
+
+impl Transport for Recorder {
+    fn get(&self, url: &str) -> ElspotResult<String> {
+        self.urls.lock().unwrap().push(url.to_string());
+        Ok(self.body.clone())
+    }
+}
+
+fn dt(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+#[test]
+fn fixed_clock() {
+    let clock = FixedClock::new(dt("2024-09-22T03:30:00Z"));
+    assert_eq!(clock.now(), dt("2024-09-22T03:30:00Z"));
+
+    clock.advance(Duration::minutes(45));
+    assert_eq!(clock.now(), dt("2024-09-22T04:15:00Z"));
+
+    clock.set(dt("2024-09-21T22:00:00Z"));
+    assert_eq!(clock.now(), dt("2024-09-21T22:00:00Z"));
+}
+
+#[test]
+fn price_now_with() {
+    let transport = Recorder::new(common::dataportal_json());
+    let clock = FixedClock::new(dt("2024-09-22T03:30:00Z"));
+
+    let price = current::price_now_with(&transport, BASE_URL, &clock, "NOK", "NO3").unwrap().unwrap();
+    assert_eq!("182.94", price.value);
+    assert_eq!(price.from, dt("2024-09-22T03:00:00Z"));
+
+    let urls = transport.urls();
+    assert_eq!(urls.len(), 1);
+    assert!(urls[0].contains("date=2024-09-22"));
+    assert!(urls[0].contains("deliveryArea=NO3"));
+}
+
+#[test]
+fn today_near_midnight() {
+    let transport = Recorder::new(common::dataportal_json());
+    // 00:30 in Oslo, so today is already the 22nd.
+    let clock = FixedClock::new(dt("2024-09-21T22:30:00Z"));
+    assert_eq!(current::local_date(&clock, "NO3").unwrap(), NaiveDate::from_ymd_opt(2024, 9, 22).unwrap());
+
+    let prices = current::today_with(&transport, BASE_URL, &clock, "NOK", "NO3").unwrap().unwrap();
+    assert_eq!(prices.len(), 24);
+    assert!(transport.urls()[0].contains("date=2024-09-22"));
+
+    // Data for another day than today is not returned.
+    let data = elspot::from_json(&common::dataportal_json()).unwrap();
+    clock.set(dt("2024-09-21T21:30:00Z"));
+    assert!(current::today_in(&[data.as_ref()], &clock, "NO3").unwrap().is_none());
+    assert_eq!(current::tomorrow_in(&[data.as_ref()], &clock, "NO3").unwrap().unwrap().len(), 24);
+}
+
+#[test]
+fn local_date_in_finland() {
+    // 00:30 on the 23rd in Helsinki, but still the 22nd in CET.
+    let clock = FixedClock::new(dt("2024-09-22T21:30:00Z"));
+    assert_eq!(current::local_date(&clock, "FI").unwrap(), NaiveDate::from_ymd_opt(2024, 9, 23).unwrap());
+    assert_eq!(current::delivery_date(clock.now()), NaiveDate::from_ymd_opt(2024, 9, 22).unwrap());
+
+    let transport = Recorder::new(common::dataportal_json());
+    let res = current::price_now_with(&transport, BASE_URL, &clock, "NOK", "FI").unwrap();
+    assert!(res.is_none());
+    assert!(transport.urls()[0].contains("date=2024-09-22"));
+}
+
+#[test]
+fn tomorrow_not_published() {
+    let transport = Recorder::new(String::new());
+    let clock = FixedClock::new(dt("2024-09-22T08:00:00Z"));
+
+    let res = current::tomorrow_with(&transport, BASE_URL, &clock, "NOK", "NO3").unwrap();
+    assert!(res.is_none());
+    assert!(transport.urls()[0].contains("date=2024-09-23"));
+}
+
+#[test]
+fn dst_days() {
+    let data = elspot::from_file("./tests/data/marketdata_page_10_NOK_25H.json").unwrap();
+    // 02:30 CEST, the first of the two hours starting 02:00 local time.
+    let clock = FixedClock::new(dt("2022-10-30T00:30:00Z"));
+    assert_eq!(current::today_in(&[data.as_ref()], &clock, "Tr.heim").unwrap().unwrap().len(), 25);
+
+    clock.set(dt("2022-10-30T01:30:00Z"));
+    let price = current::price_now_in(data.as_ref(), &clock, "Tr.heim").unwrap().unwrap();
+    assert_eq!("167.66", price.value);
+    assert_eq!(price.from, dt("2022-10-30T01:00:00Z"));
+
+    let data = elspot::from_file("./tests/data/marketdata_page_10_NOK_23H.json").unwrap();
+    let clock = FixedClock::new(dt("2023-03-26T21:59:59Z"));
+    assert_eq!(current::today_in(&[data.as_ref()], &clock, "Tr.heim").unwrap().unwrap().len(), 23);
+    clock.advance(Duration::seconds(1));
+    assert!(current::today_in(&[data.as_ref()], &clock, "Tr.heim").unwrap().is_none());
+    assert!(current::price_now_in(data.as_ref(), &clock, "Tr.heim").unwrap().is_none());
+}
@@ -1,4 +1,20 @@
-use eb_nordpool::{elspot, units};
+use std::error::Error;
+
+use eb_nordpool::{
+    elspot::{
+        self,
+        PriceExtractor,
+        dataportal_dayaheadprices::{
+            PriceData,
+            aggregates::BlockName,
+            query::QueryOptions,
+            states::State,
+        },
+    },
+    error::ElspotError,
+    region::Region,
+    units,
+};
 
 #[allow(unused_imports)]
 use chrono::Local;
@@ -14,25 +30,25 @@ fn from_file() {
             assert!(data.has_region("NO3"));
             assert_eq!(data.currency(), "NOK");
 
-            let mut prices = data.extract_prices_for_region("NO3");
+            let mut prices = data.extract_prices_for_region("NO3").unwrap();
             assert_eq!("182.94", prices[5].value);
 
-            for mut p in prices.iter_mut() {
+            for p in prices.iter_mut() {
                 let v = p.as_f32();
                 assert!(v > 170.0);
                 assert!(v < 230.0);
 
-                units::convert_to_kwh(&mut p);
+                units::convert_to_kwh(p);
                 let v = p.as_f32();
                 assert!(v > 0.17);
                 assert!(v < 0.23);
 
-                units::convert_to_currency_fraction(&mut p);
+                units::convert_to_currency_fraction(p);
                 let v = p.as_f32();
                 assert!(v > 17.0);
                 assert!(v < 23.0);
 
-                units::convert_to_mwh(&mut p);
+                units::convert_to_mwh(p);
                 let v = p.as_f32();
                 assert!(v < 23000.0);
                 assert!(v > 17000.0);
@@ -49,9 +65,107 @@ fn to_json_string() {
     let data = elspot::from_file("./tests/data/dataportal_dayaheadprices_NOK.json").unwrap();
 
     // Save data to a string (serialized json).
-    let s = data.to_json_string();
+    let s = data.to_json_string().unwrap();
 
     // We just reload the string and see if it works, unwrap() will fail if Err.
     elspot::from_json(&s).unwrap();
 }
 
+#[test]
+fn exact_prices() {
+    // More significant digits than a float holds, trailing zeros and an exponent (as written by serde_json for floats).
+    let json = std::fs::read_to_string("./tests/data/dataportal_dayaheadprices_NOK.json")
+        .unwrap()
+        .replace("\"NO3\":179.08", "\"NO3\":12345.6789")
+        .replace("\"exchangeRate\":11.68922", "\"exchangeRate\":11.689220000000000001");
+    let json = json.replacen("\"NO3\":182.94", "\"NO3\":182.940", 1).replacen("\"DK1\":0.12", "\"DK1\":1.2e-1", 1);
+
+    let values = |prices: Vec<elspot::Price>| prices.iter().map(|p| p.value.to_string()).collect::<Vec<_>>();
+    let data = elspot::from_json(&json).unwrap();
+    let no3 = values(data.extract_prices_for_region("NO3").unwrap());
+    assert!(no3.contains(&"12345.6789".to_string()));
+    assert!(no3.contains(&"182.940".to_string()));
+    assert!(values(data.extract_prices_for_region("DK1").unwrap()).contains(&"0.12".to_string()));
+    assert_eq!("11.689220000000000001", data.as_dataportal().unwrap().exchange_rate().to_string());
+
+    // Written back as the same numbers.
+    let s = data.to_json_string().unwrap();
+    assert!(s.contains("\"NO3\":12345.6789"));
+    assert!(s.contains("\"exchangeRate\":11.689220000000000001"));
+    let reloaded = elspot::from_json(&s).unwrap();
+    assert_eq!(values(reloaded.extract_prices_for_region("NO3").unwrap()), no3);
+}
+
+
+#[test]
+fn query_options() {
+    let mut q = QueryOptions::new();
+    assert!(matches!(q.build_url(), Err(ElspotError::DataPortalDayaheadPricesMissingDate)));
+
+    assert!(matches!(q.set_currency("USD"), Err(ElspotError::DataPortalDayaheadPricesUnsupportedCurrency(c)) if c == "USD"));
+    assert!(matches!(q.set_date("2024-13-22"), Err(ElspotError::DataPortalDayaheadPricesInvalidDate { .. })));
+    assert!(matches!(q.set_regions(&["NO3", "Oslo"]), Err(ElspotError::DataPortalDayaheadPricesUnsupportedRegion(r)) if r == "Oslo"));
+
+    q.set_currency("NOK").unwrap();
+    q.set_date("2024-09-22").unwrap();
+    q.set_regions(&["NO3"]).unwrap();
+    let url = q.build_url().unwrap();
+    assert!(url.ends_with("?market=DayAhead&currency=NOK&date=2024-09-22&deliveryArea=NO3"));
+}
+
+#[test]
+fn missing_file() {
+    match elspot::from_file("./tests/data/does_not_exist.json") {
+        Err(e @ ElspotError::IOError { .. }) => assert!(e.source().is_some()),
+        _ => panic!("expected ElspotError::IOError"),
+    }
+}
+
+#[test]
+fn aggregates_averages_and_states() {
+    let json_str = std::fs::read_to_string("./tests/data/dataportal_dayaheadprices_NOK.json").unwrap();
+    let data = PriceData::new(&json_str).unwrap();
+
+    let blocks = data.block_aggregates_for_region("NO3").unwrap();
+    assert_eq!(3, blocks.len());
+    assert_eq!(BlockName::OffPeak1, blocks[0].block);
+    assert_eq!(BlockName::Peak, blocks[1].block);
+    assert_eq!("Off-peak 2", blocks[2].block.to_string());
+    assert_eq!("2024-09-22T06:00:00Z", blocks[1].from.to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+    assert_eq!("2024-09-22T18:00:00Z", blocks[1].to.to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+    assert_eq!("179.08", blocks[1].min);
+    assert_eq!("193.17", blocks[1].average);
+    assert_eq!("218", blocks[1].max);
+    assert_eq!("NOK", blocks[1].currency_unit.country_code_as_str());
+    assert!(blocks[1].power_unit.is_mwh());
+    assert!(data.block_aggregates_for_region("SE3").unwrap().is_empty());
+    assert_eq!(2, data.block_aggregates_all_regions().unwrap().len());
+
+    let average = data.area_average_for_region("DK1").unwrap().unwrap();
+    assert_eq!("892.5", average.value.unwrap());
+    assert_eq!("DK1", average.region);
+    assert!(data.area_average_for_region("SE3").unwrap().is_none());
+    assert_eq!(2, data.area_averages().unwrap().len());
+
+    assert_eq!("11.68922", data.exchange_rate());
+
+    assert_eq!(Some(State::Final), data.state_for_region("NO3"));
+    assert_eq!(None, elspot::from_file("./tests/data/marketdata_page_10_NOK_23H.json").unwrap().as_dataportal().map(|d| d.version()));
+    let boxed = elspot::from_json(&json_str).unwrap();
+    assert_eq!(Some(State::Final), boxed.as_dataportal().unwrap().state_for_region("DK1"));
+    assert_eq!(None, data.state_for_region("SE3"));
+
+    // States are per region, so a day can be partly final.
+    let json_str = json_str.replace(
+        r#""areaStates":[{"state":"Final","areas":["DK1","NO3"]}]"#,
+        r#""areaStates":[{"state":"Final","areas":["DK1"]},{"state":"Preliminary","areas":["NO3"]}]"#,
+    );
+    let data = PriceData::new(&json_str).unwrap();
+    assert!(!data.is_final());
+    assert_eq!(Some(State::Final), data.state_for_region("DK1"));
+    assert_eq!(Some(State::Preliminary), data.state_for_region("NO3"));
+    assert_eq!(
+        vec![(Region::DK1, State::Final), (Region::NO3, State::Preliminary)],
+        data.region_states(),
+    );
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "blocking")]
+
 
 use chrono::Local;
 
@@ -24,16 +26,16 @@ fn from_nordpool() {
     // let regions: Vec<&str> = vec!["BG"];
 
     let data = elspot::from_nordpool(currency, &date, &regions).unwrap();
-    let mut regions = data.extract_prices_all_regions();
+    let mut regions = data.extract_prices_all_regions().unwrap();
 
     println!("Date: {}\n", data.date());
     for prices in regions.iter_mut() {
-        for mut p in prices.iter_mut() {
+        for p in prices.iter_mut() {
             let (from, _) = p.from_to();
             println!("{from}");
             println!("{}: {} | float: {}", p.region, p.price_label(), p.as_f32());
-            units::convert_to_kwh(&mut p);
-            units::convert_to_currency_fraction(&mut p);
+            units::convert_to_kwh(p);
+            units::convert_to_currency_fraction(p);
             println!("{}: {} | float: {}\n", p.region, p.price_label(), p.as_f32());
         }
     }
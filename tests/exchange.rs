@@ -0,0 +1,175 @@
+mod common;
+
+use chrono::NaiveDate;
+
+use eb_nordpool::{
+    elspot::{
+        self,
+        PriceExtractor,
+        dataportal_dayaheadprices::{PriceData, currencies::{self, SUPPORTED_CURRENCIES}},
+    },
+    error::{ExchangeError, UnitError},
+    exchange::{self, RateTable, ecb},
+    price_value::PriceValue,
+    units::{self, CurrencyCode, CurrencyUnit},
+};
+
+fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+fn value(s: &str) -> PriceValue {
+    s.parse().unwrap()
+}
+
+#[test]
+fn one_currency_list() {
+    assert_eq!(SUPPORTED_CURRENCIES.len(), CurrencyCode::ALL.len());
+    for (code, s) in CurrencyCode::ALL.iter().zip(SUPPORTED_CURRENCIES) {
+        assert_eq!(s, code.to_string());
+        assert_eq!(*code, s.parse::<currencies::Currency>().unwrap());
+        assert_eq!(*code, units::Currency::new(s).unwrap().code());
+    }
+    assert!(matches!("XYZ".parse::<CurrencyCode>(), Err(UnitError::InvalidCurrencyUnit(_))));
+
+    let c = units::Currency::from_code(CurrencyCode::SEK, CurrencyUnit::Fraction);
+    assert_eq!(CurrencyCode::SEK, c.code());
+    assert_eq!(CurrencyUnit::Fraction, c.unit());
+    assert_eq!("Öre", c.as_str());
+}
+
+#[test]
+fn dataset_exchange_rate() {
+    let data = PriceData::new(&common::dataportal_json()).unwrap();
+    let table = data.rate_table().unwrap();
+    assert_eq!(vec![date("2024-09-22")], table.dates());
+    assert_eq!(Some(value("11.68922")), table.get(CurrencyCode::NOK, date("2024-09-22")));
+    assert_eq!(None, table.get(CurrencyCode::NOK, date("2024-09-23")));
+
+    let prices = data.extract_prices_for_region("NO3").unwrap();
+    let eur = exchange::convert(&prices[5], CurrencyCode::EUR, &table).unwrap();
+    assert_eq!(CurrencyCode::EUR, eur.currency_unit.code());
+    assert_eq!("15.65", eur.rounded());
+
+    let rate = eur.exchange_rate.clone().unwrap();
+    assert_eq!((CurrencyCode::NOK, CurrencyCode::EUR), (rate.from, rate.to));
+    assert_eq!(date("2024-09-22"), rate.date);
+
+    // And back again.
+    let nok = exchange::convert(&eur, CurrencyCode::NOK, &table).unwrap();
+    assert_eq!("182.94", nok.rounded());
+    assert_eq!(value("11.68922"), nok.exchange_rate.unwrap().rate);
+
+    // Prices already in the currency are not touched.
+    let same = exchange::convert(&prices[5], CurrencyCode::NOK, &table).unwrap();
+    assert!(same.exchange_rate.is_none());
+    assert_eq!(prices[5].value, same.value);
+}
+
+#[test]
+fn user_rate_table() {
+    let mut table = RateTable::new();
+    table.insert(date("2024-09-22"), CurrencyCode::NOK, value("11.5")).unwrap();
+    table.insert(date("2024-09-22"), CurrencyCode::SEK, value("11.25")).unwrap();
+    table.insert(date("2024-09-22"), CurrencyCode::EUR, value("2")).unwrap();
+    assert_eq!(Some(value("1")), table.get(CurrencyCode::EUR, date("2024-09-22")));
+
+    // Cross rate through EUR, the currency unit is kept.
+    let mut prices = common::prices("NO1", "2024-09-22T00:00:00Z", 60, &["115", "23"]);
+    prices.iter_mut().for_each(units::convert_to_currency_fraction);
+    let sek = exchange::convert_all(&prices, CurrencyCode::SEK, &table).unwrap();
+    assert_eq!("11250", sek[0].rounded());
+    assert_eq!(units::Currency::from_code(CurrencyCode::SEK, CurrencyUnit::Fraction), sek[0].currency_unit);
+    assert_eq!(value("0.978260869565"), sek[0].exchange_rate.as_ref().unwrap().rate);
+
+    let res = exchange::convert(&prices[0], CurrencyCode::DKK, &table);
+    assert!(matches!(res, Err(ExchangeError::NoRate { currency: CurrencyCode::DKK, .. })));
+
+    let res = table.insert(date("2024-09-22"), CurrencyCode::DKK, value("0"));
+    assert!(matches!(res, Err(ExchangeError::InvalidRate { currency: CurrencyCode::DKK, .. })));
+    let res = table.insert(date("2024-09-22"), CurrencyCode::DKK, value("-7.4"));
+    assert!(res.is_err());
+}
+
+#[test]
+fn convert_price_set() {
+    let data = elspot::from_json(&common::dataportal_json()).unwrap();
+    let table = data.as_dataportal().unwrap().rate_table().unwrap();
+
+    let prices = data.extract_prices_for_region("DK1").unwrap();
+    let eur = exchange::convert_all(&prices, CurrencyCode::EUR, &table).unwrap();
+    assert_eq!(prices.len(), eur.len());
+    assert!(eur.iter().all(|p| p.currency_unit.code() == CurrencyCode::EUR));
+}
+
+#[test]
+fn ecb_files() {
+    let daily = ecb::from_file("./tests/data/ecb/eurofxref-daily.xml").unwrap();
+    assert_eq!(vec![date("2024-09-23")], daily.dates());
+    assert_eq!(Some(value("11.757")), daily.get(CurrencyCode::NOK, date("2024-09-23")));
+    assert_eq!(Some(value("1.9558")), daily.get(CurrencyCode::BGN, date("2024-09-23")));
+
+    let csv = ecb::from_file("./tests/data/ecb/eurofxref.csv").unwrap();
+    assert_eq!(vec![date("2024-09-23")], csv.dates());
+    assert_eq!(daily.get(CurrencyCode::SEK, date("2024-09-23")), csv.get(CurrencyCode::SEK, date("2024-09-23")));
+
+    let hist = ecb::from_file("./tests/data/ecb/eurofxref-hist.csv").unwrap();
+    assert_eq!(5, hist.dates().len());
+    assert_eq!(Some(value("8.303")), hist.get(CurrencyCode::NOK, date("2004-05-03")));
+    // "N/A", RON did not exist yet.
+    assert_eq!(None, hist.get(CurrencyCode::RON, date("2004-05-03")));
+
+    let xml = "<Cube><Cube time=\"2024-09-20\"><Cube currency=\"NOK\" rate=\"11.7165\" /></Cube></Cube>";
+    let table = ecb::from_xml_str(xml).unwrap();
+    assert_eq!(Some(value("11.7165")), table.get(CurrencyCode::NOK, date("2024-09-20")));
+}
+
+#[test]
+fn ecb_invalid_data() {
+    assert!(matches!(ecb::from_file("./tests/data/ecb/missing.csv"), Err(ExchangeError::IOError { .. })));
+    assert!(matches!(ecb::from_file("./tests/data/ecb/eurofxref.zip"), Err(ExchangeError::EcbUnsupportedFile(_))));
+    assert!(matches!(ecb::from_xml_str("<html></html>"), Err(ExchangeError::EcbInvalidData { .. })));
+    assert!(matches!(ecb::from_csv_str(""), Err(ExchangeError::EcbInvalidData { line: 1, .. })));
+
+    let res = ecb::from_csv_str("Date,NOK,\n2024-09-20,11.7165,\n2024-09-19,abc,\n");
+    assert!(matches!(res, Err(ExchangeError::EcbInvalidData { line: 3, .. })));
+    let res = ecb::from_csv_str("Date,NOK,\n20.09.2024,11.7165,\n");
+    assert!(matches!(res, Err(ExchangeError::EcbInvalidData { line: 2, .. })));
+    let res = ecb::from_xml_str("<Cube time='2024-09-20'><Cube currency='NOK' rate='0'/></Cube>");
+    assert!(matches!(res, Err(ExchangeError::InvalidRate { currency: CurrencyCode::NOK, .. })));
+}
+
+#[test]
+fn ecb_previous_business_day() {
+    let table = ecb::from_file("./tests/data/ecb/eurofxref-hist.csv").unwrap();
+
+    // Sunday prices use the rates from Friday.
+    let prices = elspot::from_json(&common::dataportal_json()).unwrap().extract_prices_for_region("NO3").unwrap();
+    assert_eq!(date("2024-09-22"), prices[5].date);
+    let sek = exchange::convert_all(&prices, CurrencyCode::SEK, &table).unwrap();
+    let rate = sek[5].exchange_rate.clone().unwrap();
+    assert_eq!(date("2024-09-20"), rate.date);
+    assert_eq!(value("0.968676652584"), rate.rate);
+    assert_eq!("177.21", sek[5].rounded());
+
+    let eur = exchange::convert(&prices[5], CurrencyCode::EUR, &table).unwrap();
+    assert_eq!(value("0.08534972048"), eur.exchange_rate.as_ref().unwrap().rate);
+    assert_eq!("15.61", eur.rounded());
+
+    // The last rates are used for up to a week.
+    let rate = table.exchange_rate(CurrencyCode::NOK, CurrencyCode::PLN, date("2024-09-29")).unwrap();
+    assert_eq!(date("2024-09-23"), rate.date);
+    let res = table.exchange_rate(CurrencyCode::NOK, CurrencyCode::PLN, date("2024-10-01"));
+    assert!(matches!(res, Err(ExchangeError::NoRate { currency: CurrencyCode::NOK, .. })));
+    let res = table.exchange_rate(CurrencyCode::NOK, CurrencyCode::EUR, date("2024-09-17"));
+    assert!(matches!(res, Err(ExchangeError::NoRate { currency: CurrencyCode::NOK, .. })));
+    let res = table.exchange_rate(CurrencyCode::NOK, CurrencyCode::RON, date("2004-05-04"));
+    assert!(matches!(res, Err(ExchangeError::NoRate { currency: CurrencyCode::RON, .. })));
+
+    // Tables built by hand use exact dates only, unless asked to fall back.
+    let mut exact = RateTable::new();
+    exact.insert(date("2024-09-20"), CurrencyCode::NOK, value("11.7165")).unwrap();
+    assert!(exchange::convert(&prices[5], CurrencyCode::EUR, &exact).is_err());
+    let rate = exact.fallback_days(2).exchange_rate(CurrencyCode::NOK, CurrencyCode::EUR, date("2024-09-22")).unwrap();
+    assert_eq!(date("2024-09-20"), rate.date);
+}
@@ -0,0 +1,192 @@
+#![cfg(feature = "exporter")]
+
+mod common;
+
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+
+use chrono::{DateTime, Duration, Utc};
+
+use eb_nordpool::{
+    clock::FixedClock,
+    elspot::transport::ReqwestTransport,
+    exporter::{Exporter, METRICS_CONTENT_TYPE},
+    metrics::MetricsOptions,
+    units::{CurrencyUnit, Power},
+};
+
+use common::{StandIn, dataportal_json, dataportal_json_for_date};
+
+fn clock(now: &str) -> FixedClock {
+    FixedClock::new(now.parse::<DateTime<Utc>>().unwrap())
+}
+
+#[test]
+fn metrics_from_stand_in() {
+    // Tomorrow is not published yet, which the data portal answers with an empty body.
+    let stand_in = StandIn::serve_responses(vec![(200, dataportal_json()), (200, String::new())]);
+    let clock = clock("2024-09-22T10:30:00Z");
+    let exporter = Exporter::new(ReqwestTransport, &clock, &stand_in.url, &["NOK"], &["DK1", "NO3"]).unwrap();
+
+    exporter.refresh().unwrap();
+    let requests = stand_in.requests.lock().unwrap().clone();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].contains("date=2024-09-22"));
+    assert!(requests[1].contains("date=2024-09-23"));
+    assert!(requests[0].contains("currency=NOK"));
+    assert!(requests[0].contains("deliveryArea=DK1%2CNO3"));
+
+    let metrics = exporter.metrics();
+    for line in [
+        "# TYPE elspot_price gauge",
+        "elspot_price{region=\"DK1\",currency=\"NOK\",unit=\"Kr./MWh\"} 113.15",
+        "elspot_price{region=\"NO3\",currency=\"NOK\",unit=\"Kr./MWh\"} 179.08",
+        "elspot_price_next{region=\"DK1\",currency=\"NOK\",unit=\"Kr./MWh\"} 23.73",
+        "elspot_price_next{region=\"NO3\",currency=\"NOK\",unit=\"Kr./MWh\"} 179.43",
+        "elspot_price_day_min{region=\"DK1\",currency=\"NOK\",unit=\"Kr./MWh\"} 0.12",
+        "elspot_price_day_max{region=\"DK1\",currency=\"NOK\",unit=\"Kr./MWh\"} 1837.31",
+        "elspot_price_day_avg{region=\"NO3\",currency=\"NOK\",unit=\"Kr./MWh\"} 190.54",
+        "# TYPE elspot_exporter_last_refresh_timestamp_seconds gauge",
+        "elspot_exporter_last_refresh_timestamp_seconds 1727001000",
+    ] {
+        assert!(metrics.lines().any(|l| l == line), "{line} not in\n{metrics}");
+    }
+    assert!(exporter.health().is_ok());
+
+    // Today is final and not fetched again, tomorrow is asked for until it is published.
+    exporter.refresh().unwrap();
+    assert_eq!(stand_in.request_count(), 3);
+    assert!(stand_in.requests.lock().unwrap()[2].contains("date=2024-09-23"));
+}
+
+#[test]
+fn prices_without_states_are_refetched() {
+    let json = dataportal_json().replace("\"areaStates\":[{\"state\":\"Final\",\"areas\":[\"DK1\",\"NO3\"]}]", "\"areaStates\":[]");
+    let stand_in = StandIn::serve_responses(vec![(200, json.clone()), (200, String::new()), (200, json)]);
+    let clock = clock("2024-09-22T10:30:00Z");
+    let exporter = Exporter::new(ReqwestTransport, &clock, &stand_in.url, &["NOK"], &["NO3"]).unwrap();
+
+    exporter.refresh().unwrap();
+    exporter.refresh().unwrap();
+    let requests = stand_in.requests.lock().unwrap().clone();
+    assert_eq!(requests.len(), 4);
+    assert!(requests[2].contains("date=2024-09-22"));
+}
+
+#[test]
+fn metrics_across_midnight() {
+    let stand_in = StandIn::serve_responses(vec![
+        (200, dataportal_json_for_date("2024-10-26", &["NO1"])),
+        (200, dataportal_json_for_date("2024-10-27", &["NO1"])),
+        (200, String::new()),
+    ]);
+    // 23:30 CEST, the last hour of the 26th.
+    let clock = clock("2024-10-26T21:30:00Z");
+    let exporter = Exporter::new(ReqwestTransport, &clock, &stand_in.url, &["EUR"], &["NO1"]).unwrap();
+    exporter.refresh().unwrap();
+
+    let metrics = exporter.metrics();
+    for line in [
+        "elspot_price{region=\"NO1\",currency=\"EUR\",unit=\"Eur./MWh\"} 2623",
+        "elspot_price_next{region=\"NO1\",currency=\"EUR\",unit=\"Eur./MWh\"} 2700",
+        "elspot_price_day_min{region=\"NO1\",currency=\"EUR\",unit=\"Eur./MWh\"} 2600",
+        "elspot_price_day_max{region=\"NO1\",currency=\"EUR\",unit=\"Eur./MWh\"} 2623",
+        "elspot_price_day_avg{region=\"NO1\",currency=\"EUR\",unit=\"Eur./MWh\"} 2611.5",
+    ] {
+        assert!(metrics.lines().any(|l| l == line), "{line} not in\n{metrics}");
+    }
+
+    // Tomorrow (with 25 hours) becomes today without a refresh.
+    clock.advance(Duration::hours(3));
+    let metrics = exporter.metrics();
+    for line in [
+        "elspot_price{region=\"NO1\",currency=\"EUR\",unit=\"Eur./MWh\"} 2702",
+        "elspot_price_next{region=\"NO1\",currency=\"EUR\",unit=\"Eur./MWh\"} 2703",
+        "elspot_price_day_min{region=\"NO1\",currency=\"EUR\",unit=\"Eur./MWh\"} 2700",
+        "elspot_price_day_max{region=\"NO1\",currency=\"EUR\",unit=\"Eur./MWh\"} 2724",
+        "elspot_price_day_avg{region=\"NO1\",currency=\"EUR\",unit=\"Eur./MWh\"} 2712",
+    ] {
+        assert!(metrics.lines().any(|l| l == line), "{line} not in\n{metrics}");
+    }
+    assert!(exporter.health().is_ok());
+
+    // Only the 28th is fetched, it is not published yet.
+    exporter.refresh().unwrap();
+    assert_eq!(stand_in.request_count(), 3);
+    assert!(stand_in.requests.lock().unwrap()[2].contains("date=2024-10-28"));
+}
+
+#[test]
+fn metrics_in_other_units() {
+    let stand_in = StandIn::serve_responses(vec![(200, dataportal_json()), (200, String::new())]);
+    let clock = clock("2024-09-22T10:30:00Z");
+    let options = MetricsOptions::new().name("price").currency_unit(CurrencyUnit::Fraction).power_unit(Power::kWh);
+    let exporter = Exporter::new(ReqwestTransport, &clock, &stand_in.url, &["NOK"], &["NO3"]).unwrap().options(options);
+    exporter.refresh().unwrap();
+
+    let metrics = exporter.metrics();
+    assert!(metrics.lines().any(|l| l == "price{region=\"NO3\",currency=\"NOK\",unit=\"Øre/kWh\"} 17.908"), "{metrics}");
+    assert!(metrics.lines().any(|l| l == "price_next{region=\"NO3\",currency=\"NOK\",unit=\"Øre/kWh\"} 17.943"), "{metrics}");
+}
+
+#[test]
+fn refresh_failure() {
+    let stand_in = StandIn::serve(500, String::new());
+    let clock = clock("2024-09-22T10:30:00Z");
+    let exporter = Exporter::new(ReqwestTransport, &clock, &stand_in.url, &["NOK", "EUR"], &["NO3"]).unwrap();
+
+    assert!(exporter.health().unwrap_err().contains("NOK, EUR"));
+    assert!(exporter.refresh().is_err());
+    // Both days for both currencies are attempted.
+    assert_eq!(stand_in.request_count(), 4);
+
+    let health = exporter.health().unwrap_err();
+    assert!(health.starts_with("no prices for 2024-09-22 in NOK, EUR: "), "{health}");
+    assert!(health.contains("500"), "{health}");
+
+    let metrics = exporter.metrics();
+    assert!(metrics.contains("# TYPE elspot_price gauge"));
+    assert!(!metrics.lines().any(|l| l.starts_with("elspot_price{") || l.starts_with("elspot_exporter_last_refresh_timestamp_seconds ")));
+}
+
+#[test]
+fn unsupported_query() {
+    let clock = clock("2024-09-22T10:30:00Z");
+
+    assert!(Exporter::new(ReqwestTransport, &clock, "http://localhost", &["XYZ"], &["NO3"]).is_err());
+    assert!(Exporter::new(ReqwestTransport, &clock, "http://localhost", &["NOK"], &["XX9"]).is_err());
+    assert!(Exporter::new(ReqwestTransport, &clock, "http://localhost", &["NOK"], &[]).is_err());
+}
+
+#[test]
+fn serve_metrics_and_health() {
+    let stand_in = StandIn::serve_responses(vec![(200, dataportal_json()), (200, String::new())]);
+    let clock = clock("2024-09-22T10:30:00Z");
+    let exporter = Arc::new(Exporter::new(ReqwestTransport, clock, &stand_in.url, &["NOK"], &["NO3"]).unwrap());
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let server = Arc::clone(&exporter);
+    thread::spawn(move || server.serve(&listener));
+
+    let response = reqwest::blocking::get(format!("{url}/healthz")).unwrap();
+    assert_eq!(response.status(), 503);
+    assert_eq!(response.text().unwrap(), "no prices for 2024-09-22 in NOK\n");
+
+    exporter.refresh().unwrap();
+
+    let response = reqwest::blocking::get(format!("{url}/healthz")).unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().unwrap(), "ok\n");
+
+    let response = reqwest::blocking::get(format!("{url}/metrics")).unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], METRICS_CONTENT_TYPE);
+    assert_eq!(response.text().unwrap(), exporter.metrics());
+
+    assert_eq!(reqwest::blocking::get(format!("{url}/metrics?format=text")).unwrap().status(), 200);
+    assert_eq!(reqwest::blocking::get(format!("{url}/")).unwrap().status(), 404);
+    let response = reqwest::blocking::Client::new().post(format!("{url}/metrics")).send().unwrap();
+    assert_eq!(response.status(), 405);
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use eb_nordpool::{
+    elspot::{self, local_day, transport::Transport},
+    error::ElspotResult,
+};
+
+const BASE_URL: &str = "http://stand-in/api/DayAheadPrices";
+
+/// Answers with the delivery day in the `date` query parameter, an empty body (not published) for other days.
+struct Days {
+    days: HashMap<String, String>,
+    urls: Mutex<Vec<String>>,
+}
+
+impl Days {
+    fn new(dates: &[&str]) -> Self {
+        let days = dates
+            .iter()
+            .map(|d| (d.to_string(), common::dataportal_json_for_date(d, &["FI", "NO1"])))
+            .collect();
+
+        Self { days, urls: Mutex::new(vec![]) }
+    }
+
+    fn urls(&self) -> Vec<String> {
+        self.urls.lock().unwrap().clone()
+    }
+}
+
+impl Transport for Days {
+    fn get(&self, url: &str) -> ElspotResult<String> {
+        self.urls.lock().unwrap().push(url.to_string());
+        let date = url.split("date=").nth(1).unwrap_or("").get(..10).unwrap_or("");
+
+        Ok(self.days.get(date).cloned().unwrap_or_default())
+    }
+}
+
+fn dt(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+#[test]
+fn bounds() {
+    assert_eq!(
+        local_day::bounds("NO1", date("2024-10-27")).unwrap(),
+        (dt("2024-10-26T22:00:00Z"), dt("2024-10-27T23:00:00Z")),
+    );
+    assert_eq!(
+        local_day::bounds("FI", date("2024-10-27")).unwrap(),
+        (dt("2024-10-26T21:00:00Z"), dt("2024-10-27T22:00:00Z")),
+    );
+    assert_eq!(
+        local_day::bounds("NO1", date("2024-03-31")).unwrap(),
+        (dt("2024-03-30T23:00:00Z"), dt("2024-03-31T22:00:00Z")),
+    );
+    assert_eq!(
+        local_day::bounds("FI", date("2024-03-31")).unwrap(),
+        (dt("2024-03-30T22:00:00Z"), dt("2024-03-31T21:00:00Z")),
+    );
+    assert!(local_day::bounds("XX", date("2024-03-31")).is_err());
+}
+
+#[test]
+fn delivery_dates() {
+    assert_eq!(local_day::delivery_dates("NO1", date("2024-10-27")).unwrap(), vec![date("2024-10-27")]);
+    assert_eq!(local_day::delivery_dates("DK1", date("2024-09-22")).unwrap(), vec![date("2024-09-22")]);
+    assert_eq!(
+        local_day::delivery_dates("FI", date("2024-10-27")).unwrap(),
+        vec![date("2024-10-26"), date("2024-10-27")],
+    );
+    assert_eq!(
+        local_day::delivery_dates("EE", date("2024-03-31")).unwrap(),
+        vec![date("2024-03-30"), date("2024-03-31")],
+    );
+}
+
+#[test]
+fn oslo_dst_days() {
+    let transport = Days::new(&["2024-03-31", "2024-10-27"]);
+
+    let prices = local_day::prices_with(&transport, BASE_URL, "EUR", "NO1", date("2024-10-27")).unwrap().unwrap();
+    assert_eq!(prices.len(), 25);
+    assert_eq!(prices[0].from, dt("2024-10-26T22:00:00Z"));
+    assert_eq!("2700", prices[0].value);
+    assert_eq!("2724", prices[24].value);
+
+    let prices = local_day::prices_with(&transport, BASE_URL, "EUR", "NO1", date("2024-03-31")).unwrap().unwrap();
+    assert_eq!(prices.len(), 23);
+    assert_eq!(prices[22].to, dt("2024-03-31T22:00:00Z"));
+
+    // A CET region only needs its own delivery day.
+    assert_eq!(transport.urls().len(), 2);
+}
+
+#[test]
+fn helsinki_dst_days() {
+    let transport = Days::new(&["2024-10-26", "2024-10-27", "2024-03-30", "2024-03-31"]);
+
+    // The local day starts with the last hour of the 26th (23:00 CEST) and ends before the last hour of the 27th.
+    let prices = local_day::prices_with(&transport, BASE_URL, "EUR", "FI", date("2024-10-27")).unwrap().unwrap();
+    assert_eq!(prices.len(), 25);
+    assert_eq!(prices[0].from, dt("2024-10-26T21:00:00Z"));
+    assert_eq!("2623", prices[0].value);
+    assert_eq!("2700", prices[1].value);
+    assert_eq!("2723", prices[24].value);
+    assert_eq!(prices[24].to, dt("2024-10-27T22:00:00Z"));
+
+    let urls = transport.urls();
+    assert_eq!(urls.len(), 2);
+    assert!(urls[0].contains("date=2024-10-26"));
+    assert!(urls[1].contains("date=2024-10-27"));
+
+    let prices = local_day::prices_with(&transport, BASE_URL, "EUR", "FI", date("2024-03-31")).unwrap().unwrap();
+    assert_eq!(prices.len(), 23);
+    assert_eq!("3023", prices[0].value);
+    assert_eq!("3100", prices[1].value);
+    assert_eq!("3121", prices[22].value);
+    for (from, to) in prices.windows(2).map(|w| (w[0].to, w[1].from)) {
+        assert_eq!(from, to);
+    }
+}
+
+#[test]
+fn not_published() {
+    // The 28th is not published yet, so the Helsinki 28th is not complete.
+    let transport = Days::new(&["2024-10-27"]);
+
+    let res = local_day::prices_with(&transport, BASE_URL, "EUR", "FI", date("2024-10-28")).unwrap();
+    assert!(res.is_none());
+    let res = local_day::prices_with(&transport, BASE_URL, "EUR", "FI", date("2024-10-27")).unwrap();
+    assert!(res.is_none());
+}
+
+#[test]
+fn prices_in() {
+    let day_26 = elspot::from_json(&common::dataportal_json_for_date("2024-10-26", &["FI"])).unwrap();
+    let day_27 = elspot::from_json(&common::dataportal_json_for_date("2024-10-27", &["FI"])).unwrap();
+
+    assert!(local_day::prices_in(&[day_27.as_ref()], "FI", date("2024-10-27")).unwrap().is_none());
+    assert!(local_day::prices_in(&[day_27.as_ref()], "NO1", date("2024-10-27")).unwrap().is_none());
+
+    // Order does not matter and the same day may be passed twice.
+    let datasets = [day_27.as_ref(), day_26.as_ref(), day_27.as_ref()];
+    let prices = local_day::prices_in(&datasets, "FI", date("2024-10-27")).unwrap().unwrap();
+    assert_eq!(prices.len(), 25);
+    assert_eq!(prices[0].date, date("2024-10-26"));
+    assert_eq!(prices[1].date, date("2024-10-27"));
+}
@@ -12,17 +12,17 @@ fn eur_24h() {
     assert!(data.has_region("FI"));
     assert!(data.has_region("BE"));
 
-    let prices = data.extract_prices_for_region("Tr.heim");
+    let prices = data.extract_prices_for_region("Tr.heim").unwrap();
     let p = &prices[1];
     assert_eq!("5.82", p.value);
     assert_eq!(data.date(), p.date);
 
-    let prices = data.extract_prices_for_region("FI");
+    let prices = data.extract_prices_for_region("FI").unwrap();
     let p = &prices[2];
     assert_eq!("-5.00", p.value);
     assert_eq!(data.date(), p.date);
 
-    let prices_all = data.extract_prices_all_regions();
+    let prices_all = data.extract_prices_all_regions().unwrap();
     for prices in prices_all {
         assert_eq!(prices.len(), 24);
         for p in prices {
@@ -38,7 +38,7 @@ fn nok_25h() {
     let data = elspot::from_file("./tests/data/marketdata_page_10_NOK_25H.json").unwrap();
     assert!(data.is_preliminary());
 
-    let price = data.extract_prices_for_region("Tr.heim");
+    let price = data.extract_prices_for_region("Tr.heim").unwrap();
     let p = &price[3];
     assert_eq!("167.66", p.value);
 
@@ -46,11 +46,11 @@ fn nok_25h() {
         match region {
             // Test data has no prices for these regions..
             "AT" | "BE" | "DE-LU" | "FR" |"NL" => {
-                let prices = data.extract_prices_for_region(region);
+                let prices = data.extract_prices_for_region(region).unwrap();
                 assert_eq!(prices.len(), 0);
             },
             _ => {
-                let prices = data.extract_prices_for_region(region);
+                let prices = data.extract_prices_for_region(region).unwrap();
                 assert_eq!(prices.len(), 25);
                 if region != "SYS" {
                     for p in prices {
@@ -70,7 +70,7 @@ fn nok_23h() {
     assert!(data.is_preliminary());
     assert_eq!("2023-03-26", data.date().to_string());
 
-    let prices = data.extract_prices_for_region("Oslo");
+    let prices = data.extract_prices_for_region("Oslo").unwrap();
     assert_eq!(prices.len(), 23);
     let p = &prices[0];
     let (from, to) = p.from_to();
@@ -82,11 +82,11 @@ fn nok_23h() {
         match region {
             // Test data has no prices for these regions..
             "AT" | "BE" | "DE-LU" | "FR" |"NL" => {
-                let prices = data.extract_prices_for_region(region);
+                let prices = data.extract_prices_for_region(region).unwrap();
                 assert_eq!(prices.len(), 0);
             },
             _ => {
-                let prices = data.extract_prices_for_region(region);
+                let prices = data.extract_prices_for_region(region).unwrap();
                 assert_eq!(prices.len(), 23);
                 for p in prices {
                     let (from, _) = p.from_to();
@@ -102,7 +102,7 @@ fn to_json_string() {
     let data = elspot::from_file("./tests/data/marketdata_page_10_EUR_24H.json").unwrap();
 
     // Save data to a string (serialized json).
-    let s = data.to_json_string();
+    let s = data.to_json_string().unwrap();
 
     // We just reload the string and see if it works, unwrap() will fail if Err.
     elspot::from_json(&s).unwrap();
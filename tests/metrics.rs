@@ -0,0 +1,102 @@
+mod common;
+
+use chrono::{DateTime, Utc};
+
+use eb_nordpool::{
+    elspot::{self, Price},
+    metrics::{self, Exposition, MetricsOptions},
+    units::{self, CurrencyCode, CurrencyUnit, Power},
+};
+
+fn all_prices() -> Vec<Price> {
+    let data = elspot::from_json(&common::dataportal_json()).unwrap();
+
+    data.extract_prices_all_regions().unwrap().into_iter().flatten().collect()
+}
+
+fn dt(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+#[test]
+fn line_protocol() {
+    let prices = all_prices();
+    let out = metrics::line_protocol(&prices, &MetricsOptions::new());
+
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(prices.len(), lines.len());
+    assert_eq!("elspot_price,region=DK1,currency=NOK,unit=Kr./MWh value=1142.39 1726956000000000000", lines[0]);
+    assert!(lines[24].starts_with("elspot_price,region=NO3,"));
+
+    let options = MetricsOptions::new()
+        .name("spot price")
+        .currency_unit(CurrencyUnit::Fraction)
+        .power_unit(Power::kWh);
+    let out = metrics::line_protocol(&prices[..1], &options);
+    assert_eq!("spot\\ price,region=DK1,currency=NOK,unit=Øre/kWh value=114.239 1726956000000000000\n", out);
+}
+
+#[test]
+fn prometheus_current_interval() {
+    let prices = all_prices();
+    let no3: Vec<&Price> = prices.iter().filter(|p| p.region == "NO3").collect();
+
+    // 05:30 local time (CEST) is in the sixth hour.
+    let out = metrics::prometheus(&prices, dt("2024-09-22T03:30:00Z"), &MetricsOptions::new());
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!("# HELP elspot_price Day-ahead price for the current delivery period.", lines[0]);
+    assert_eq!("# TYPE elspot_price gauge", lines[1]);
+    assert_eq!(4, lines.len());
+    assert!(lines[2].starts_with("elspot_price{region=\"DK1\",currency=\"NOK\",unit=\"Kr./MWh\"} "));
+    assert_eq!(format!("elspot_price{{region=\"NO3\",currency=\"NOK\",unit=\"Kr./MWh\"}} {}", no3[5].value), lines[3]);
+
+    // The end of a period belongs to the next one, and there is nothing after the last.
+    let out = metrics::prometheus(&prices, no3[5].to, &MetricsOptions::new());
+    assert!(out.ends_with(&format!("}} {}\n", no3[6].value)));
+    let out = metrics::prometheus(&prices, dt("2024-09-22T22:00:00Z"), &MetricsOptions::new());
+    assert_eq!(2, out.lines().count());
+
+    let options = MetricsOptions::new().currency_unit(CurrencyUnit::Fraction).power_unit(Power::kWh);
+    let out = metrics::prometheus(&prices, dt("2024-09-22T03:30:00Z"), &options);
+    assert!(out.contains("unit=\"Øre/kWh\""));
+}
+
+#[test]
+fn prometheus_same_region_in_two_currencies() {
+    let nok = all_prices();
+    let mut eur = nok.clone();
+    for p in eur.iter_mut() {
+        p.currency_unit = units::Currency::from_code(CurrencyCode::EUR, CurrencyUnit::Full);
+        p.value = p.value.move_comma_left(1);
+    }
+    let no3_nok: Vec<&Price> = nok.iter().filter(|p| p.region == "NO3").collect();
+    let no3_eur: Vec<&Price> = eur.iter().filter(|p| p.region == "NO3").collect();
+
+    // One gauge per region and currency, whatever order the prices are in.
+    let now = dt("2024-09-22T03:30:00Z");
+    let out = metrics::prometheus(&[nok.clone(), eur.clone()].concat(), now, &MetricsOptions::new());
+    assert_eq!(out, metrics::prometheus(&[eur.clone(), nok.clone()].concat(), now, &MetricsOptions::new()));
+
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(6, lines.len());
+    assert_eq!(format!("elspot_price{{region=\"NO3\",currency=\"EUR\",unit=\"Eur./MWh\"}} {}", no3_eur[5].value), lines[4]);
+    assert_eq!(format!("elspot_price{{region=\"NO3\",currency=\"NOK\",unit=\"Kr./MWh\"}} {}", no3_nok[5].value), lines[5]);
+}
+
+#[test]
+fn exposition() {
+    let prices = all_prices();
+    let mut e = Exposition::new();
+    e.gauge("elspot price-max", "Highest price\nof the day.");
+    e.price("elspot price-max", &prices[0], &[("day", "today")]);
+    e.sample("elspot_up", &[], "1".parse().unwrap());
+    e.sample("elspot_info", &[("note", "a \"quoted\" \\ value")], "1".parse().unwrap());
+
+    let lines: Vec<&str> = e.as_str().lines().collect();
+    assert_eq!("# HELP elspot_price_max Highest price\\nof the day.", lines[0]);
+    assert_eq!("# TYPE elspot_price_max gauge", lines[1]);
+    assert_eq!("elspot_price_max{region=\"DK1\",currency=\"NOK\",unit=\"Kr./MWh\",day=\"today\"} 1142.39", lines[2]);
+    assert_eq!("elspot_up 1", lines[3]);
+    assert_eq!("elspot_info{note=\"a \\\"quoted\\\" \\\\ value\"} 1", lines[4]);
+    assert_eq!(e.to_string(), e.clone().into_string());
+}
@@ -0,0 +1,77 @@
+use eb_nordpool::{
+    error::UnitError,
+    price_value::PriceValue,
+};
+
+fn v(s: &str) -> PriceValue {
+    s.parse().unwrap()
+}
+
+#[test]
+fn parse_and_display() {
+    assert_eq!("182.94", v("182.94").to_string());
+    assert_eq!("-5.00", v("-5.00").to_string());
+    assert_eq!("167.680", v("0167.680").to_string());
+    assert_eq!("1234.56", v("1 234,56").to_string());
+    assert_eq!("1234.56", v("1\u{a0}234,56").to_string());
+    assert_eq!("0.5", v(",5").to_string());
+    assert_eq!(PriceValue::new(18294, 2), v("182.94"));
+
+    for s in ["", "-", "1.2.3", "1,2.3", "12a", "1 .5", "1  234", "1e5", "NaN"] {
+        assert!(matches!(s.parse::<PriceValue>(), Err(UnitError::InvalidPriceValue(_))), "{s:?}");
+    }
+}
+
+#[test]
+fn compares_numerically() {
+    assert_eq!(v("5.00"), v("5"));
+    assert_eq!("5", v("5.00"));
+    assert_eq!(v("-0.60"), "-0.6");
+    assert!(v("-1") < v("-0.99"));
+    assert!(v("10.5") > v("9.999"));
+    assert_ne!(v("1"), "not a number");
+}
+
+#[test]
+fn moving_the_comma_is_exact() {
+    let p = v("167.68");
+
+    assert_eq!("0.16768", p.move_comma_left(3).to_string());
+    assert_eq!("16768", p.move_comma_right(2).to_string());
+    assert_eq!(p, p.move_comma_left(3).move_comma_right(3));
+    assert_eq!("300", v("3").move_comma_right(2).to_string());
+    // 0.1 + 0.2 is not 0.3 with floats.
+    assert_eq!(v("0.3"), v("0.1").checked_add(v("0.2")).unwrap());
+}
+
+#[test]
+fn checked_arithmetic() {
+    assert_eq!(v("1.5"), v("1").checked_add(v("0.5")).unwrap());
+    assert_eq!(v("-0.5"), v("1").checked_sub(v("1.5")).unwrap());
+    assert_eq!(v("-3.75"), v("2.5").checked_mul(v("-1.5")).unwrap());
+    assert_eq!(v("0.333333333333"), v("1").checked_div(v("3")).unwrap());
+    assert_eq!(v("0.666666666667"), v("2").checked_div(v("3")).unwrap());
+    assert_eq!(v("40"), v("100").checked_div(v("2.5")).unwrap());
+
+    assert_eq!(None, v("1").checked_div(PriceValue::ZERO));
+    assert_eq!(None, PriceValue::new(i128::MAX, 0).checked_add(v("1")));
+    assert_eq!(None, PriceValue::new(i128::MAX, 0).checked_mul(v("2")));
+}
+
+#[test]
+fn rounding() {
+    assert_eq!("10.51", v("10.505").round(2).to_string());
+    assert_eq!("-11", v("-10.5").round(0).to_string());
+    assert_eq!("-10", v("-10.49").round(0).to_string());
+    assert_eq!("0", v("-0.4").round(0).to_string());
+    assert_eq!("182.9", v("182.94").round(1).to_string());
+    // Rounding does not add decimals.
+    assert_eq!("5", v("5.00").round(2).to_string());
+}
+
+#[test]
+fn to_float() {
+    assert_eq!(182.94_f64, v("182.94").to_f64());
+    assert_eq!(-0.6_f32, v("-0.6").to_f32());
+    assert_eq!(300_f64, v("3").move_comma_right(2).to_f64());
+}
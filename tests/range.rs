@@ -0,0 +1,80 @@
+mod common;
+
+use std::sync::Mutex;
+
+use eb_nordpool::{
+    elspot::{
+        range::fetch_range_with,
+        transport::Transport,
+    },
+    error::{ElspotError, ElspotResult},
+};
+
+const BASE_URL: &str = "http://localhost/api/DayAheadPrices";
+
+/// Serves the test data moved to the requested delivery day, but fails for 2024-09-24.
+struct DaysTransport {
+    requests: Mutex<Vec<String>>,
+}
+
+impl Transport for DaysTransport {
+    fn get(&self, url: &str) -> ElspotResult<String> {
+        self.requests.lock().unwrap().push(url.to_string());
+
+        let date = url.split("date=").nth(1).unwrap()[..10].to_string();
+        let day: i64 = date[8..].parse().unwrap();
+        if date == "2024-09-24" {
+            return Err(ElspotError::NetworkDisabled(url.to_string()));
+        }
+
+        Ok(common::dataportal_json_for_day(day - 22))
+    }
+}
+
+fn transport() -> DaysTransport {
+    DaysTransport { requests: Mutex::new(vec![]) }
+}
+
+#[test]
+fn merges_days_in_order() {
+    for concurrency in [1, 3] {
+        let transport = transport();
+        let range = fetch_range_with(&transport, BASE_URL, "NOK", "2024-09-20", "2024-09-23", &["DK1", "NO3"], concurrency).unwrap();
+
+        assert!(range.is_complete());
+        assert_eq!(transport.requests.lock().unwrap().len(), 4);
+        assert_eq!(range.dates.len(), 4);
+
+        let prices = range.prices_for_region("NO3");
+        assert_eq!(prices.len(), 4 * 24);
+        assert!(prices.windows(2).all(|w| w[0].to == w[1].from));
+        assert_eq!(prices[0].date.to_string(), "2024-09-20");
+        assert_eq!(prices[95].date.to_string(), "2024-09-23");
+        assert_eq!(range.prices_for_region("DK1").len(), 4 * 24);
+        assert!(range.prices_for_region("SE3").is_empty());
+    }
+}
+
+#[test]
+fn reports_failed_days() {
+    let range = fetch_range_with(&transport(), BASE_URL, "NOK", "2024-09-23", "2024-09-25", &["NO3"], 2).unwrap();
+
+    assert!(!range.is_complete());
+    assert_eq!(range.failures.len(), 1);
+    assert_eq!(range.failures[0].date.to_string(), "2024-09-24");
+    assert!(matches!(range.failures[0].error, ElspotError::NetworkDisabled(_)));
+    assert_eq!(range.prices_for_region("NO3").len(), 2 * 24);
+}
+
+#[test]
+fn invalid_input() {
+    let transport = transport();
+
+    let res = fetch_range_with(&transport, BASE_URL, "NOK", "2024-09-23", "2024-09-22", &["NO3"], 1);
+    assert!(matches!(res, Err(ElspotError::InvalidDateRange { .. })));
+
+    let res = fetch_range_with(&transport, BASE_URL, "USD", "2024-09-22", "2024-09-23", &["NO3"], 1);
+    assert!(matches!(res, Err(ElspotError::DataPortalDayaheadPricesUnsupportedCurrency(_))));
+
+    assert!(transport.requests.lock().unwrap().is_empty());
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use chrono_tz::Europe::{Berlin, Helsinki, Oslo};
+
+use eb_nordpool::{
+    elspot::{
+        self,
+        dataportal_dayaheadprices::{
+            currencies::Currency,
+            query::QueryOptions,
+            regions::{self, MarketGroup, SUPPORTED_REGIONS},
+        },
+    },
+    error::RegionError,
+    region::Region,
+    region_time::tz_from_region,
+};
+
+#[test]
+fn parse_and_display() {
+    for code in SUPPORTED_REGIONS {
+        let region: Region = code.parse().unwrap();
+        assert_eq!(code, region.to_string());
+        assert_eq!(Some(region), Region::from_code(code));
+    }
+    assert_eq!(Region::ALL.len(), SUPPORTED_REGIONS.len());
+
+    assert!(matches!("XX".parse::<Region>(), Err(RegionError::UnknownRegion(r)) if r == "XX"));
+    assert!("no3".parse::<Region>().is_err());
+}
+
+#[test]
+fn legacy_aliases() {
+    assert_eq!(Region::NO1, "Oslo".parse::<Region>().unwrap());
+    assert_eq!(Region::NO2, "Kr.sand".parse::<Region>().unwrap());
+    assert_eq!(Region::NO3, "Tr.heim".parse::<Region>().unwrap());
+    assert_eq!(Region::NO3, "Molde".parse::<Region>().unwrap());
+    assert_eq!(Region::NO4, "Tromsø".parse::<Region>().unwrap());
+    assert_eq!(Region::NO5, "Bergen".parse::<Region>().unwrap());
+    assert_eq!(Region::GER, "DE-LU".parse::<Region>().unwrap());
+
+    // Aliases are not area codes.
+    assert_eq!(None, Region::from_code("Tr.heim"));
+}
+
+#[test]
+fn timezones() {
+    assert_eq!(Oslo, Region::NO3.tz());
+    assert_eq!(Helsinki, Region::FI.tz());
+    assert_eq!(Berlin, Region::GER.tz());
+    assert_eq!(Oslo, tz_from_region("Tr.heim").unwrap());
+    assert!(matches!(tz_from_region("XX"), Err(RegionError::RegionTzNotSupported(_))));
+}
+
+#[test]
+fn compare_and_order() {
+    assert_eq!(Region::NO3, "NO3");
+    assert_eq!("NO3", Region::NO3);
+    assert!(Region::NO3 != "Tr.heim");
+
+    let mut regions = vec![Region::SE3, Region::DK1, Region::NO3, Region::FI];
+    regions.sort();
+    assert_eq!(vec!["DK1", "FI", "NO3", "SE3"], regions);
+}
+
+#[test]
+fn prices_are_typed() {
+    let data = elspot::from_json(&common::dataportal_json()).unwrap();
+    let prices = data.extract_prices(Region::NO3).unwrap();
+    assert_eq!(Region::NO3, prices[0].region);
+    assert_eq!(prices.len(), data.extract_prices_for_region("NO3").unwrap().len());
+    assert!(data.extract_prices(Region::SE3).unwrap().is_empty());
+
+    // Legacy names are mapped to their area.
+    let data = elspot::from_file("./tests/data/marketdata_page_10_NOK_25H.json").unwrap();
+    let prices = data.extract_prices_for_region("Tr.heim").unwrap();
+    assert_eq!(Region::NO3, prices[0].region);
+    assert_eq!(25, data.extract_prices(Region::NO3).unwrap().len());
+    assert_eq!(Region::NO1, data.extract_prices(Region::NO1).unwrap()[0].region);
+}
+
+#[test]
+fn query_options() {
+    let mut q = QueryOptions::new();
+    q.set_currency("EUR").unwrap();
+    q.set_date("2024-09-22").unwrap();
+    q.add_regions(&[Region::NO3, Region::FI]);
+    q.set_regions(&["NO3", "DK1"]).unwrap();
+
+    let url = q.build_url().unwrap();
+    assert!(url.ends_with("deliveryArea=NO3%2CFI%2CDK1"));
+}
+
+#[test]
+fn registry_covers_supported_regions() {
+    let zones = regions::zones();
+    assert_eq!(SUPPORTED_REGIONS.len(), zones.len());
+    for (i, z) in zones.iter().enumerate() {
+        assert_eq!(SUPPORTED_REGIONS[i], z.code());
+        assert_eq!(Region::ALL[i], z.region);
+        assert_eq!(z.region, regions::zone(z.region).region);
+        assert_eq!(z.tz(), z.region.tz());
+    }
+}
+
+#[test]
+fn lookups() {
+    let no3 = regions::zone(Region::NO3);
+    assert_eq!(Some("NO"), no3.country);
+    assert_eq!("Central Norway", no3.name);
+    assert_eq!("Midt-Norge", no3.local_name);
+    assert_eq!(Some("10YNO-3--------J"), no3.eic);
+    assert_eq!(Currency::NOK, no3.currency);
+    assert_eq!(MarketGroup::Nordic, no3.group());
+    assert_eq!(Oslo, no3.tz());
+    assert_eq!("NO3 (Central Norway, Nordic)", no3.to_string());
+
+    assert_eq!(Region::NO3, regions::zone_by_code("Tr.heim").unwrap().region);
+    assert!(regions::zone_by_code("XX").is_none());
+
+    assert_eq!(Region::FI, regions::zone_by_eic("10YFI-1--------U").unwrap().region);
+    assert_eq!(Helsinki, regions::zone_by_eic("10YFI-1--------U").unwrap().tz());
+    assert!(regions::zone_by_eic("10YXX").is_none());
+
+    let norway: Vec<Region> = regions::zones_by_country("no").iter().map(|z| z.region).collect();
+    assert_eq!(vec![Region::NO1, Region::NO2, Region::NO3, Region::NO4, Region::NO5], norway);
+    assert_eq!(Region::TEL, regions::zones_by_country("RO")[0].region);
+
+    let baltic: Vec<Region> = regions::zones_in_group(MarketGroup::Baltic).iter().map(|z| z.region).collect();
+    assert_eq!(vec![Region::EE, Region::LT, Region::LV], baltic);
+    assert_eq!(MarketGroup::System, regions::zone(Region::SYS).group());
+    assert!(regions::zone(Region::SYS).eic.is_none());
+}
+
+#[test]
+fn neighbours_are_symmetric() {
+    for z in regions::zones() {
+        assert!(!z.neighbours.contains(&z.region));
+        for n in z.neighbours {
+            assert!(regions::zone(*n).neighbours.contains(&z.region), "{} -> {}", z.region, n);
+        }
+    }
+    assert!(regions::zone(Region::FI).neighbours.contains(&Region::EE));
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use chrono::{DateTime, Utc};
+
+use eb_nordpool::{
+    elspot,
+    error::StatsError,
+    resample,
+    units::Mtu,
+};
+
+fn utc(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+#[test]
+fn quarters_to_hourly() {
+    let prices = common::prices("SE3", "2025-10-01T00:00:00Z", 15, &["10", "20", "30", "41", "-5", "5", "5", "-5"]);
+
+    let hourly = resample::to_hourly(&prices).unwrap();
+    assert_eq!(2, hourly.len());
+
+    let h = &hourly[0];
+    assert_eq!(utc("2025-10-01T00:00:00Z"), h.price.from);
+    assert_eq!(utc("2025-10-01T01:00:00Z"), h.price.to);
+    assert_eq!(Mtu::Sixty, h.price.market_time_unit);
+    assert_eq!("25.25", h.price.value);
+    assert_eq!("10", h.min);
+    assert_eq!("41", h.max);
+    assert_eq!("SE3", h.price.region);
+
+    assert_eq!("0", hourly[1].price.value);
+    assert_eq!("-5", hourly[1].min);
+    assert_eq!("5", hourly[1].max);
+}
+
+#[test]
+fn hourly_to_quarters_and_thirty_minutes() {
+    let prices = common::prices("NO1", "2024-10-01T00:00:00Z", 60, &["100", "-1.5"]);
+
+    let quarters = resample::to_quarter_hourly(&prices).unwrap();
+    assert_eq!(8, quarters.len());
+    for (i, q) in quarters.iter().enumerate() {
+        assert_eq!(Mtu::Fifteen, q.market_time_unit);
+        assert_eq!(prices[i / 4].value, q.value);
+        assert_eq!(prices[i / 4].date, q.date);
+        assert_eq!(q.from + chrono::Duration::minutes(15), q.to);
+    }
+
+    let halves = resample::resample(&quarters, Mtu::Thirty).unwrap();
+    assert_eq!(4, halves.len());
+    assert_eq!(Mtu::Thirty, halves[3].price.market_time_unit);
+    assert_eq!("-1.5", halves[3].price.value);
+    assert_eq!(Mtu::Thirty, Mtu::new(halves[3].price.from, halves[3].price.to).unwrap());
+
+    // And back again gives the prices we started with.
+    let hourly = resample::to_hourly(&quarters).unwrap();
+    for (h, p) in hourly.iter().zip(prices.iter()) {
+        assert_eq!(p.value, h.price.value);
+        assert_eq!(p.from, h.price.from);
+        assert_eq!(p.to, h.price.to);
+    }
+}
+
+#[test]
+fn mixed_mtu() {
+    let mut prices = common::prices("SE3", "2025-09-30T21:00:00Z", 60, &["100"]);
+    prices.extend(common::prices("SE3", "2025-09-30T22:00:00Z", 15, &["10", "20", "30", "40"]));
+
+    let hourly = resample::to_hourly(&prices).unwrap();
+    assert_eq!(2, hourly.len());
+    assert_eq!("100", hourly[0].price.value);
+    assert_eq!("25", hourly[1].price.value);
+
+    assert_eq!(8, resample::to_quarter_hourly(&prices).unwrap().len());
+}
+
+#[test]
+fn dataportal_day() {
+    let data = elspot::from_file("./tests/data/dataportal_dayaheadprices_NOK.json").unwrap();
+    let prices = data.extract_prices_for_region("NO3").unwrap();
+
+    let quarters = resample::to_quarter_hourly(&prices).unwrap();
+    assert_eq!(96, quarters.len());
+    let hourly = resample::to_hourly(&quarters).unwrap();
+    assert_eq!(24, hourly.len());
+    assert_eq!("182.94", hourly[5].price.value);
+}
+
+#[test]
+fn incomplete_interval() {
+    let prices = common::prices("SE3", "2025-10-01T00:00:00Z", 15, &["10", "20", "30"]);
+
+    match resample::to_hourly(&prices) {
+        Err(StatsError::IncompleteInterval { region, from }) => {
+            assert_eq!("SE3", region);
+            assert_eq!(utc("2025-10-01T00:00:00Z"), from);
+        }
+        res => panic!("expected incomplete interval, got {res:?}"),
+    }
+
+    assert!(matches!(resample::to_hourly(&[]), Err(StatsError::NoPrices)));
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+
+use eb_nordpool::{
+    elspot,
+    schedule,
+};
+
+fn utc(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+#[test]
+fn cheapest_and_most_expensive_window() {
+    let prices = common::prices("NO1", "2024-10-01T00:00:00Z", 60, &["50", "10", "20", "5", "40", "60", "30"]);
+    let (from, to) = (utc("2024-10-01T00:00:00Z"), utc("2024-10-02T00:00:00Z"));
+
+    let w = schedule::cheapest_window(&prices, Duration::hours(2), from, to).unwrap().unwrap();
+    assert_eq!(utc("2024-10-01T02:00:00Z"), w.from);
+    assert_eq!(utc("2024-10-01T04:00:00Z"), w.to);
+    assert_eq!("25", w.total);
+    assert_eq!("12.5", w.average);
+    assert_eq!(2, w.prices.len());
+    assert_eq!("NO1", w.region);
+
+    let w = schedule::most_expensive_window(&prices, Duration::hours(2), from, to).unwrap().unwrap();
+    assert_eq!(utc("2024-10-01T04:00:00Z"), w.from);
+    assert_eq!("100", w.total);
+
+    // Only whole prices within the limits are used.
+    let w = schedule::cheapest_window(&prices, Duration::hours(2), utc("2024-10-01T03:30:00Z"), to).unwrap().unwrap();
+    assert_eq!(utc("2024-10-01T05:00:00Z"), w.from);
+    assert_eq!("90", w.total);
+
+    assert!(schedule::cheapest_window(&prices, Duration::hours(8), from, to).unwrap().is_none());
+    assert!(schedule::cheapest_window(&prices, Duration::minutes(90), from, to).unwrap().is_none());
+    assert!(schedule::cheapest_window(&prices, Duration::zero(), from, to).unwrap().is_none());
+}
+
+#[test]
+fn window_does_not_span_gaps() {
+    let mut prices = common::prices("NO1", "2024-10-01T00:00:00Z", 60, &["1", "1"]);
+    prices.extend(common::prices("NO1", "2024-10-01T03:00:00Z", 60, &["1", "50", "50"]));
+
+    let w = schedule::cheapest_window(&prices, Duration::hours(3), utc("2024-10-01T00:00:00Z"), utc("2024-10-02T00:00:00Z"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(utc("2024-10-01T03:00:00Z"), w.from);
+    assert_eq!("101", w.total);
+}
+
+#[test]
+fn quarter_hours_and_mixed_mtu() {
+    let mut prices = common::prices("SE3", "2025-10-01T00:00:00Z", 60, &["100", "100"]);
+    prices.extend(common::prices("SE3", "2025-10-01T02:00:00Z", 15, &["40", "8", "4", "12", "80"]));
+    let (from, to) = (utc("2025-10-01T00:00:00Z"), utc("2025-10-02T00:00:00Z"));
+
+    let w = schedule::cheapest_window(&prices, Duration::minutes(45), from, to).unwrap().unwrap();
+    assert_eq!(utc("2025-10-01T02:15:00Z"), w.from);
+    assert_eq!(utc("2025-10-01T03:00:00Z"), w.to);
+    // (8 + 4 + 12) / 4 for three quarters.
+    assert_eq!("6", w.total);
+    assert_eq!("8", w.average);
+
+    // An hour can be one hourly price, or four quarters.
+    let w = schedule::cheapest_window(&prices, Duration::hours(1), from, to).unwrap().unwrap();
+    assert_eq!(utc("2025-10-01T02:00:00Z"), w.from);
+    assert_eq!("16", w.total);
+    assert_eq!(4, w.prices.len());
+
+    let w = schedule::most_expensive_window(&prices, Duration::minutes(75), from, to).unwrap().unwrap();
+    assert_eq!(utc("2025-10-01T01:00:00Z"), w.from);
+    assert_eq!("110", w.total);
+}
+
+#[test]
+fn cheapest_slots() {
+    let prices = common::prices("NO1", "2024-10-01T00:00:00Z", 60, &["50", "10", "20", "5", "40", "10", "30"]);
+    let (from, to) = (utc("2024-10-01T00:00:00Z"), utc("2024-10-02T00:00:00Z"));
+
+    let w = schedule::cheapest_slots(&prices, 3, from, to).unwrap().unwrap();
+    let starts: Vec<u32> = w.prices.iter().map(|p| chrono::Timelike::hour(&p.from)).collect();
+    assert_eq!(vec![1, 3, 5], starts);
+    assert_eq!("25", w.total);
+    assert_eq!(Duration::hours(3), w.duration());
+    assert_eq!(utc("2024-10-01T01:00:00Z"), w.from);
+    assert_eq!(utc("2024-10-01T06:00:00Z"), w.to);
+
+    assert!(schedule::cheapest_slots(&prices, 8, from, to).unwrap().is_none());
+    assert!(schedule::cheapest_slots(&prices, 0, from, to).unwrap().is_none());
+}
+
+#[test]
+fn dst_days() {
+    // 2024-10-27 has 25 hours in Oslo, the window is still exactly 3 hours long.
+    let data = elspot::from_file("./tests/data/marketdata_page_10_NOK_25H.json").unwrap();
+    let prices = data.extract_prices_for_region("Tr.heim").unwrap();
+    let (from, to) = (prices[0].from, prices[prices.len() - 1].to);
+    assert_eq!(Duration::hours(25), to - from);
+
+    let w = schedule::cheapest_window(&prices, Duration::hours(3), from, to).unwrap().unwrap();
+    assert_eq!(Duration::hours(3), w.to - w.from);
+    assert_eq!(3, w.prices.len());
+    let all = schedule::cheapest_window(&prices, Duration::hours(25), from, to).unwrap().unwrap();
+    assert_eq!(25, all.prices.len());
+
+    let data = elspot::from_file("./tests/data/marketdata_page_10_NOK_23H.json").unwrap();
+    let prices = data.extract_prices_for_region("Tr.heim").unwrap();
+    let (from, to) = (prices[0].from, prices[prices.len() - 1].to);
+    assert!(schedule::cheapest_window(&prices, Duration::hours(23), from, to).unwrap().is_some());
+    assert!(schedule::cheapest_window(&prices, Duration::hours(24), from, to).unwrap().is_none());
+}
+
+#[test]
+fn today_and_tomorrow() {
+    let today = elspot::from_json(&common::dataportal_json()).unwrap();
+    let tomorrow = elspot::from_json(&common::dataportal_json_for_day(1)).unwrap();
+    let mut prices = today.extract_prices_for_region("NO3").unwrap();
+    prices.extend(tomorrow.extract_prices_for_region("NO3").unwrap());
+
+    // The cheapest window between the evening today and the night tomorrow crosses the day boundary (22:00 UTC).
+    let w = schedule::cheapest_window(&prices, Duration::hours(4), utc("2024-09-22T20:00:00Z"), utc("2024-09-23T01:00:00Z"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(utc("2024-09-22T21:00:00Z"), w.from);
+    assert_eq!(utc("2024-09-23T01:00:00Z"), w.to);
+    assert_eq!("734.67", w.total);
+    assert_eq!("183.6675", w.average);
+}
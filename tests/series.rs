@@ -0,0 +1,163 @@
+mod common;
+
+use chrono::{DateTime, Utc};
+
+use eb_nordpool::{
+    elspot,
+    error::SeriesError,
+    region::Region,
+    series::{PriceSeries, PriceSet},
+    units,
+};
+
+fn utc(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+#[test]
+fn extract_series_and_set() {
+    let data = elspot::from_file("./tests/data/dataportal_dayaheadprices_NOK.json").unwrap();
+
+    let series = data.extract_series_for_region("NO3").unwrap().unwrap();
+    assert_eq!("NO3", series.region());
+    assert_eq!(24, series.len());
+    assert_eq!("NOK", series.currency_unit().country_code_as_str());
+    assert!(series.power_unit().is_mwh());
+    assert_eq!(utc("2024-09-21T22:00:00Z"), series.from());
+    assert_eq!(utc("2024-09-22T22:00:00Z"), series.to());
+    assert!(data.extract_series_for_region("SE3").unwrap().is_none());
+
+    let set = data.extract_price_set().unwrap();
+    assert_eq!(vec!["DK1", "NO3"], set.regions());
+    assert_eq!(2, set.len());
+    assert_eq!(24, set.iter().map(|s| s.len()).min().unwrap());
+}
+
+#[test]
+fn lookup_and_slice() {
+    let data = elspot::from_file("./tests/data/dataportal_dayaheadprices_NOK.json").unwrap();
+    let series = data.extract_series_for_region("NO3").unwrap().unwrap();
+
+    assert_eq!("182.94", series.price_at(utc("2024-09-22T03:00:00Z")).unwrap().value);
+    assert_eq!("182.94", series.price_at(utc("2024-09-22T03:59:59Z")).unwrap().value);
+    assert_eq!("180.13", series.price_at(utc("2024-09-22T04:00:00Z")).unwrap().value);
+    assert!(series.price_at(utc("2024-09-21T21:59:59Z")).is_none());
+    assert!(series.price_at(utc("2024-09-22T22:00:00Z")).is_none());
+
+    let slice = series.slice(utc("2024-09-22T03:00:00Z"), utc("2024-09-22T06:00:00Z")).unwrap();
+    assert_eq!(3, slice.len());
+    assert_eq!(utc("2024-09-22T03:00:00Z"), slice.from());
+    assert_eq!(utc("2024-09-22T06:00:00Z"), slice.to());
+    assert!(series.slice(utc("2024-09-23T00:00:00Z"), utc("2024-09-24T00:00:00Z")).is_none());
+
+    let values: Vec<String> = slice.iter().map(|p| p.value.to_string()).collect();
+    assert_eq!(vec!["182.94", "180.13", "185.04"], values);
+    assert_eq!(3, (&slice).into_iter().count());
+    assert_eq!(3, slice.into_prices().len());
+
+    let set = data.extract_price_set().unwrap();
+    assert_eq!("1059.63", set.price_at(Region::DK1, utc("2024-09-22T03:30:00Z")).unwrap().value);
+    assert!(set.price_at(Region::SE3, utc("2024-09-22T03:30:00Z")).is_none());
+    assert_eq!("1059.63", set.price_at_str("DK1", utc("2024-09-22T03:30:00Z")).unwrap().value);
+    assert!(set.price_at_str("SE3", utc("2024-09-22T03:30:00Z")).is_none());
+    assert!(set.price_at_str("XX9", utc("2024-09-22T03:30:00Z")).is_none());
+    assert_eq!(24, set.get_str("NO3").unwrap().len());
+    let sliced = set.slice(utc("2024-09-22T03:00:00Z"), utc("2024-09-22T06:00:00Z"));
+    assert_eq!(2, sliced.len());
+    assert_eq!(3, sliced.get(Region::DK1).unwrap().len());
+}
+
+#[test]
+fn concat_days() {
+    let today = elspot::from_json(&common::dataportal_json()).unwrap();
+    let tomorrow = elspot::from_json(&common::dataportal_json_for_day(1)).unwrap();
+    let after = elspot::from_json(&common::dataportal_json_for_day(2)).unwrap();
+
+    let mut series = today.extract_series_for_region("NO3").unwrap().unwrap();
+    series.concat(tomorrow.extract_series_for_region("NO3").unwrap().unwrap()).unwrap();
+    assert_eq!(48, series.len());
+    assert!(series.gaps().is_empty());
+
+    // The same day again overlaps.
+    let res = series.concat(tomorrow.extract_series_for_region("NO3").unwrap().unwrap());
+    assert!(matches!(res, Err(SeriesError::Overlap { .. })));
+
+    // Skipping a day leaves a gap.
+    let mut set = today.extract_price_set().unwrap();
+    match set.concat(after.extract_price_set().unwrap()) {
+        Err(SeriesError::Gap { region, from, to }) => {
+            assert_eq!("DK1", region);
+            assert_eq!(utc("2024-09-22T22:00:00Z"), from);
+            assert_eq!(utc("2024-09-23T22:00:00Z"), to);
+        }
+        res => panic!("expected gap, got {res:?}"),
+    }
+    // ..and the set is left as it was.
+    assert_eq!(24, set.get(Region::NO3).unwrap().len());
+
+    set.concat(tomorrow.extract_price_set().unwrap()).unwrap();
+    assert_eq!(48, set.get(Region::NO3).unwrap().len());
+    assert_eq!(48, set.get(Region::DK1).unwrap().len());
+}
+
+#[test]
+fn merge_with_gaps_and_duplicates() {
+    let today = elspot::from_json(&common::dataportal_json()).unwrap();
+    let after = elspot::from_json(&common::dataportal_json_for_day(2)).unwrap();
+
+    let mut series = today.extract_series_for_region("NO3").unwrap().unwrap();
+    series.merge(after.extract_series_for_region("NO3").unwrap().unwrap()).unwrap();
+    assert_eq!(48, series.len());
+    assert_eq!(vec![(utc("2024-09-22T22:00:00Z"), utc("2024-09-23T22:00:00Z"))], series.gaps());
+
+    // Identical prices are kept once.
+    series.merge(today.extract_series_for_region("NO3").unwrap().unwrap()).unwrap();
+    assert_eq!(48, series.len());
+
+    // The same hour with another price is an overlap.
+    let other = common::prices("NO3", "2024-09-22T03:00:00Z", 60, &["1"]);
+    let res = series.merge(PriceSeries::new(other).unwrap());
+    assert!(matches!(res, Err(SeriesError::Overlap { .. })));
+}
+
+#[test]
+fn invalid_series() {
+    assert!(matches!(PriceSeries::new(vec![]), Err(SeriesError::NoPrices)));
+
+    let mut prices = common::prices("NO1", "2024-10-01T00:00:00Z", 60, &["1"]);
+    prices.extend(common::prices("NO2", "2024-10-01T01:00:00Z", 60, &["1"]));
+    assert!(matches!(PriceSeries::new(prices), Err(SeriesError::MixedRegions { .. })));
+
+    let mut prices = common::prices("NO1", "2024-10-01T00:00:00Z", 60, &["1", "2"]);
+    units::convert_to_kwh(&mut prices[0]);
+    assert!(matches!(PriceSeries::new(prices), Err(SeriesError::MixedUnits { .. })));
+
+    let mut prices = common::prices("NO1", "2024-10-01T00:00:00Z", 60, &["1"]);
+    prices.extend(common::prices("NO1", "2024-10-01T00:30:00Z", 15, &["1"]));
+    assert!(matches!(PriceSeries::new(prices), Err(SeriesError::Overlap { .. })));
+
+    // Unsorted prices are sorted.
+    let mut prices = common::prices("NO1", "2024-10-01T00:00:00Z", 60, &["1", "2", "3"]);
+    prices.reverse();
+    let series = PriceSeries::new(prices).unwrap();
+    assert_eq!("1", series.prices()[0].value);
+    assert_eq!(0, PriceSet::from_regions(vec![vec![]]).unwrap().len());
+}
+
+#[test]
+fn convert_all_prices() {
+    let data = elspot::from_file("./tests/data/dataportal_dayaheadprices_NOK.json").unwrap();
+
+    let mut series = data.extract_series_for_region("NO3").unwrap().unwrap();
+    series.convert(units::convert_to_kwh);
+    assert_eq!("0.18294", series.prices()[5].value);
+    assert!(series.power_unit().is_kwh());
+
+    let mut set = data.extract_price_set().unwrap();
+    set.convert(units::convert_to_currency_fraction);
+    set.convert(units::convert_to_kwh);
+    for series in set {
+        assert!(series.currency_unit().is_fraction());
+        assert!(series.iter().all(|p| p.power_unit.is_kwh()));
+    }
+}
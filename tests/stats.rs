@@ -0,0 +1,104 @@
+mod common;
+
+use eb_nordpool::{
+    elspot,
+    error::StatsError,
+    stats,
+    units,
+};
+
+#[test]
+fn statistics_for_a_day() {
+    let data = elspot::from_file("./tests/data/dataportal_dayaheadprices_NOK.json").unwrap();
+    let prices = data.extract_prices_for_region("NO3").unwrap();
+
+    let summary = stats::summary(&prices).unwrap();
+    assert_eq!("NO3", summary.region);
+    assert_eq!(24, summary.count);
+    assert_eq!("2024-09-21 22:00:00 UTC", summary.from.to_string());
+    assert_eq!("2024-09-22 22:00:00 UTC", summary.to.to_string());
+    assert_eq!("NOK", summary.currency_unit.country_code_as_str());
+    assert!(summary.power_unit.is_mwh());
+    assert_eq!("179.08", summary.min);
+    assert_eq!("218", summary.max);
+    // Same as the day average published by Nord Pool.
+    assert_eq!("190.54", summary.mean.round(2));
+    assert_eq!(summary.mean, summary.time_weighted_mean);
+    assert_eq!("183.99", summary.median);
+
+    assert_eq!(summary.min, stats::min(&prices).unwrap().value);
+    assert_eq!(summary.max, stats::percentile(&prices, 100.0).unwrap().value);
+    assert_eq!(summary.median, stats::median(&prices).unwrap().value);
+    assert_eq!(summary.std_dev, stats::std_dev(&prices).unwrap().value);
+    assert_eq!("11.82", summary.std_dev.round(2));
+
+    let mean = stats::mean(&prices).unwrap();
+    assert_eq!("NO3", mean.region);
+    assert_eq!(summary.from, mean.from);
+    assert_eq!(summary.to, mean.to);
+}
+
+#[test]
+fn units_follow_the_prices() {
+    let mut prices = common::prices("NO1", "2024-10-01T00:00:00Z", 60, &["100", "200"]);
+    for p in prices.iter_mut() {
+        units::convert_to_currency_fraction(p);
+        units::convert_to_kwh(p);
+    }
+
+    let mean = stats::mean(&prices).unwrap();
+    assert_eq!("15", mean.value);
+    assert!(mean.currency_unit.is_fraction());
+    assert!(mean.power_unit.is_kwh());
+}
+
+#[test]
+fn percentiles_are_interpolated() {
+    let prices = common::prices("NO1", "2024-10-01T00:00:00Z", 60, &["40", "10", "30", "20"]);
+
+    assert_eq!("10", stats::percentile(&prices, 0.0).unwrap().value);
+    assert_eq!("17.5", stats::percentile(&prices, 25.0).unwrap().value);
+    assert_eq!("25", stats::median(&prices).unwrap().value);
+    assert_eq!("37", stats::percentile(&prices, 90.0).unwrap().value);
+    assert!(matches!(stats::percentile(&prices, 100.5), Err(StatsError::InvalidPercentile(_))));
+    assert!(matches!(stats::percentile(&prices, f64::NAN), Err(StatsError::InvalidPercentile(_))));
+}
+
+#[test]
+fn mixed_mtu_is_weighted_by_duration() {
+    // One hour at 100, followed by four quarters (one hour) at 10, 20, 30 and 40.
+    let mut prices = common::prices("SE3", "2025-09-30T20:00:00Z", 60, &["100"]);
+    prices.extend(common::prices("SE3", "2025-09-30T21:00:00Z", 15, &["10", "20", "30", "40"]));
+
+    assert_eq!("40", stats::mean(&prices).unwrap().value);
+    assert_eq!("62.5", stats::time_weighted_mean(&prices).unwrap().value);
+    assert_eq!("2025-09-30 22:00:00 UTC", stats::time_weighted_mean(&prices).unwrap().to.to_string());
+}
+
+#[test]
+fn multiple_days() {
+    let today = elspot::from_json(&common::dataportal_json()).unwrap();
+    let tomorrow = elspot::from_json(&common::dataportal_json_for_day(1)).unwrap();
+    let mut prices = today.extract_prices_for_region("NO3").unwrap();
+    prices.extend(tomorrow.extract_prices_for_region("NO3").unwrap());
+
+    let summary = stats::summary(&prices).unwrap();
+    assert_eq!(48, summary.count);
+    assert_eq!("2024-09-21 22:00:00 UTC", summary.from.to_string());
+    assert_eq!("2024-09-23 22:00:00 UTC", summary.to.to_string());
+    assert_eq!(stats::summary(&prices[..24]).unwrap().mean, summary.mean);
+}
+
+#[test]
+fn invalid_input() {
+    assert!(matches!(stats::mean(&[]), Err(StatsError::NoPrices)));
+    assert!(matches!(stats::summary(&[]), Err(StatsError::NoPrices)));
+
+    let mut prices = common::prices("NO1", "2024-10-01T00:00:00Z", 60, &["1", "2"]);
+    prices.extend(common::prices("NO2", "2024-10-01T00:00:00Z", 60, &["1"]));
+    assert!(matches!(stats::max(&prices), Err(StatsError::MixedRegions { .. })));
+
+    let mut prices = common::prices("NO1", "2024-10-01T00:00:00Z", 60, &["1", "2"]);
+    units::convert_to_kwh(&mut prices[1]);
+    assert!(matches!(stats::min(&prices), Err(StatsError::MixedUnits { .. })));
+}
@@ -0,0 +1,177 @@
+#![cfg(feature = "sqlite")]
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+
+use eb_nordpool::{
+    elspot::{
+        PriceExtractor,
+        dataportal_dayaheadprices::{PriceData, states::State},
+    },
+    exchange,
+    store::{PriceStore, Source},
+    units,
+};
+
+fn day() -> (chrono::DateTime<Utc>, chrono::DateTime<Utc>) {
+    (
+        Utc.with_ymd_and_hms(2024, 9, 21, 22, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2024, 9, 22, 22, 0, 0).unwrap(),
+    )
+}
+
+#[test]
+fn insert_and_query() {
+    let data = PriceData::new(&common::dataportal_json()).unwrap();
+    let mut store = PriceStore::open_in_memory().unwrap();
+
+    let fetched_at = Utc.with_ymd_and_hms(2024, 9, 23, 8, 0, 0).unwrap();
+    assert_eq!(store.insert_data(&data, fetched_at).unwrap(), 48);
+
+    let (from, to) = day();
+    let prices = store.query("NO3", "NOK", from, to).unwrap();
+    let extracted = data.extract_prices_for_region("NO3").unwrap();
+    assert_eq!(prices.len(), extracted.len());
+    for (stored, p) in prices.iter().zip(extracted.iter()) {
+        assert_eq!(stored.value, p.value);
+        assert_eq!(stored.from, p.from);
+        assert_eq!(stored.to, p.to);
+        assert_eq!(stored.date, p.date);
+        assert_eq!(stored.region, "NO3");
+        assert!(stored.currency_unit.is_full());
+        assert!(stored.power_unit.is_mwh());
+    }
+
+    // Time range is start inclusive and end exclusive.
+    let prices = store.query("NO3", "NOK", prices[2].from, prices[5].from).unwrap();
+    assert_eq!(prices.len(), 3);
+    assert!(store.query("NO3", "EUR", from, to).unwrap().is_empty());
+}
+
+#[test]
+fn values_are_stored_exact_in_full_units() {
+    let data = PriceData::new(&common::dataportal_json()).unwrap();
+    let mut prices = data.extract_prices_for_region("NO3").unwrap();
+    for p in prices.iter_mut() {
+        units::convert_to_kwh(p);
+        units::convert_to_currency_fraction(p);
+    }
+    assert_eq!(prices[5].value, "18.294");
+
+    let mut store = PriceStore::open_in_memory().unwrap();
+    let source = Source { state: State::Final, version: 3, fetched_at: Utc::now() };
+    store.insert(&prices, &source).unwrap();
+
+    let (from, to) = day();
+    let stored = store.query("NO3", "NOK", from, to).unwrap();
+    assert_eq!(stored[5].value, "182.94");
+}
+
+#[test]
+fn upsert_preliminary_to_final() {
+    let data = PriceData::new(&common::dataportal_json()).unwrap();
+    let prices = data.extract_prices_for_region("NO3").unwrap();
+    let (from, to) = day();
+    let mut store = PriceStore::open_in_memory().unwrap();
+
+    let mut preliminary = prices.clone();
+    preliminary[0].value = "100.00".parse().unwrap();
+    let fetched_at = Utc.with_ymd_and_hms(2024, 9, 21, 12, 45, 0).unwrap();
+    let source = Source { state: State::Preliminary, version: 1, fetched_at };
+    assert_eq!(store.insert(&preliminary, &source).unwrap(), 24);
+
+    let stored = store.query_with_source("NO3", "NOK", from, to).unwrap();
+    assert_eq!(stored[0].price.value, "100.00");
+    assert_eq!(stored[0].source.state, State::Preliminary);
+
+    // Final replaces preliminary.
+    let source = Source { state: State::Final, version: 1, fetched_at: fetched_at + chrono::Duration::hours(1) };
+    assert_eq!(store.insert(&prices, &source).unwrap(), 24);
+    let stored = store.query_with_source("NO3", "NOK", from, to).unwrap();
+    assert_eq!(stored.len(), 24);
+    assert_eq!(stored[0].price.value, "182");
+    assert_eq!(stored[0].source.state, State::Final);
+    assert_eq!(stored[0].source.fetched_at, fetched_at + chrono::Duration::hours(1));
+
+    // Preliminary never replaces final, and neither does an older version.
+    let source = Source { state: State::Preliminary, version: 2, fetched_at: Utc::now() };
+    assert_eq!(store.insert(&preliminary, &source).unwrap(), 0);
+    let source = Source { state: State::Final, version: 0, fetched_at: Utc::now() };
+    assert_eq!(store.insert(&preliminary, &source).unwrap(), 0);
+    assert_eq!(store.query("NO3", "NOK", from, to).unwrap()[0].value, "182");
+
+    // A newer final version (a correction) does.
+    let source = Source { state: State::Final, version: 2, fetched_at: Utc::now() };
+    assert_eq!(store.insert(&preliminary, &source).unwrap(), 24);
+    assert_eq!(store.query("NO3", "NOK", from, to).unwrap()[0].value, "100.00");
+}
+
+#[test]
+fn insert_data_uses_state_per_region() {
+    let json = common::dataportal_json().replace(
+        "\"areaStates\":[{\"state\":\"Final\",\"areas\":[\"DK1\",\"NO3\"]}]",
+        "\"areaStates\":[{\"state\":\"Final\",\"areas\":[\"DK1\"]},{\"state\":\"Preliminary\",\"areas\":[\"NO3\"]}]",
+    );
+    let data = PriceData::new(&json).unwrap();
+    let (from, to) = day();
+    let mut store = PriceStore::open_in_memory().unwrap();
+    assert_eq!(store.insert_data(&data, Utc::now()).unwrap(), 48);
+
+    let dk1 = store.query_with_source("DK1", "NOK", from, to).unwrap();
+    assert!(dk1.iter().all(|p| p.source.state == State::Final));
+    let no3 = store.query_with_source("NO3", "NOK", from, to).unwrap();
+    assert!(no3.iter().all(|p| p.source.state == State::Preliminary));
+
+    // The preliminary region is upgraded once it is final.
+    let data = PriceData::new(&common::dataportal_json()).unwrap();
+    assert_eq!(store.insert_data(&data, Utc::now()).unwrap(), 48);
+    let no3 = store.query_with_source("NO3", "NOK", from, to).unwrap();
+    assert!(no3.iter().all(|p| p.source.state == State::Final));
+
+    // Without any states nothing is known to be final.
+    let json = common::dataportal_json().replace(
+        "\"areaStates\":[{\"state\":\"Final\",\"areas\":[\"DK1\",\"NO3\"]}]",
+        "\"areaStates\":[]",
+    );
+    let mut store = PriceStore::open_in_memory().unwrap();
+    store.insert_data(&PriceData::new(&json).unwrap(), Utc::now()).unwrap();
+    let stored = store.query_with_source("DK1", "NOK", from, to).unwrap();
+    assert!(stored.iter().all(|p| p.source.state == State::Preliminary));
+}
+
+#[test]
+fn exchange_rate_is_not_stored() {
+    let data = PriceData::new(&common::dataportal_json()).unwrap();
+    let prices = data.extract_prices_for_region("NO3").unwrap();
+    let converted: Vec<_> = prices
+        .iter()
+        .map(|p| exchange::convert(p, units::CurrencyCode::EUR, &data.rate_table().unwrap()).unwrap())
+        .collect();
+    assert!(converted[0].exchange_rate.is_some());
+
+    let mut store = PriceStore::open_in_memory().unwrap();
+    let source = Source { state: State::Final, version: 1, fetched_at: Utc::now() };
+    store.insert(&converted, &source).unwrap();
+
+    let (from, to) = day();
+    let stored = store.query("NO3", "EUR", from, to).unwrap();
+    assert_eq!(stored.len(), 24);
+    assert!(stored.iter().all(|p| p.exchange_rate.is_none()));
+}
+
+#[test]
+fn open_file() {
+    let dir = common::temp_dir("store_open_file");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("prices.sqlite");
+
+    let data = PriceData::new(&common::dataportal_json()).unwrap();
+    PriceStore::open(&path).unwrap().insert_data(&data, Utc::now()).unwrap();
+
+    let (from, to) = day();
+    let store = PriceStore::open(&path).unwrap();
+    assert_eq!(store.query("DK1", "NOK", from, to).unwrap().len(), 24);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use eb_nordpool::{
+    elspot::{
+        self,
+        transport::FileTransport,
+    },
+    error::ElspotError,
+};
+
+const NORDPOOL_URL: &str = "https://dataportal-api.nordpoolgroup.com/api/DayAheadPrices?market=DayAhead&currency=NOK&date=2024-09-22&deliveryArea=DK1%2CNO3";
+
+#[test]
+fn replay_from_nordpool() {
+    // The recording in tests/data/replay is keyed by the url that from_nordpool builds.
+    let transport = FileTransport::replay("./tests/data/replay");
+    assert!(transport.path_for(NORDPOOL_URL).is_file());
+
+    let data = elspot::from_nordpool_with(&transport, "NOK", "2024-09-22", &["DK1", "NO3"]).unwrap();
+    assert_eq!(data.currency(), "NOK");
+    let prices = data.extract_prices_for_region("NO3").unwrap();
+    assert_eq!("182.94", prices[5].value);
+}
+
+#[test]
+fn replay_missing() {
+    let transport = FileTransport::replay("./tests/data/replay");
+
+    let res = elspot::from_nordpool_with(&transport, "NOK", "2024-09-23", &["DK1", "NO3"]);
+    assert!(matches!(res, Err(ElspotError::NetworkDisabled(url)) if url.contains("2024-09-23")));
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn record_and_replay() {
+    use std::fs;
+
+    use common::StandIn;
+    use eb_nordpool::elspot::transport::{ReqwestTransport, Transport};
+
+    let server = StandIn::serve(200, common::page_10_json());
+    let url = format!("{}/api/marketdata/page/10?currency=EUR", server.url);
+    let dir = common::temp_dir("record_and_replay");
+
+    let transport = FileTransport::record(&dir, ReqwestTransport);
+    let data = elspot::marketdata_page_10::from_url_with(&transport, &url).unwrap();
+    assert_eq!(server.request_count(), 1);
+    assert!(transport.path_for(&url).is_file());
+
+    // Recorded responses are served without another request.
+    transport.get(&url).unwrap();
+    assert_eq!(server.request_count(), 1);
+
+    let replayed = elspot::from_url_with(&FileTransport::replay(&dir), &url).unwrap();
+    assert_eq!(replayed.date(), elspot::PriceExtractor::date(&data));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
@@ -1,19 +1,26 @@
 use chrono::{Duration, TimeZone, Utc};
 
-use eb_nordpool::{elspot::Price, units};
+use eb_nordpool::{
+    elspot::Price,
+    error::{RegionError, UnitError},
+    price_value::{PriceValue, Rounding, RoundingMode},
+    region::Region,
+    units,
+};
 
 fn get_dummy_price(v: &str) -> Price {
     let dt = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
 
     Price {
-        value: String::from(v),
+        value: v.parse().unwrap(),
         from: dt,
         to: dt + Duration::hours(1),
         date: dt.date_naive(),
-        region: String::from("NO3"),
-        currency_unit: units::Currency::NOK(units::CurrencyUnit::Full),
+        region: Region::NO3,
+        currency_unit: units::Currency::from_code(units::CurrencyCode::NOK, units::CurrencyUnit::Full),
         market_time_unit: units::Mtu::Sixty,
         power_unit: units::Power::MWh,
+        exchange_rate: None,
     }
 }
 
@@ -47,16 +54,27 @@ fn converting_price() {
     assert_eq!("167.68", p.value);
     assert_eq!("MWh", p.power_unit.as_str());
 
-    p.value = String::from("10.505");
+    p.value = "10.505".parse().unwrap();
     units::convert_to_currency_fraction(&mut p);
     assert_eq!(1051_f32, p.as_f32());
     assert_eq!(1051_f64, p.as_f64());
     assert_eq!("Øre", p.currency_unit.as_str());
 
-    p.value = String::from("10.5");
-    assert_eq!(11_i32, p.as_i32());
+    p.value = "10.5".parse().unwrap();
+    assert_eq!(11_i32, p.as_i32().unwrap());
     assert_eq!(11_f64, p.as_f64());
-    assert_eq!(11_i64, p.as_i64());
+    assert_eq!(11_i64, p.as_i64().unwrap());
+}
+
+#[test]
+fn integer_out_of_range() {
+    let p = get_dummy_price("3000000000.4");
+    assert_eq!(3_000_000_000_i64, p.as_i64().unwrap());
+    assert!(matches!(p.as_i32(), Err(UnitError::PriceValueOutOfRange(v)) if v == "3000000000.4"));
+
+    let p = get_dummy_price("-2147483648.49");
+    assert_eq!(i32::MIN, p.as_i32().unwrap());
+    assert!(get_dummy_price("100000000000000000000").as_i64().is_err());
 }
 
 #[test]
@@ -64,27 +82,149 @@ fn negative_price() {
     let mut p = get_dummy_price("-0.6");
     units::convert_to_currency_fraction(&mut p);
     assert_eq!(-60_f32, p.as_f32());
-    assert_eq!(-60_i32, p.as_i32());
+    assert_eq!(-60_i32, p.as_i32().unwrap());
 
     units::convert_to_kwh(&mut p);
     assert_eq!(-0.0_f32, p.as_f32());
-    assert_eq!(-0_i32, p.as_i32());
+    assert_eq!(-0_i32, p.as_i32().unwrap());
 
     let mut p = get_dummy_price("-30");
     units::convert_to_kwh(&mut p);
     assert_eq!(-0.03_f32, p.as_f32());
-    assert_eq!(-0_i32, p.as_i32());
+    assert_eq!(-0_i32, p.as_i32().unwrap());
 
     units::convert_to_currency_fraction(&mut p);
     assert_eq!(-3_f32, p.as_f32());
-    assert_eq!(-3_i32, p.as_i32());
+    assert_eq!(-3_i32, p.as_i32().unwrap());
 
 
     let p = get_dummy_price("-10.5");
     assert_eq!(-10.5_f32, p.as_f32());
-    assert_eq!(-11_i32, p.as_i32());
+    assert_eq!(-11_i32, p.as_i32().unwrap());
 
     let p = get_dummy_price("-10.49");
     assert_eq!(-10.49_f32, p.as_f32());
-    assert_eq!(-10_i32, p.as_i32());
-}
\ No newline at end of file
+    assert_eq!(-10_i32, p.as_i32().unwrap());
+}
+#[test]
+fn invalid_price() {
+    assert!(matches!("1.234,56".parse::<PriceValue>(), Err(UnitError::InvalidPriceValue(_))));
+    assert!(matches!("12a".parse::<PriceValue>(), Err(UnitError::InvalidPriceValue(_))));
+
+    let p = get_dummy_price("1 234,56");
+    assert_eq!(1234.56_f32, p.as_f32());
+    assert_eq!("NOK 1234,56 Kr./MWh", p.price_label());
+
+    let p = get_dummy_price("1.5");
+    assert!(matches!(p.from_to_with_region("XX"), Err(RegionError::RegionTzNotSupported(_))));
+}
+
+#[test]
+fn rounding_modes() {
+    let half_even = Rounding::new(2).mode(RoundingMode::HalfEven);
+    let truncate = Rounding::new(2).mode(RoundingMode::TowardZero);
+
+    let p = get_dummy_price("10.505");
+    assert_eq!(10.51_f32, p.as_f32_with(Rounding::new(2)));
+    assert_eq!(10.5_f32, p.as_f32_with(half_even));
+    assert_eq!(10.5_f64, p.as_f64_with(truncate));
+    assert_eq!("NOK 10,5 Kr./MWh", p.price_label_with(half_even));
+
+    let p = get_dummy_price("10.515");
+    assert_eq!(10.52_f32, p.as_f32_with(half_even));
+    assert_eq!(10.51_f32, p.as_f32_with(truncate));
+
+    let p = get_dummy_price("-10.5");
+    assert_eq!(-11_i32, p.as_i32().unwrap());
+    assert_eq!(-10_i32, p.as_i32_with(Rounding::new(0).mode(RoundingMode::HalfEven)).unwrap());
+    assert_eq!(-10_i64, p.as_i64_with(Rounding::new(0).mode(RoundingMode::TowardZero)).unwrap());
+
+    // Øre per kWh usually needs more decimals than the default (whole numbers for fraction units).
+    let mut p = get_dummy_price("167.6849");
+    units::convert_to_currency_fraction(&mut p);
+    units::convert_to_kwh(&mut p);
+    assert_eq!("16.76849", p.value);
+    assert_eq!(17_f64, p.as_f64());
+    assert_eq!(16.768_f64, p.as_f64_with(Rounding::new(3)));
+    assert_eq!(16.7685_f64, p.as_f64_with(Rounding::new(4)));
+    assert_eq!(16.76849_f64, p.as_f64_with(Rounding::new(5)));
+    assert_eq!("NOK 16,7685 Øre/kWh", p.price_label_with(Rounding::new(4)));
+    assert_eq!("NOK 16,7684 Øre/kWh", p.price_label_with(Rounding::new(4).mode(RoundingMode::TowardZero)));
+
+    // By default fraction units are rounded to 2 decimals before they are rounded to a whole number.
+    let mut p = get_dummy_price("10.50495");
+    units::convert_to_currency_fraction(&mut p);
+    assert_eq!("1050.495", p.value);
+    assert_eq!("1051", p.rounded());
+    assert_eq!(1051_i32, p.as_i32().unwrap());
+    assert_eq!(1051_f64, p.as_f64());
+    assert_eq!(1050_i32, p.as_i32_with(Rounding::new(0)).unwrap());
+    assert_eq!("1050", p.rounded_with(Rounding::new(0)));
+    assert_eq!("1050", p.rounded_with(Rounding::default().mode(RoundingMode::TowardZero)));
+}
+
+// Simple xorshift, so that the property tests below are reproducible without extra dependencies.
+fn next_random(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+#[test]
+fn default_rounding_is_unchanged() {
+    let mut state = 0x2545_F491_4F6C_DD1D;
+    for _ in 0..5_000 {
+        let mantissa = (next_random(&mut state) % 2_000_000) as i64 - 1_000_000;
+        let scale = next_random(&mut state) % 5;
+        let mut p = get_dummy_price(&PriceValue::new(mantissa.into(), scale as u32).to_string());
+        if next_random(&mut state).is_multiple_of(2) {
+            units::convert_to_currency_fraction(&mut p);
+        }
+        let decimals = if p.currency_unit.is_fraction() { 0 } else { 2 };
+        let explicit = Rounding::new(decimals).mode(RoundingMode::HalfAwayFromZero);
+        // The default rounds to 2 decimals first, also for fraction units.
+        let mut two_decimals = p.clone();
+        two_decimals.value = p.value.round_with(2, RoundingMode::HalfAwayFromZero);
+
+        assert_eq!(p.as_f32(), p.as_f32_with(Rounding::default()));
+        assert_eq!(p.as_f64(), two_decimals.as_f64_with(explicit));
+        assert_eq!(p.as_i32().unwrap(), two_decimals.as_i32_with(explicit).unwrap());
+        assert_eq!(p.as_i64().unwrap(), p.as_i64_with(Rounding::default()).unwrap());
+        assert_eq!(p.price_label(), two_decimals.price_label_with(explicit));
+        // Same expectation as the float based rounding in `converting_price` and `negative_price`.
+        assert_eq!(p.as_i64().unwrap(), p.as_f64().round() as i64);
+        assert!((p.as_f64() - two_decimals.value.to_f64()).abs() <= 0.5 * 10_f64.powi(-(decimals as i32)) + 1e-9);
+    }
+}
+
+#[test]
+fn rounding_modes_properties() {
+    let mut state = 0x9E37_79B9_7F4A_7C15;
+    for _ in 0..5_000 {
+        let mantissa = (next_random(&mut state) % 2_000_000) as i64 - 1_000_000;
+        let v = PriceValue::new(mantissa.into(), 4);
+        let decimals = (next_random(&mut state) % 5) as u32;
+        let unit = PriceValue::new(1, decimals);
+
+        let away = v.round_with(decimals, RoundingMode::HalfAwayFromZero);
+        let even = v.round_with(decimals, RoundingMode::HalfEven);
+        let truncated = v.round_with(decimals, RoundingMode::TowardZero);
+
+        for r in [away, even, truncated] {
+            assert!(r.scale() <= decimals);
+            assert!(v.checked_sub(r).unwrap() < unit && r.checked_sub(v).unwrap() < unit);
+        }
+        // Truncation never moves away from zero.
+        assert!(truncated.checked_mul(truncated).unwrap() <= v.checked_mul(v).unwrap());
+        // Half even and half away only differ on ties, and then by one unit.
+        if away != even {
+            let minus_unit = PriceValue::ZERO.checked_sub(unit).unwrap();
+            let twice_rest = v.checked_sub(truncated).unwrap().checked_mul(PriceValue::from(2)).unwrap();
+            assert!(twice_rest == unit || twice_rest == minus_unit);
+
+            let diff = away.checked_sub(even).unwrap();
+            assert!(diff == unit || diff == minus_unit);
+        }
+    }
+}